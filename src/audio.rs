@@ -0,0 +1,497 @@
+use crate::{
+    durations::{Duration, Tempo},
+    error::{AudioError, ResonataError},
+    melodies::Melody,
+    nope,
+    notes::*,
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::f64::consts::TAU;
+
+pub use temperament::{Temperament, TuningSystem};
+
+mod temperament;
+mod tests;
+mod utils;
+mod wav;
+
+type Result<T> = std::result::Result<T, ResonataError>;
+
+/// A mono buffer of audio samples between -1 and 1, at a sample rate in Hz.
+///
+/// Buffers are written to and read from 16-bit PCM WAV files.
+///
+/// ### Examples
+/// ```
+/// use resonata::audio::*;
+///
+/// let mut buffer = Buffer::from_samples(8000, vec![0.5; 4000]).unwrap();
+/// assert_eq!(buffer.seconds(), 0.5);
+///
+/// let echo = Buffer::from_samples(8000, vec![0.25; 4000]).unwrap();
+/// buffer.mix(&echo, 2000).unwrap();
+/// assert_eq!(buffer.len(), 6000);
+/// assert_eq!(buffer.samples()[2000..4000], [0.75; 2000]);
+/// assert_eq!(buffer.peak(), 0.75);
+///
+/// let mut wav = Vec::new();
+/// buffer.write_wav(&mut wav).unwrap();
+/// assert_eq!(wav.len(), 44 + 2 * 6000);
+/// let read = Buffer::read_wav(&wav[..]).unwrap();
+/// assert_eq!(read.sample_rate(), 8000);
+/// assert!((read.samples()[3000] - 0.75).abs() < 1e-4);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Buffer {
+    sample_rate: u32,
+    samples: Vec<f64>,
+}
+
+/// An attack, decay, sustain and release envelope, which shapes the loudness of a note
+/// over time.
+///
+/// The note rises from silence to full loudness over the attack, falls to the sustain
+/// level over the decay, and holds there until it ends. It then fades to silence over
+/// the release, so that it sounds for the release after its duration. Times are in
+/// seconds, and the sustain level is between 0 and 1.
+///
+/// ### Examples
+/// ```
+/// use resonata::audio::*;
+///
+/// let envelope = Envelope::new(0.1, 0.2, 0.5, 0.5).unwrap();
+/// assert_eq!(envelope.level(0.05, 1.0), 0.5);
+/// assert_eq!(envelope.level(0.2, 1.0), 0.75);
+/// assert_eq!(envelope.level(0.6, 1.0), 0.5);
+/// assert_eq!(envelope.level(1.25, 1.0), 0.25);
+/// assert_eq!(envelope.level(1.5, 1.0), 0.0);
+/// assert!(Envelope::new(0.1, 0.2, 1.5, 0.4).is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Envelope {
+    attack: f64,
+    decay: f64,
+    sustain: f64,
+    release: f64,
+}
+
+/// The sound of a synthesizer's notes.
+///
+/// - `Sine` is a pure tone.
+/// - `Sawtooth` and `Square` are bright and hollow, with all harmonics and odd
+///   harmonics. They are not band-limited, so high notes alias.
+/// - `Additive` sums the harmonics of the note with the given amplitudes, from the
+///   fundamental up.
+/// - `Pluck` is a plucked string by the Karplus–Strong algorithm, a burst of noise
+///   in a delay line as long as the period of the note, averaged as it recirculates
+///   so that it decays by itself. It suits an envelope with a full sustain.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Timbre {
+    Sine,
+    Sawtooth,
+    Square,
+    Additive(Vec<f64>),
+    Pluck,
+}
+
+/// An offline synthesizer that renders notes, chords, melodies and ear-training
+/// prompts to audio.
+///
+/// Pitches are tuned by the synthesizer's temperament, and durations take their length
+/// from its tempo. Every note has the synthesizer's timbre and envelope, and is scaled
+/// by its gain, so that notes sounding together add up and may need normalizing.
+/// Rendering is deterministic.
+///
+/// ### Examples
+/// ```
+/// use resonata::{notes::*, durations::*, audio::*, melodies::*};
+///
+/// let synthesizer = Synthesizer::new(22050)
+///     .unwrap()
+///     .with_tempo(Tempo::quarter(120.0).unwrap())
+///     .with_timbre(Timbre::Additive(vec![1.0, 0.5, 0.25]));
+///
+/// let note = synthesizer.render_note(pnote!("A4").unwrap(), Duration::quarter()).unwrap();
+/// assert!((note.seconds() - 0.5 - synthesizer.envelope().release()).abs() < 1e-9);
+///
+/// let melody = melody!("C4:1/4 E4:1/4 G4:1/2").unwrap();
+/// let buffer = synthesizer.render_melody(&melody).unwrap();
+/// assert!((buffer.seconds() - 2.0 - synthesizer.envelope().release()).abs() < 1e-9);
+///
+/// // Each note rings into the next for its release.
+/// assert!(buffer.peak() > synthesizer.gain() && buffer.peak() < 2.0 * synthesizer.gain());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Synthesizer {
+    sample_rate: u32,
+    temperament: Temperament,
+    tempo: Tempo,
+    timbre: Timbre,
+    envelope: Envelope,
+    gain: f64,
+}
+
+impl Buffer {
+    /// Creates an empty buffer at the sample rate.
+    ///
+    /// ### Failures
+    /// Returns an error if the sample rate is zero.
+    pub fn new(sample_rate: u32) -> Result<Self> {
+        Buffer::from_samples(sample_rate, Vec::new())
+    }
+
+    /// Creates a buffer of the samples at the sample rate.
+    ///
+    /// ### Failures
+    /// Returns an error if the sample rate is zero.
+    pub fn from_samples(sample_rate: u32, samples: Vec<f64>) -> Result<Self> {
+        if sample_rate == 0 {
+            nope!(AudioError::InvalidSampleRate(sample_rate));
+        }
+        Ok(Buffer { sample_rate, samples })
+    }
+
+    /// Returns the sample rate in Hz.
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// Returns the samples.
+    pub fn samples(&self) -> &Vec<f64> {
+        &self.samples
+    }
+
+    /// Returns the number of samples.
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// Returns true if the buffer has no samples.
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Returns the length of the buffer in seconds.
+    pub fn seconds(&self) -> f64 {
+        self.samples.len() as f64 / self.sample_rate as f64
+    }
+
+    /// Returns the largest absolute sample.
+    pub fn peak(&self) -> f64 {
+        self.samples.iter().fold(0.0, |peak, sample| sample.abs().max(peak))
+    }
+
+    /// Adds another buffer into this one, starting at the given sample, lengthening
+    /// this buffer as needed.
+    ///
+    /// ### Failures
+    /// Returns an error if the sample rates differ.
+    pub fn mix(&mut self, other: &Buffer, offset: usize) -> Result<()> {
+        if other.sample_rate != self.sample_rate {
+            nope!(AudioError::SampleRateMismatch(self.sample_rate, other.sample_rate));
+        }
+        if self.samples.len() < offset + other.samples.len() {
+            self.samples.resize(offset + other.samples.len(), 0.0);
+        }
+        for (sample, other) in self.samples[offset..].iter_mut().zip(&other.samples) {
+            *sample += other;
+        }
+        Ok(())
+    }
+
+    /// Appends another buffer to the end of this one.
+    ///
+    /// ### Failures
+    /// Returns an error if the sample rates differ.
+    pub fn append(&mut self, other: &Buffer) -> Result<()> {
+        self.mix(other, self.samples.len())
+    }
+
+    /// Returns the buffer scaled so that its peak is the given level, or unchanged if
+    /// it is silent.
+    pub fn normalized(&self, peak: f64) -> Self {
+        let scale = match self.peak() {
+            0.0 => 1.0,
+            current => peak / current,
+        };
+        let samples = self.samples.iter().map(|sample| sample * scale).collect();
+        Buffer { sample_rate: self.sample_rate, samples }
+    }
+
+    /// Returns the samples as 16-bit PCM, clipping those outside -1 to 1.
+    pub fn to_pcm16(&self) -> Vec<i16> {
+        self.samples
+            .iter()
+            .map(|sample| (sample.clamp(-1.0, 1.0) * i16::MAX as f64).round() as i16)
+            .collect()
+    }
+}
+
+impl Envelope {
+    /// Creates an envelope from its attack, decay and release in seconds and its
+    /// sustain level.
+    ///
+    /// ### Failures
+    /// Returns an error if a time is negative or not finite, or the sustain level is not
+    /// between 0 and 1.
+    pub fn new(attack: f64, decay: f64, sustain: f64, release: f64) -> Result<Self> {
+        let times = [attack, decay, release];
+        if times.iter().any(|time| !time.is_finite() || *time < 0.0)
+            || !(0.0..=1.0).contains(&sustain)
+        {
+            nope!(AudioError::InvalidEnvelope);
+        }
+        Ok(Envelope { attack, decay, sustain, release })
+    }
+
+    /// Returns the attack in seconds.
+    pub fn attack(&self) -> f64 {
+        self.attack
+    }
+
+    /// Returns the decay in seconds.
+    pub fn decay(&self) -> f64 {
+        self.decay
+    }
+
+    /// Returns the sustain level.
+    pub fn sustain(&self) -> f64 {
+        self.sustain
+    }
+
+    /// Returns the release in seconds.
+    pub fn release(&self) -> f64 {
+        self.release
+    }
+
+    /// Returns the level of a note held for the given number of seconds, at the given
+    /// time from its start.
+    pub fn level(&self, time: f64, held: f64) -> f64 {
+        let on = |time: f64| {
+            if time < self.attack {
+                time / self.attack
+            } else if time < self.attack + self.decay {
+                1.0 - (1.0 - self.sustain) * (time - self.attack) / self.decay
+            } else {
+                self.sustain
+            }
+        };
+        match time < held {
+            true => on(time),
+            false if self.release == 0.0 => 0.0,
+            false => on(held) * (1.0 - (time - held) / self.release).max(0.0),
+        }
+    }
+}
+
+impl Synthesizer {
+    /// Creates a synthesizer at the sample rate, in equal temperament with A4 at 440
+    /// Hz, at 120 quarter notes a minute, with a sine timbre, the default envelope and
+    /// a gain of one quarter.
+    ///
+    /// ### Failures
+    /// Returns an error if the sample rate is zero.
+    pub fn new(sample_rate: u32) -> Result<Self> {
+        if sample_rate == 0 {
+            nope!(AudioError::InvalidSampleRate(sample_rate));
+        }
+        Ok(Synthesizer {
+            sample_rate,
+            temperament: Temperament::equal(),
+            tempo: Tempo::quarter(120.0).unwrap(),
+            timbre: Timbre::Sine,
+            envelope: Envelope::default(),
+            gain: 0.25,
+        })
+    }
+
+    /// Sets the temperament of pitches.
+    pub fn with_temperament(mut self, temperament: Temperament) -> Self {
+        self.temperament = temperament;
+        self
+    }
+
+    /// Sets the tempo durations are played at.
+    pub fn with_tempo(mut self, tempo: Tempo) -> Self {
+        self.tempo = tempo;
+        self
+    }
+
+    /// Sets the timbre of notes.
+    pub fn with_timbre(mut self, timbre: Timbre) -> Self {
+        self.timbre = timbre;
+        self
+    }
+
+    /// Sets the envelope of notes.
+    pub fn with_envelope(mut self, envelope: Envelope) -> Self {
+        self.envelope = envelope;
+        self
+    }
+
+    /// Sets the peak level of a single note.
+    ///
+    /// ### Failures
+    /// Returns an error if the gain is not a finite, positive number.
+    pub fn with_gain(mut self, gain: f64) -> Result<Self> {
+        if gain <= 0.0 || !gain.is_finite() {
+            nope!(AudioError::InvalidGain(gain.to_string()));
+        }
+        self.gain = gain;
+        Ok(self)
+    }
+
+    /// Returns the sample rate in Hz.
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// Returns the temperament.
+    pub fn temperament(&self) -> &Temperament {
+        &self.temperament
+    }
+
+    /// Returns the tempo.
+    pub fn tempo(&self) -> Tempo {
+        self.tempo
+    }
+
+    /// Returns the timbre.
+    pub fn timbre(&self) -> &Timbre {
+        &self.timbre
+    }
+
+    /// Returns the envelope.
+    pub fn envelope(&self) -> Envelope {
+        self.envelope
+    }
+
+    /// Returns the gain.
+    pub fn gain(&self) -> f64 {
+        self.gain
+    }
+
+    /// Renders a note held for the duration, followed by its release.
+    ///
+    /// ### Failures
+    /// Returns an error if the sound is too long to hold in memory.
+    pub fn render_note(&self, pitch: PitchedNote, duration: Duration) -> Result<Buffer> {
+        self.render_chord(&[pitch], duration)
+    }
+
+    /// Renders notes sounding together for the duration, followed by their release.
+    ///
+    /// ### Failures
+    /// Returns an error if the sound is too long to hold in memory.
+    pub fn render_chord(&self, pitches: &[PitchedNote], duration: Duration) -> Result<Buffer> {
+        self.render(&[(pitches.to_vec(), duration)])
+    }
+
+    /// Renders a melody, with silence for its rests.
+    ///
+    /// ### Failures
+    /// Returns an error if the sound is too long to hold in memory.
+    pub fn render_melody(&self, melody: &Melody) -> Result<Buffer> {
+        let sonorities = melody
+            .events()
+            .iter()
+            .map(|event| (event.pitch().into_iter().collect(), event.duration()))
+            .collect::<Vec<_>>();
+        self.render(&sonorities)
+    }
+
+    /// Renders groups of notes sounding together for their durations, one after the
+    /// other, such as the prompt of an ear-training exercise. Each note sounds into the
+    /// next group for its release.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::{audio::*, durations::*, exercises::*};
+    ///
+    /// let exercise = ExerciseGenerator::new(Difficulty::Beginner).generate(ExerciseKind::Cadence, 0).unwrap();
+    /// let synthesizer = Synthesizer::new(8000).unwrap().with_timbre(Timbre::Pluck);
+    /// let buffer = synthesizer.render(exercise.prompt()).unwrap().normalized(0.9);
+    /// assert!((buffer.peak() - 0.9).abs() < 1e-9);
+    ///
+    /// let slow = synthesizer.with_tempo(Tempo::quarter(1e-300).unwrap());
+    /// assert!(slow.render(exercise.prompt()).is_err());
+    /// ```
+    ///
+    /// ### Failures
+    /// Returns an error if the sound is too long to hold in memory.
+    pub fn render(&self, sonorities: &[(Vec<PitchedNote>, Duration)]) -> Result<Buffer> {
+        let seconds =
+            sonorities.iter().map(|(_, duration)| duration.to_seconds(&self.tempo)).sum::<f64>()
+                + self.envelope.release;
+        let samples = seconds * self.sample_rate as f64;
+        if !samples.is_finite() || samples > (isize::MAX as usize / size_of::<f64>()) as f64 {
+            nope!(AudioError::InvalidLength(seconds.to_string()));
+        }
+
+        let mut buffer = Buffer { sample_rate: self.sample_rate, samples: Vec::new() };
+        let mut position = 0.0;
+        for (pitches, duration) in sonorities {
+            let seconds = duration.to_seconds(&self.tempo);
+            let offset = (position * self.sample_rate as f64).round() as usize;
+            for pitch in pitches {
+                let tone =
+                    Buffer { sample_rate: self.sample_rate, samples: self.tone(*pitch, seconds) };
+                // Both buffers are at the synthesizer's sample rate.
+                buffer.mix(&tone, offset).unwrap();
+            }
+            position += seconds;
+        }
+        let end = ((position + self.envelope.release) * self.sample_rate as f64).round() as usize;
+        buffer.samples.resize(buffer.samples.len().max(end), 0.0);
+        Ok(buffer)
+    }
+
+    /// Returns the samples of a note held for the given number of seconds, followed by
+    /// its release.
+    fn tone(&self, pitch: PitchedNote, held: f64) -> Vec<f64> {
+        let rate = self.sample_rate as f64;
+        let frequency = self.temperament.frequency(pitch);
+        let length = ((held + self.envelope.release) * rate).round() as usize;
+        let wave = |shape: &dyn Fn(f64) -> f64| -> Vec<f64> {
+            (0..length).map(|i| shape((frequency * i as f64 / rate).fract())).collect()
+        };
+        let mut samples = match &self.timbre {
+            Timbre::Sine => wave(&|phase| (TAU * phase).sin()),
+            Timbre::Sawtooth => wave(&|phase| 2.0 * phase - 1.0),
+            Timbre::Square => wave(&|phase| if phase < 0.5 { 1.0 } else { -1.0 }),
+            Timbre::Additive(amplitudes) => {
+                let total = amplitudes.iter().map(|a| a.abs()).sum::<f64>();
+                wave(&|phase| {
+                    let sum = amplitudes.iter().enumerate().map(|(harmonic, amplitude)| {
+                        amplitude * (TAU * phase * (harmonic + 1) as f64).sin()
+                    });
+                    match total {
+                        0.0 => 0.0,
+                        total => sum.sum::<f64>() / total,
+                    }
+                })
+            }
+            Timbre::Pluck => pluck(frequency, rate, length),
+        };
+        for (i, sample) in samples.iter_mut().enumerate() {
+            *sample *= self.gain * self.envelope.level(i as f64 / rate, held);
+        }
+        samples
+    }
+}
+
+/// Returns the samples of a string plucked at the frequency, by the Karplus–Strong
+/// algorithm. The noise is seeded by the frequency, so the same note always sounds
+/// the same.
+fn pluck(frequency: f64, rate: f64, length: usize) -> Vec<f64> {
+    let period = ((rate / frequency).round() as usize).max(2);
+    let mut rng = StdRng::seed_from_u64(frequency.to_bits());
+    let mut line = (0..period).map(|_| rng.gen_range(-1.0..1.0)).collect::<Vec<f64>>();
+    let mut samples = Vec::with_capacity(length);
+    for i in 0..length {
+        let (current, next) = (i % period, (i + 1) % period);
+        samples.push(line[current]);
+        line[current] = 0.996 * (line[current] + line[next]) / 2.0;
+    }
+    samples
+}
@@ -0,0 +1,158 @@
+use super::*;
+
+/// The frequency ratios of just intonation above the tonic, by semitones.
+const JUST_RATIOS: [f64; 12] = [
+    1.0,
+    16.0 / 15.0,
+    9.0 / 8.0,
+    6.0 / 5.0,
+    5.0 / 4.0,
+    4.0 / 3.0,
+    45.0 / 32.0,
+    3.0 / 2.0,
+    8.0 / 5.0,
+    5.0 / 3.0,
+    9.0 / 5.0,
+    15.0 / 8.0,
+];
+
+/// A system for tuning the twelve notes of the octave.
+///
+/// - `Equal` divides the octave into twelve equal semitones.
+/// - `Just` tunes each note to a ratio of small whole numbers above the tonic.
+/// - `Pythagorean` tunes each note by pure fifths from the tonic.
+/// - `Meantone` tunes each note by quarter-comma meantone fifths from the tonic, a
+///   little narrow so that four of them make a pure major third.
+///
+/// Pythagorean and meantone tunings follow the spelling of notes, so that G♯ and A♭
+/// are different pitches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TuningSystem {
+    Equal,
+    Just,
+    Pythagorean,
+    Meantone,
+}
+
+/// A temperament, which gives the frequency of every pitch: a tuning system, the tonic
+/// it is built on, and the frequency of A4 in equal temperament, to which the tonic is
+/// tuned.
+///
+/// ### Examples
+/// ```
+/// use resonata::{notes::*, audio::*};
+///
+/// let equal = Temperament::equal();
+/// assert_eq!(equal.frequency(pnote!("A4").unwrap()), 440.0);
+/// assert!((equal.frequency(pnote!("C4").unwrap()) - 261.626).abs() < 1e-3);
+///
+/// let just = Temperament::just(note!("C").unwrap());
+/// assert_eq!(just.frequency(pnote!("C4").unwrap()), equal.frequency(pnote!("C4").unwrap()));
+/// assert!((just.cents(pnote!("E4").unwrap()) + 13.686).abs() < 1e-3);
+///
+/// let pythagorean = Temperament::pythagorean(note!("C").unwrap());
+/// assert!(pythagorean.frequency(pnote!("G#4").unwrap()) > pythagorean.frequency(pnote!("Ab4").unwrap()));
+///
+/// let baroque = Temperament::equal().with_reference(415.0).unwrap();
+/// assert_eq!(baroque.frequency(pnote!("A3").unwrap()), 207.5);
+/// assert!(Temperament::equal().with_reference(0.0).is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Temperament {
+    system: TuningSystem,
+    tonic: Note,
+    reference: f64,
+}
+
+impl Temperament {
+    /// Creates a temperament in the given system, built on the tonic, with A4 at 440 Hz.
+    pub fn new(system: TuningSystem, tonic: Note) -> Self {
+        Temperament { system, tonic, reference: 440.0 }
+    }
+
+    /// Creates an equal temperament with A4 at 440 Hz.
+    pub fn equal() -> Self {
+        Temperament::new(TuningSystem::Equal, Note::from_note_name(NoteName::C))
+    }
+
+    /// Creates a just temperament built on the tonic.
+    pub fn just(tonic: Note) -> Self {
+        Temperament::new(TuningSystem::Just, tonic)
+    }
+
+    /// Creates a Pythagorean temperament built on the tonic.
+    pub fn pythagorean(tonic: Note) -> Self {
+        Temperament::new(TuningSystem::Pythagorean, tonic)
+    }
+
+    /// Creates a quarter-comma meantone temperament built on the tonic.
+    pub fn meantone(tonic: Note) -> Self {
+        Temperament::new(TuningSystem::Meantone, tonic)
+    }
+
+    /// Sets the frequency of A4 in equal temperament, in Hz.
+    ///
+    /// ### Failures
+    /// Returns an error if the frequency is not a finite, positive number.
+    pub fn with_reference(mut self, frequency: f64) -> Result<Self> {
+        if frequency <= 0.0 || !frequency.is_finite() {
+            nope!(AudioError::InvalidReference(frequency.to_string()));
+        }
+        self.reference = frequency;
+        Ok(self)
+    }
+
+    /// Returns the tuning system.
+    pub fn system(&self) -> TuningSystem {
+        self.system
+    }
+
+    /// Returns the tonic the temperament is built on.
+    pub fn tonic(&self) -> Note {
+        self.tonic
+    }
+
+    /// Returns the frequency of A4 in equal temperament, in Hz.
+    pub fn reference(&self) -> f64 {
+        self.reference
+    }
+
+    /// Returns the frequency of the pitch in Hz. The tonic is tuned as in equal
+    /// temperament, and every other note to its ratio above the tonic, in the octave
+    /// nearest to its equal tempered pitch.
+    pub fn frequency(&self, pitch: PitchedNote) -> f64 {
+        let equal = |semitones: f64| self.reference * 2f64.powf((semitones - 69.0) / 12.0);
+        let frequency = equal(pitch.to_midi_number() as f64);
+        let note = Note::from(pitch);
+        let ratio = match self.system {
+            TuningSystem::Equal => return frequency,
+            TuningSystem::Just => {
+                JUST_RATIOS[self.tonic.semitones_to(&note).rem_euclid(12) as usize]
+            }
+            TuningSystem::Pythagorean => 1.5f64.powi(fifths(note) - fifths(self.tonic)),
+            TuningSystem::Meantone => 5f64.powf(0.25).powi(fifths(note) - fifths(self.tonic)),
+        };
+        let tuned = equal(60.0 + self.tonic.to_chromatic_scale_degree() as f64) * ratio;
+        tuned * 2f64.powf((frequency / tuned).log2().round())
+    }
+
+    /// Returns how far the pitch is from its equal tempered pitch, in cents.
+    pub fn cents(&self, pitch: PitchedNote) -> f64 {
+        let equal = Temperament { reference: self.reference, ..Temperament::equal() };
+        1200.0 * (self.frequency(pitch) / equal.frequency(pitch)).log2()
+    }
+}
+
+/// Returns the position of the note on the line of fifths, counting from C.
+fn fifths(note: Note) -> i32 {
+    let natural = match note.name() {
+        NoteName::F => -1,
+        NoteName::C => 0,
+        NoteName::G => 1,
+        NoteName::D => 2,
+        NoteName::A => 3,
+        NoteName::E => 4,
+        NoteName::B => 5,
+    };
+    natural + 7 * note.accidental().to_semitones()
+}
@@ -0,0 +1,182 @@
+#[cfg(test)]
+mod tests {
+    use super::super::*;
+    use crate::{durations::Event, note, pnote};
+
+    fn pitch(s: &str) -> PitchedNote {
+        s.parse().unwrap()
+    }
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn test_temperament() {
+        let equal = Temperament::equal();
+        assert!(close(equal.frequency(pitch("C5")), 2.0 * equal.frequency(pitch("C4"))));
+        assert!(close(equal.frequency(pitch("G#4")), equal.frequency(pitch("Ab4"))));
+        assert!(close(equal.cents(pitch("B#3")), 0.0));
+
+        let d = note!("D").unwrap();
+        let just = Temperament::just(d);
+        let ratio = |temperament: &Temperament, low: &str, high: &str| {
+            temperament.frequency(pitch(high)) / temperament.frequency(pitch(low))
+        };
+        assert!(close(just.frequency(pitch("D4")), equal.frequency(pitch("D4"))));
+        assert!(close(ratio(&just, "D4", "F#4"), 5.0 / 4.0));
+        assert!(close(ratio(&just, "D4", "G4"), 4.0 / 3.0));
+        assert!(close(ratio(&just, "D3", "C#5"), 15.0 / 4.0));
+        assert!(close(ratio(&just, "D4", "D6"), 4.0));
+
+        let pythagorean = Temperament::pythagorean(note!("C").unwrap());
+        assert!(close(ratio(&pythagorean, "C4", "G4"), 1.5));
+        assert!(close(ratio(&pythagorean, "F3", "C4"), 1.5));
+        assert!(close(ratio(&pythagorean, "Ab4", "G#4"), 531441.0 / 524288.0));
+
+        // Meantone thirds are pure, and its sharps are lower than its flats.
+        let meantone = Temperament::meantone(note!("C").unwrap());
+        assert!(close(ratio(&meantone, "C4", "E4"), 1.25));
+        assert!(close(ratio(&meantone, "E4", "G#4"), 1.25));
+        assert!(meantone.frequency(pitch("G#4")) < meantone.frequency(pitch("Ab4")));
+
+        let temperament = Temperament::just(d).with_reference(432.0).unwrap();
+        assert!(close(
+            temperament.frequency(pitch("D4")),
+            equal.with_reference(432.0).unwrap().frequency(pitch("D4"))
+        ));
+        assert_eq!(temperament.to_string(), "just intonation on D, A4 = 432 Hz");
+        assert_eq!(equal.to_string(), "equal temperament, A4 = 440 Hz");
+    }
+
+    #[test]
+    fn test_synthesis() {
+        let envelope = Envelope::new(0.0, 0.0, 1.0, 0.0).unwrap();
+        let synthesizer =
+            Synthesizer::new(44100).unwrap().with_envelope(envelope).with_gain(0.5).unwrap();
+
+        // A second of A4 crosses zero twice a cycle.
+        let tone = synthesizer.render_note(pnote!("A4").unwrap(), Duration::half()).unwrap();
+        assert_eq!(tone.len(), 44100);
+        let crossings = tone.samples().windows(2).filter(|pair| (pair[0] < 0.0) != (pair[1] < 0.0));
+        assert!((crossings.count() as i32 - 880).abs() <= 1);
+        assert!((tone.peak() - 0.5).abs() < 1e-3);
+
+        for timbre in [Timbre::Sawtooth, Timbre::Square, Timbre::Additive(vec![1.0, 0.0, 1.0])] {
+            let tone = synthesizer
+                .clone()
+                .with_timbre(timbre)
+                .render_note(pitch("A4"), Duration::half())
+                .unwrap();
+            assert!(tone.peak() <= 0.5 + 1e-9 && tone.peak() > 0.3);
+        }
+        assert_eq!(
+            synthesizer
+                .clone()
+                .with_timbre(Timbre::Square)
+                .render_note(pitch("A4"), Duration::half())
+                .unwrap()
+                .samples()[..3],
+            [0.5; 3]
+        );
+
+        // Notes of a chord add up, and rests are silent.
+        let chord = synthesizer
+            .render_chord(&[pitch("C4"), pitch("E4"), pitch("G4")], Duration::half())
+            .unwrap();
+        assert!(chord.peak() > 1.0);
+        assert!(chord.normalized(1.0).peak() <= 1.0 + 1e-9);
+        let melody = Melody::new(vec![
+            Event::Note(pitch("C4"), Duration::quarter()),
+            Event::Rest(Duration::quarter()),
+            Event::Note(pitch("C4"), Duration::quarter()),
+        ]);
+        let buffer = synthesizer.render_melody(&melody).unwrap();
+        assert_eq!(buffer.len(), 3 * 22050);
+        assert!(buffer.samples()[22050..44100].iter().all(|sample| *sample == 0.0));
+        assert_eq!(buffer.samples()[..22050], buffer.samples()[44100..]);
+
+        // Tunings sound different, and plucked strings decay.
+        let just = synthesizer.clone().with_temperament(Temperament::just(note!("C").unwrap()));
+        assert_ne!(
+            just.render_note(pitch("E4"), Duration::half()).unwrap(),
+            synthesizer.render_note(pitch("E4"), Duration::half()).unwrap()
+        );
+        let pluck = synthesizer.clone().with_timbre(Timbre::Pluck);
+        let tone = pluck.render_note(pitch("E2"), Duration::whole()).unwrap();
+        let energy = |samples: &[f64]| samples.iter().map(|s| s * s).sum::<f64>();
+        assert!(
+            energy(&tone.samples()[..4410]) > 10.0 * energy(&tone.samples()[tone.len() - 4410..])
+        );
+        assert_eq!(pluck.render_note(pitch("E2"), Duration::whole()).unwrap(), tone);
+
+        // The envelope releases after the note.
+        let envelope = Envelope::new(0.1, 0.1, 0.5, 0.25).unwrap();
+        let tone =
+            synthesizer.with_envelope(envelope).render_note(pitch("A4"), Duration::half()).unwrap();
+        assert_eq!(tone.len(), 44100 + 11025);
+        assert_eq!(envelope.level(1.0, 1.0), 0.5);
+        assert_eq!(Envelope::new(-0.1, 0.0, 0.5, 0.0), Err(AudioError::InvalidEnvelope.into()));
+        for time in [f64::INFINITY, f64::NAN] {
+            assert!(Envelope::new(0.0, 0.0, 0.5, time).is_err());
+            assert!(Envelope::new(time, 0.0, 0.5, 0.0).is_err());
+        }
+        assert_eq!(Synthesizer::new(0), Err(AudioError::InvalidSampleRate(0).into()));
+        let synthesizer = Synthesizer::new(44100).unwrap();
+        for value in [0.0, -1.0, f64::INFINITY, f64::NAN] {
+            assert!(synthesizer.clone().with_gain(value).is_err());
+            assert!(Temperament::equal().with_reference(value).is_err());
+        }
+        let slow = synthesizer.with_tempo(Tempo::quarter(f64::MIN_POSITIVE).unwrap());
+        assert!(slow.render_note(pitch("A4"), Duration::new(u32::MAX, 1).unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_wav() {
+        let samples = vec![0.0, 0.5, -0.5, 1.0, -1.0, 2.0];
+        let buffer = Buffer::from_samples(8000, samples).unwrap();
+        assert_eq!(buffer.to_pcm16(), [0, 16384, -16384, 32767, -32767, 32767]);
+
+        let mut wav = Vec::new();
+        buffer.write_wav(&mut wav).unwrap();
+        assert_eq!(&wav[..4], b"RIFF");
+        assert_eq!(&wav[8..16], b"WAVEfmt ");
+        assert_eq!(&wav[36..40], b"data");
+        assert_eq!(u32::from_le_bytes(wav[4..8].try_into().unwrap()), 36 + 12);
+        let read = Buffer::read_wav(&wav[..]).unwrap();
+        assert_eq!(read.len(), 6);
+        assert!(read
+            .samples()
+            .iter()
+            .zip([0.0, 0.5, -0.5, 1.0, -1.0, 1.0])
+            .all(|(a, b)| (a - b).abs() < 1e-4));
+
+        // Stereo files are mixed down to mono.
+        let mut stereo = wav.clone();
+        stereo[22] = 2;
+        assert_eq!(Buffer::read_wav(&stereo[..]).unwrap().len(), 3);
+        let mut eight_bit = wav.clone();
+        eight_bit[34] = 8;
+        assert!(matches!(
+            Buffer::read_wav(&eight_bit[..]),
+            Err(ResonataError::AudioError(AudioError::InvalidWav(_)))
+        ));
+        assert!(Buffer::read_wav(&b"RIFF"[..]).is_err());
+
+        let fast = Buffer::from_samples(u32::MAX, vec![0.0]).unwrap();
+        let invalid = Err(AudioError::InvalidSampleRate(u32::MAX).into());
+        assert_eq!(fast.write_wav(&mut Vec::new()), invalid);
+
+        let path = std::env::temp_dir().join("resonata_test_wav.wav");
+        buffer.save_wav(&path).unwrap();
+        assert_eq!(Buffer::load_wav(&path).unwrap(), read);
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(
+            Buffer::load_wav(&path),
+            Err(ResonataError::AudioError(AudioError::Io(_)))
+        ));
+
+        let mut other = Buffer::new(44100).unwrap();
+        assert_eq!(other.append(&buffer), Err(AudioError::SampleRateMismatch(44100, 8000).into()));
+    }
+}
@@ -0,0 +1,31 @@
+use super::*;
+use std::fmt::{self, Display, Formatter};
+
+impl Default for Envelope {
+    /// An envelope with a quick attack, a short decay to a sustain level of 0.7, and a
+    /// tenth of a second of release.
+    fn default() -> Self {
+        Envelope { attack: 0.01, decay: 0.1, sustain: 0.7, release: 0.1 }
+    }
+}
+
+impl Display for TuningSystem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            TuningSystem::Equal => "equal temperament",
+            TuningSystem::Just => "just intonation",
+            TuningSystem::Pythagorean => "Pythagorean tuning",
+            TuningSystem::Meantone => "quarter-comma meantone",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl Display for Temperament {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.system() {
+            TuningSystem::Equal => write!(f, "{}, A4 = {} Hz", self.system(), self.reference()),
+            system => write!(f, "{} on {}, A4 = {} Hz", system, self.tonic(), self.reference()),
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use super::*;
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+impl Buffer {
+    /// Writes the buffer as a mono 16-bit PCM WAV file.
+    ///
+    /// ### Failures
+    /// Returns an error if the sample rate or the length of the buffer is too large for
+    /// the sizes in a WAV header, or if the writer fails.
+    pub fn write_wav<W: Write>(&self, mut writer: W) -> Result<()> {
+        let data = self.to_pcm16();
+        let byte_rate = self
+            .sample_rate
+            .checked_mul(2)
+            .ok_or(AudioError::InvalidSampleRate(self.sample_rate))?;
+        let size = u32::try_from(data.len())
+            .ok()
+            .and_then(|len| len.checked_mul(2))
+            .filter(|size| size.checked_add(36).is_some())
+            .ok_or(AudioError::BufferTooLong(data.len()))?;
+        let mut bytes = Vec::with_capacity(44 + size as usize);
+        bytes.extend(b"RIFF");
+        bytes.extend((36 + size).to_le_bytes());
+        bytes.extend(b"WAVEfmt ");
+        bytes.extend(16u32.to_le_bytes());
+        bytes.extend(1u16.to_le_bytes());
+        bytes.extend(1u16.to_le_bytes());
+        bytes.extend(self.sample_rate.to_le_bytes());
+        bytes.extend(byte_rate.to_le_bytes());
+        bytes.extend(2u16.to_le_bytes());
+        bytes.extend(16u16.to_le_bytes());
+        bytes.extend(b"data");
+        bytes.extend(size.to_le_bytes());
+        for sample in data {
+            bytes.extend(sample.to_le_bytes());
+        }
+        writer.write_all(&bytes).map_err(|e| AudioError::Io(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Reads a 16-bit PCM WAV file, mixing its channels down to mono.
+    ///
+    /// ### Failures
+    /// Returns an error if the reader fails, or if the file is not 16-bit PCM WAV.
+    pub fn read_wav<R: Read>(mut reader: R) -> Result<Self> {
+        let invalid = |reason: &str| AudioError::InvalidWav(reason.to_string());
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).map_err(|e| AudioError::Io(e.to_string()))?;
+        if bytes.len() < 12 || &bytes[..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+            nope!(invalid("not a RIFF WAVE file"));
+        }
+        let u16_at = |i: usize| u16::from_le_bytes([bytes[i], bytes[i + 1]]);
+        let u32_at =
+            |i: usize| u32::from_le_bytes([bytes[i], bytes[i + 1], bytes[i + 2], bytes[i + 3]]);
+
+        let mut format = None;
+        let mut position = 12;
+        while position + 8 <= bytes.len() {
+            let (id, size) = (&bytes[position..position + 4], u32_at(position + 4) as usize);
+            let body = position + 8;
+            let end = (body + size).min(bytes.len());
+            match id {
+                b"fmt " if size >= 16 && end - body >= 16 => {
+                    format =
+                        Some((u16_at(body), u16_at(body + 2), u32_at(body + 4), u16_at(body + 14)));
+                }
+                b"data" => {
+                    let Some((encoding, channels, sample_rate, bits)) = format else {
+                        nope!(invalid("data before format"));
+                    };
+                    if encoding != 1 || bits != 16 || channels == 0 {
+                        nope!(invalid("not 16-bit PCM"));
+                    }
+                    let samples = bytes[body..end]
+                        .chunks_exact(2 * channels as usize)
+                        .map(|frame| {
+                            let total = frame
+                                .chunks_exact(2)
+                                .map(|sample| i16::from_le_bytes([sample[0], sample[1]]) as f64)
+                                .sum::<f64>();
+                            total / channels as f64 / i16::MAX as f64
+                        })
+                        .collect();
+                    return Buffer::from_samples(sample_rate, samples);
+                }
+                _ => {}
+            }
+            // Chunks are padded to an even length.
+            position = body + size + size % 2;
+        }
+        nope!(invalid("no data"))
+    }
+
+    /// Saves the buffer to a mono 16-bit PCM WAV file at the path.
+    ///
+    /// ### Failures
+    /// Returns an error if the buffer cannot be written as a WAV file, or if the file
+    /// cannot be written.
+    pub fn save_wav<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let file = File::create(path).map_err(|e| AudioError::Io(e.to_string()))?;
+        let mut writer = BufWriter::new(file);
+        self.write_wav(&mut writer)?;
+        writer.flush().map_err(|e| AudioError::Io(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Loads a 16-bit PCM WAV file from the path, mixing its channels down to mono.
+    ///
+    /// ### Failures
+    /// Returns an error if the file cannot be read, or is not 16-bit PCM WAV.
+    pub fn load_wav<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let file = File::open(path).map_err(|e| AudioError::Io(e.to_string()))?;
+        Buffer::read_wav(BufReader::new(file))
+    }
+}
@@ -0,0 +1,295 @@
+use crate::{
+    error::{ChordError, ResonataError},
+    intervals::{Interval, Size},
+    nope,
+    notes::*,
+    scales::ScaleDegree,
+};
+
+pub use crate::chord;
+pub use chord_scales::{ChordFunction, ChordScale};
+pub use figured_bass::{Figure, FiguredBass};
+pub use neo_riemannian::{TonnetzPosition, Transformation};
+
+mod chord_scales;
+mod figured_bass;
+mod neo_riemannian;
+mod tests;
+mod utils;
+
+type Result<T> = std::result::Result<T, ResonataError>;
+
+/// The kind of a chord, as named by the suffix of its chord symbol.
+///
+/// ### Examples
+/// ```
+/// use resonata::{chords::*, intervals::*};
+///
+/// assert_eq!(ChordType::Minor7.to_string(), "m7");
+/// assert_eq!(ChordType::HalfDiminished7.intervals(), vec![
+///     inv!("m3").unwrap(),
+///     inv!("d5").unwrap(),
+///     inv!("m7").unwrap(),
+/// ]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChordType {
+    Major,
+    Minor,
+    Diminished,
+    Augmented,
+    Suspended2,
+    Suspended4,
+    Power,
+    Major6,
+    Minor6,
+    SixNine,
+    Dominant7,
+    Major7,
+    Minor7,
+    MinorMajor7,
+    HalfDiminished7,
+    Diminished7,
+    Dominant7Sus4,
+    Dominant9,
+    Major9,
+    Minor9,
+    Dominant11,
+    Minor11,
+    Dominant13,
+    Major13,
+    Minor13,
+}
+
+/// A chord, written as a chord symbol such as "G7", "Cmaj9♯11" or "D♭m7/C♭"
+///
+/// A chord is represented by its root, its type, any alterations or added tones, and
+/// an optional bass note for slash chords. Altered degrees replace the degree of the
+/// same number in the chord type, so G9♯11 has a sharp eleventh and C7♭5 has no
+/// perfect fifth, while natural degrees, written "add9", are added to it.
+/// "alt" stands for ♭5, ♯5, ♭9 and ♯9.
+///
+/// A macro is provided to make creating chords easier:
+/// chord!(string)
+/// chord!(root, type)
+///
+/// ### Examples
+/// ```
+/// use resonata::{notes::*, chords::*};
+///
+/// let chord = chord!("G7b9").unwrap();
+/// assert_eq!(chord.root(), note!("G").unwrap());
+/// assert_eq!(chord.chord_type(), ChordType::Dominant7);
+/// assert_eq!(chord.notes(), ["G", "B", "D", "F", "Ab"].map(|n| n.parse::<Note>().unwrap()));
+///
+/// let chord = chord!("Dbm7/Cb").unwrap();
+/// assert_eq!(chord.bass(), note!("Cb").unwrap());
+/// assert_eq!(chord, chord!("D♭m7/C♭").unwrap());
+///
+/// let chord = chord!(note!("C").unwrap(), ChordType::Major7);
+/// assert_eq!(chord, chord!("CΔ7").unwrap());
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct Chord {
+    root: Note,
+    chord_type: ChordType,
+    alterations: Vec<ScaleDegree>,
+    bass: Option<Note>,
+}
+
+/// A macro to create a chord.
+#[macro_export]
+macro_rules! chord {
+    ($str:literal) => {
+        $str.parse::<Chord>()
+    };
+    ($root:expr, $chord_type:expr) => {
+        Chord::new($root, $chord_type)
+    };
+}
+
+impl ChordType {
+    /// Returns the intervals of the chord type above its root, in ascending order.
+    pub fn intervals(&self) -> Vec<Interval> {
+        use ChordType::*;
+        let intervals: &[&str] = match self {
+            Major => &["M3", "P5"],
+            Minor => &["m3", "P5"],
+            Diminished => &["m3", "d5"],
+            Augmented => &["M3", "A5"],
+            Suspended2 => &["M2", "P5"],
+            Suspended4 => &["P4", "P5"],
+            Power => &["P5"],
+            Major6 => &["M3", "P5", "M6"],
+            Minor6 => &["m3", "P5", "M6"],
+            SixNine => &["M3", "P5", "M6", "M9"],
+            Dominant7 => &["M3", "P5", "m7"],
+            Major7 => &["M3", "P5", "M7"],
+            Minor7 => &["m3", "P5", "m7"],
+            MinorMajor7 => &["m3", "P5", "M7"],
+            HalfDiminished7 => &["m3", "d5", "m7"],
+            Diminished7 => &["m3", "d5", "d7"],
+            Dominant7Sus4 => &["P4", "P5", "m7"],
+            Dominant9 => &["M3", "P5", "m7", "M9"],
+            Major9 => &["M3", "P5", "M7", "M9"],
+            Minor9 => &["m3", "P5", "m7", "M9"],
+            Dominant11 => &["M3", "P5", "m7", "M9", "P11"],
+            Minor11 => &["m3", "P5", "m7", "M9", "P11"],
+            Dominant13 => &["M3", "P5", "m7", "M9", "M13"],
+            Major13 => &["M3", "P5", "M7", "M9", "M13"],
+            Minor13 => &["m3", "P5", "m7", "M9", "P11", "M13"],
+        };
+        intervals.iter().map(|interval| interval.parse().unwrap()).collect()
+    }
+}
+
+impl Chord {
+    /// Creates a chord from a root and a chord type.
+    pub fn new(root: Note, chord_type: ChordType) -> Self {
+        Chord { root, chord_type, alterations: Vec::new(), bass: None }
+    }
+
+    /// Returns the chord with the given altered or added degree. Fifths may be altered,
+    /// ninths, elevenths and thirteenths may be altered or added, and seconds, fourths
+    /// and sixths may be added. An alteration is a single flat or sharp.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::{notes::*, chords::*};
+    ///
+    /// let chord = chord!("C7").unwrap().with_alteration("#11".parse().unwrap()).unwrap();
+    /// assert_eq!(chord, chord!("C7#11").unwrap());
+    /// assert!(chord!("C").unwrap().with_alteration("b3".parse().unwrap()).is_err());
+    /// ```
+    pub fn with_alteration(mut self, degree: ScaleDegree) -> Result<Self> {
+        let valid = degree.accidental().to_semitones().abs() <= 1
+            && match degree.number() {
+                5 => !degree.is_diatonic(),
+                9 | 11 | 13 => true,
+                2 | 4 | 6 => degree.is_diatonic(),
+                _ => false,
+            };
+        if !valid {
+            nope!(ChordError::InvalidChordAlteration(degree.to_string()));
+        }
+        if !self.alterations.contains(&degree) {
+            self.alterations.push(degree);
+            self.alterations.sort_by_key(|degree| (degree.number(), degree.accidental()));
+        }
+        Ok(self)
+    }
+
+    /// Returns the chord over the given bass note.
+    pub fn with_bass(mut self, bass: Note) -> Self {
+        self.bass = if bass == self.root { None } else { Some(bass) };
+        self
+    }
+
+    /// Returns the root of the chord.
+    pub fn root(&self) -> Note {
+        self.root
+    }
+
+    /// Returns the type of the chord.
+    pub fn chord_type(&self) -> ChordType {
+        self.chord_type
+    }
+
+    /// Returns the altered and added degrees of the chord, in ascending order.
+    pub fn alterations(&self) -> &Vec<ScaleDegree> {
+        &self.alterations
+    }
+
+    /// Returns the bass note of the chord, which is the root unless the chord is
+    /// written over another note.
+    pub fn bass(&self) -> Note {
+        self.bass.unwrap_or(self.root)
+    }
+
+    /// Returns the intervals of the chord above its root, in ascending order.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::{chords::*, intervals::*};
+    ///
+    /// let intervals = chord!("C7b5#9").unwrap().intervals();
+    /// assert_eq!(intervals, ["M3", "d5", "m7", "A9"].map(|i| i.parse::<Interval>().unwrap()));
+    /// ```
+    pub fn intervals(&self) -> Vec<Interval> {
+        let mut intervals = self.chord_type.intervals();
+        intervals.retain(|interval| {
+            !self.alterations.iter().any(|degree| {
+                !degree.is_diatonic() && degree_of_interval(interval) == degree.number()
+            })
+        });
+        for degree in &self.alterations {
+            let interval = degree_interval(*degree);
+            if !intervals.iter().any(|i| i.to_semitones() == interval.to_semitones()) {
+                intervals.push(interval);
+            }
+        }
+        intervals.sort_by_key(|interval| interval.to_semitones());
+        intervals
+    }
+
+    /// Returns the notes of the chord in root position, spelled from the root.
+    pub fn notes(&self) -> Vec<Note> {
+        let mut notes = vec![self.root];
+        notes.extend(self.intervals().into_iter().map(|interval| self.root + interval));
+        notes
+    }
+
+    /// Returns true if the chord contains the note, or an enharmonic equivalent of it.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::{notes::*, chords::*};
+    ///
+    /// let chord = chord!("E7").unwrap();
+    /// assert!(chord.contains(note!("G#").unwrap()));
+    /// assert!(chord.contains(note!("Ab").unwrap()));
+    /// assert!(!chord.contains(note!("G").unwrap()));
+    /// ```
+    pub fn contains(&self, note: Note) -> bool {
+        let pitch_class = note.to_chromatic_scale_degree().rem_euclid(12);
+        self.notes().iter().any(|n| n.to_chromatic_scale_degree().rem_euclid(12) == pitch_class)
+    }
+
+    /// Returns true if the chord has a major third and a minor seventh.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::chords::*;
+    ///
+    /// assert!(chord!("G13").unwrap().is_dominant());
+    /// assert!(chord!("G7alt").unwrap().is_dominant());
+    /// assert!(!chord!("G7sus4").unwrap().is_dominant());
+    /// assert!(!chord!("Gmaj7").unwrap().is_dominant());
+    /// ```
+    pub fn is_dominant(&self) -> bool {
+        let intervals = self.intervals();
+        let has = |size: Size, semitones: i32| {
+            intervals.iter().any(|i| i.size() == size && i.to_semitones() % 12 == semitones)
+        };
+        has(Size::Third, 4) && has(Size::Seventh, 10)
+    }
+}
+
+/// Returns the chord degree an interval stands for, counting from 1 for the root.
+fn degree_of_interval(interval: &Interval) -> u8 {
+    interval.size() as u8 + 1 + 7 * interval.octaves()
+}
+
+/// Returns the interval of a degree above the root, altered from its major or perfect form
+/// by the single flat or sharp that [Chord::with_alteration] allows.
+fn degree_interval(degree: ScaleDegree) -> Interval {
+    let index = (degree.number() - 1) % 7;
+    let semitones = [0, 2, 4, 5, 7, 9, 11][index as usize];
+    let interval = Interval::from_semitones(semitones).unwrap();
+    let interval = match degree.accidental().to_semitones() {
+        n if n > 0 => interval.raised(n as u8).unwrap(),
+        n if n < 0 => interval.lowered(-n as u8).unwrap(),
+        _ => interval,
+    };
+    interval.compound((degree.number() - 1) / 7).unwrap()
+}
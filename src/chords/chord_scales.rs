@@ -0,0 +1,453 @@
+use super::*;
+use crate::{
+    error::KeyError,
+    keys::Key,
+    scales::{
+        pitch_class, pitch_class_set, rotate_set, types::utils::ALL_SCALES, Scale, ScaleEnumType,
+    },
+};
+
+/// The function of a chord in a key, as a degree of the key, or as a degree of the key
+/// of another degree for secondary functions, such as V/ii.
+///
+/// Functions are written as Roman numerals. Case is ignored, since the chord itself
+/// gives the quality, and a flat or sharp before a numeral alters the degree.
+///
+/// ### Examples
+/// ```
+/// use resonata::{chords::*, scales::*};
+///
+/// let function: ChordFunction = "V/ii".parse().unwrap();
+/// assert_eq!(function.degree(), "5".parse().unwrap());
+/// assert_eq!(function.target(), "2".parse().unwrap());
+/// assert!(function.is_secondary());
+/// assert_eq!("bVII".parse::<ChordFunction>().unwrap().degree(), "b7".parse().unwrap());
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct ChordFunction {
+    degree: ScaleDegree,
+    target: ScaleDegree,
+}
+
+/// A scale to play over a chord, as returned by [Chord::chord_scales], with the
+/// chord's available tensions and avoid notes in that scale.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChordScale {
+    root: Note,
+    scale: Scale,
+    scale_type: Option<ScaleEnumType>,
+    notes: Vec<Note>,
+    tensions: Vec<ScaleDegree>,
+    avoid_notes: Vec<Note>,
+}
+
+impl ChordFunction {
+    /// Creates the function of a chord on the given degree of the key.
+    pub fn new(degree: ScaleDegree) -> Self {
+        ChordFunction { degree, target: ScaleDegree::new(1, Accidental::Natural).unwrap() }
+    }
+
+    /// Creates the function of a chord on the given degree of the key of the target
+    /// degree, such as V/ii for the dominant of the supertonic.
+    pub fn secondary(degree: ScaleDegree, target: ScaleDegree) -> Self {
+        ChordFunction { degree, target }
+    }
+
+    /// Returns the degree of the chord's root in the key of its target.
+    pub fn degree(&self) -> ScaleDegree {
+        self.degree
+    }
+
+    /// Returns the degree the chord is applied to, which is the tonic for chords
+    /// that are not secondary.
+    pub fn target(&self) -> ScaleDegree {
+        self.target
+    }
+
+    /// Returns true if the chord is applied to a degree other than the tonic.
+    pub fn is_secondary(&self) -> bool {
+        self.target.number() != 1 || !self.target.is_diatonic()
+    }
+
+    /// Returns the chord on this function in a key, given by a scale and a tonic, made
+    /// by stacking thirds of the key, or of the key of the target degree for secondary
+    /// functions, up to the fifth or the seventh.
+    ///
+    /// Secondary targets are taken to be major or harmonic minor keys, by the triad on
+    /// them in the key, so that V/x is always major. Chords on altered degrees, such as
+    /// ♭VI, are major triads, or dominant seventh chords. A seventh with no chord type,
+    /// such as on III of harmonic minor, is left out.
+    ///
+    /// ### Failures
+    /// Returns an error if the target of a secondary function has no major or minor
+    /// triad, as in V/vii, or if the thirds stacked on the degree make no known triad.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::{notes::*, chords::*, scales::*};
+    ///
+    /// let (major, c) = (Scale::major(), note!("C").unwrap());
+    /// let chord = |s: &str, seventh| s.parse::<ChordFunction>().unwrap().to_chord(&major, c, seventh);
+    /// assert_eq!(chord("ii", true).unwrap(), chord!("Dm7").unwrap());
+    /// assert_eq!(chord("V/vi", false).unwrap(), chord!("E").unwrap());
+    /// assert_eq!(chord("vii/v", true).unwrap(), chord!("F#ø7").unwrap());
+    /// assert_eq!(chord("vii/vi", true).unwrap(), chord!("G#o7").unwrap());
+    /// assert_eq!(chord("bVI", false).unwrap(), chord!("Ab").unwrap());
+    /// ```
+    pub fn to_chord(&self, scale: &Scale, tonic: Note, seventh: bool) -> Result<Chord> {
+        let (scale, tonic) = self
+            .local_key(scale, tonic)
+            .ok_or_else(|| ChordError::InvalidChordFunction(self.to_string()))?;
+        let root = scale.note_at(self.degree, tonic);
+        if !self.degree.is_diatonic() {
+            let chord_type = if seventh { ChordType::Dominant7 } else { ChordType::Major };
+            return Ok(Chord::new(root, chord_type));
+        }
+
+        let above = |steps: u8| -> Result<i32> {
+            let degree = ScaleDegree::new(self.degree.number() + steps, Accidental::Natural)?;
+            Ok((pitch_class(&scale.note_at(degree, tonic)) - pitch_class(&root)).rem_euclid(12))
+        };
+        let triad = match (above(2)?, above(4)?) {
+            (4, 7) => ChordType::Major,
+            (3, 7) => ChordType::Minor,
+            (3, 6) => ChordType::Diminished,
+            (4, 8) => ChordType::Augmented,
+            _ => nope!(ChordError::UnknownChordType(self.to_string())),
+        };
+        let chord_type = match (triad, if seventh { above(6)? } else { 0 }) {
+            (ChordType::Major, 10) => ChordType::Dominant7,
+            (ChordType::Major, 11) => ChordType::Major7,
+            (ChordType::Minor, 10) => ChordType::Minor7,
+            (ChordType::Minor, 11) => ChordType::MinorMajor7,
+            (ChordType::Diminished, 10) => ChordType::HalfDiminished7,
+            (ChordType::Diminished, 9) => ChordType::Diminished7,
+            (triad, _) => triad,
+        };
+        Ok(Chord::new(root, chord_type))
+    }
+
+    /// Returns the key the degree is in, as a scale and a tonic: the key itself, or the
+    /// key of the target degree for secondary functions. That is a major or harmonic
+    /// minor key, by the triad on the target in the key, or `None` if the triad is
+    /// neither major nor minor.
+    fn local_key(&self, scale: &Scale, tonic: Note) -> Option<(Scale, Note)> {
+        if !self.is_secondary() {
+            return Some((scale.clone(), tonic));
+        }
+        let target = scale.note_at(self.target, tonic);
+        let above = |steps: u8| {
+            let degree = ScaleDegree::new(self.target.number() + steps, Accidental::Natural);
+            let note = scale.note_at(degree.ok()?, tonic);
+            Some((pitch_class(&note) - pitch_class(&target)).rem_euclid(12))
+        };
+        match (above(2)?, above(4)?) {
+            (4, 7) => Some((Scale::major(), target)),
+            (3, 7) => Some((Scale::harmonic_minor(), target)),
+            _ => None,
+        }
+    }
+}
+
+impl ChordScale {
+    /// Returns the root of the scale, which is the root of the chord.
+    pub fn root(&self) -> Note {
+        self.root
+    }
+
+    /// Returns the scale.
+    pub fn scale(&self) -> &Scale {
+        &self.scale
+    }
+
+    /// Returns the name of the scale, or `None` for scales outside the catalog.
+    pub fn scale_type(&self) -> Option<ScaleEnumType> {
+        self.scale_type
+    }
+
+    /// Returns the notes of the scale from the root, spelled to agree with the chord
+    /// and the key.
+    pub fn notes(&self) -> &Vec<Note> {
+        &self.notes
+    }
+
+    /// Returns the tensions available on the chord in this scale, such as ♭9 or ♯11.
+    pub fn tensions(&self) -> &Vec<ScaleDegree> {
+        &self.tensions
+    }
+
+    /// Returns the notes of the scale that clash with the chord. These are the notes a
+    /// semitone above a chord tone, except for ♭9 and ♭13 on dominant chords, and notes
+    /// that are neither chord tones nor tensions.
+    pub fn avoid_notes(&self) -> &Vec<Note> {
+        &self.avoid_notes
+    }
+}
+
+impl Chord {
+    /// Returns the scales that fit the chord in its function in a key, given by a scale
+    /// and a tonic, best first.
+    ///
+    /// The first scale is derived from the key: the notes of the key, or of the key of
+    /// the target degree for secondary functions, with the chord tones put in place of
+    /// the notes they alter. Scales from the catalog follow that contain the chord, with
+    /// the perfect fifth of dominant chords being optional, in order of how few notes
+    /// they change, then of how few avoid notes they have. Scales with consecutive
+    /// semitones, such as the bebop scales, and scales with fewer than six notes are left
+    /// out.
+    ///
+    /// Secondary targets are taken to be major or harmonic minor keys, as in
+    /// [ChordFunction::to_chord].
+    ///
+    /// ### Failures
+    /// Returns an error if the target of a secondary function has no major or minor
+    /// triad, or if the chord's root is not on the degree of its function.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::{notes::*, chords::*, scales::*};
+    ///
+    /// let g7 = chord!("G7").unwrap();
+    ///
+    /// // G7 as V of C
+    /// let scales = g7.chord_scales(&"V".parse().unwrap(), &Scale::major(), note!("C").unwrap()).unwrap();
+    /// assert_eq!(scales[0].scale_type(), Some(MajorMode::Mixolydian.into()));
+    /// assert_eq!(scales[0].tensions(), &["9", "13"].map(|d| d.parse::<ScaleDegree>().unwrap()));
+    /// assert_eq!(scales[0].avoid_notes(), &vec![note!("C").unwrap()]);
+    ///
+    /// let names = scales.iter().filter_map(|s| s.scale_type()).collect::<Vec<_>>();
+    /// assert!(names.contains(&MelodicMinorMode::LydianDominant.into()));
+    /// assert!(names.contains(&MelodicMinorMode::Altered.into()));
+    /// assert!(names.contains(&ScaleType::DominantDiminished.into()));
+    ///
+    /// // G7 as V/ii of Bb, resolving to C minor
+    /// let scales = g7.chord_scales(&"V/ii".parse().unwrap(), &Scale::major(), note!("Bb").unwrap()).unwrap();
+    /// assert_eq!(scales[0].scale_type(), Some(HarmonicMinorMode::PhrygianDominant.into()));
+    /// assert_eq!(scales[0].tensions(), &["b9", "b13"].map(|d| d.parse::<ScaleDegree>().unwrap()));
+    /// assert_eq!(scales[0].notes()[6], note!("F").unwrap());
+    ///
+    /// assert!(g7.chord_scales(&"V/ii".parse().unwrap(), &Scale::major(), note!("C").unwrap()).is_err());
+    /// ```
+    pub fn chord_scales(
+        &self,
+        function: &ChordFunction,
+        scale: &Scale,
+        tonic: Note,
+    ) -> Result<Vec<ChordScale>> {
+        let (local_scale, local_tonic) = function
+            .local_key(scale, tonic)
+            .ok_or_else(|| ChordError::InvalidChordFunction(function.to_string()))?;
+        if pitch_class(&local_scale.note_at(function.degree, local_tonic))
+            != pitch_class(&self.root)
+        {
+            nope!(ChordError::ChordNotOnDegree(self.root));
+        }
+
+        let derived = self.derived_scale_notes(&local_scale.to_notes(local_tonic));
+        let derived_set = pitch_class_set(&derived);
+        let chord_notes = self.notes();
+        let required = self.required_pitch_classes();
+
+        let mut chord_scales = Vec::new();
+        if derived.len() == local_scale.to_notes(local_tonic).len() {
+            let mut notes = derived.clone();
+            notes.push(self.root);
+            chord_scales.push(self.chord_scale(Scale::from_notes(notes), &derived));
+        }
+
+        let mut candidates: Vec<(u32, usize, ChordScale)> = Vec::new();
+        let catalog = ALL_SCALES.iter().flat_map(|scales| scales.iter());
+        for (index, (_, scale)) in catalog.enumerate() {
+            let scale = scale();
+            let set = rotate_set(scale.number(), pitch_class(&self.root) as u32);
+            if scale.cardinality() < 6
+                || scale.has_consecutive_semitones()
+                || set & required != required
+                || chord_scales
+                    .iter()
+                    .chain(candidates.iter().map(|(_, _, c)| c))
+                    .any(|c| c.scale == scale)
+            {
+                continue;
+            }
+            let notes = scale
+                .to_notes(self.root)
+                .into_iter()
+                .map(|note| {
+                    let same = |other: &&Note| pitch_class(other) == pitch_class(&note);
+                    chord_notes.iter().chain(derived.iter()).find(same).copied().unwrap_or(note)
+                })
+                .collect::<Vec<_>>();
+            candidates.push((
+                (set ^ derived_set).count_ones(),
+                index,
+                self.chord_scale(scale, &notes),
+            ));
+        }
+        candidates.sort_by_key(|(distance, index, chord_scale)| {
+            (*distance, chord_scale.avoid_notes.len(), *index)
+        });
+        chord_scales.extend(candidates.into_iter().map(|(_, _, chord_scale)| chord_scale));
+        Ok(chord_scales)
+    }
+
+    /// Returns the scales that fit the chord in its function in a key, as described in
+    /// [Chord::chord_scales], using the tonic and scale type of the key.
+    ///
+    /// ### Failures
+    /// Returns an error if the key is not a known scale type, or if the chord's root is
+    /// not on the degree of its function.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::{notes::*, chords::*, keys::*, scales::*};
+    ///
+    /// let key = key!("F#").unwrap();
+    /// let scales = chord!("Am7").unwrap().chord_scales_in_key(&"ii".parse().unwrap(), &key).unwrap();
+    /// assert_eq!(scales[0].scale_type(), Some(MajorMode::Dorian.into()));
+    /// assert!(scales[0].avoid_notes().is_empty());
+    /// ```
+    pub fn chord_scales_in_key(
+        &self,
+        function: &ChordFunction,
+        key: &Key,
+    ) -> Result<Vec<ChordScale>> {
+        match (key.root(), key.to_scale_type()) {
+            (Some(tonic), Some(scale_type)) => {
+                self.chord_scales(function, &scale_type.to_scale(), tonic)
+            }
+            _ => nope!(KeyError::InvalidKey),
+        }
+    }
+
+    /// Returns the function of the chord in a key, given by a scale and a tonic, or
+    /// `None` if its root is not on a degree of the key.
+    ///
+    /// Chords made of notes of the key are on the degree of their root. Major triads,
+    /// dominant chords and diminished chords that are not are read as applied to the
+    /// degree a fourth or a semitone above their root, if that degree has a major or
+    /// minor triad, so that they become V/x or VII/x, or V and VII when applied to the
+    /// tonic. Other chromatic chords, such as ♭VI or ♭II, are on the altered degree of
+    /// their root.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::{notes::*, chords::*, scales::*};
+    ///
+    /// let (major, c) = (Scale::major(), note!("C").unwrap());
+    /// let function = |symbol: &str| symbol.parse::<Chord>().unwrap().function_in(&major, c);
+    /// assert_eq!(function("Dm7").unwrap().to_string(), "II");
+    /// assert_eq!(function("A7").unwrap().to_string(), "V/II");
+    /// assert_eq!(function("F#o7").unwrap().to_string(), "VII/V");
+    /// assert_eq!(function("Ab").unwrap().to_string(), "♭VI");
+    /// assert_eq!(function("F#").unwrap().to_string(), "♯IV");
+    /// assert_eq!(chord!("F").unwrap().function_in(&Scale::major_pentatonic(), c), None);
+    ///
+    /// let (minor, a) = (Scale::minor(), note!("A").unwrap());
+    /// assert_eq!(chord!("E7").unwrap().function_in(&minor, a).unwrap().to_string(), "V");
+    /// ```
+    pub fn function_in(&self, scale: &Scale, tonic: Note) -> Option<ChordFunction> {
+        let degree = scale.degree_of(self.root, tonic)?;
+        let scale_set = pitch_class_set(&scale.to_notes(tonic));
+        let chord_set = pitch_class_set(&self.notes());
+        if chord_set & scale_set == chord_set {
+            return Some(ChordFunction::new(degree));
+        }
+
+        let applied = match self.chord_type {
+            ChordType::Diminished | ChordType::Diminished7 | ChordType::HalfDiminished7 => {
+                Some((7, Interval::from_semitones(1).unwrap()))
+            }
+            ChordType::Major => Some((5, Interval::from_semitones(5).unwrap())),
+            _ if self.is_dominant() => Some((5, Interval::from_semitones(5).unwrap())),
+            _ => None,
+        };
+        if let Some((number, interval)) = applied {
+            let target = self.root + interval;
+            if let Some(target) = scale.degree_of(target, tonic).filter(|d| d.is_diatonic()) {
+                if target.number() == 1 {
+                    return Some(ChordFunction::new(degree));
+                }
+                let degree = ScaleDegree::new(number, Accidental::Natural).ok()?;
+                let function = ChordFunction::secondary(degree, target);
+                if function.local_key(scale, tonic).is_some() {
+                    return Some(function);
+                }
+            }
+        }
+        Some(ChordFunction::new(degree))
+    }
+
+    /// Returns the notes of a scale, from the chord root, with each chord tone in place
+    /// of the scale note with the same name. Chord tones that share a name with another
+    /// chord tone are added.
+    fn derived_scale_notes(&self, scale_notes: &[Note]) -> Vec<Note> {
+        let mut notes = scale_notes.to_vec();
+        let mut replaced = Vec::new();
+        for chord_note in self.notes() {
+            match notes.iter().position(|note| note.name() == chord_note.name()) {
+                Some(i) if !replaced.contains(&chord_note.name()) => notes[i] = chord_note,
+                _ => notes.push(chord_note),
+            }
+            replaced.push(chord_note.name());
+        }
+        let chord_set = pitch_class_set(&self.notes());
+        notes.retain(|note| {
+            self.notes().contains(note) || chord_set & (1 << pitch_class(note)) == 0
+        });
+        notes.sort_by_key(|note| (pitch_class(note) - pitch_class(&self.root)).rem_euclid(12));
+        notes.dedup_by_key(|note| pitch_class(note));
+        notes
+    }
+
+    /// Returns the pitch classes a scale must contain to fit the chord, relative to C.
+    fn required_pitch_classes(&self) -> u16 {
+        let mut notes = self.notes();
+        if self.is_dominant() {
+            notes.retain(|note| (pitch_class(note) - pitch_class(&self.root)).rem_euclid(12) != 7);
+        }
+        pitch_class_set(&notes)
+    }
+
+    /// Finds the tensions and avoid notes of the chord in a scale, spelled by the given notes.
+    fn chord_scale(&self, scale: Scale, notes: &[Note]) -> ChordScale {
+        let chord_set = pitch_class_set(&self.notes()) & pitch_class_set(notes);
+        let mut tensions = Vec::new();
+        let mut avoid_notes = Vec::new();
+        for note in notes {
+            if chord_set & (1 << pitch_class(note)) != 0 {
+                continue;
+            }
+            let semitones = (pitch_class(note) - pitch_class(&self.root)).rem_euclid(12);
+            let tension = match semitones {
+                1 => Some((9, Accidental::Flat(1))),
+                2 => Some((9, Accidental::Natural)),
+                3 => Some((9, Accidental::Sharp(1))),
+                5 => Some((11, Accidental::Natural)),
+                6 => Some((11, Accidental::Sharp(1))),
+                8 => Some((13, Accidental::Flat(1))),
+                9 => Some((13, Accidental::Natural)),
+                _ => None,
+            };
+            let below = (pitch_class(note) - 1).rem_euclid(12);
+            let clashes = chord_set & (1 << below) != 0
+                && !(self.is_dominant() && matches!(semitones, 1 | 3 | 8));
+            match tension {
+                Some((number, accidental)) if !clashes => {
+                    tensions.push(ScaleDegree::new(number, accidental).unwrap())
+                }
+                _ => avoid_notes.push(*note),
+            }
+        }
+        tensions.sort_by_key(|degree| (degree.number(), degree.accidental()));
+
+        ChordScale {
+            root: self.root,
+            scale_type: scale.get_known_scale_type(),
+            scale,
+            notes: notes.to_vec(),
+            tensions,
+            avoid_notes,
+        }
+    }
+}
@@ -0,0 +1,337 @@
+use super::*;
+use crate::keys::Key;
+
+/// A single figure of a figured bass, such as "6", "#6", "b7", "4" or a lone "#".
+///
+/// The number is the diatonic interval above the bass, read from the key. A sharp or
+/// flat raises or lowers that note of the key by a semitone, and a natural cancels
+/// the key's accidental, as in Baroque practice, so "#" over a B♭ in F major gives B♮.
+/// A slashed figure, written "6\\" or "6+", is raised like a sharp. A figure with an
+/// accidental and no number alters the third.
+///
+/// ### Examples
+/// ```
+/// use resonata::{notes::*, chords::*};
+///
+/// let figure: Figure = "#6".parse().unwrap();
+/// assert_eq!(figure.number(), 6);
+/// assert_eq!(figure.accidental(), Some(Accidental::Sharp(1)));
+/// assert_eq!("b".parse::<Figure>().unwrap().number(), 3);
+/// assert!("6\\".parse::<Figure>().unwrap().is_slashed());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Figure {
+    number: Option<u8>,
+    accidental: Option<Accidental>,
+    slashed: bool,
+}
+
+/// A bass note with the figures above it
+///
+/// Figures may be abbreviated as they usually are: no figures stand for 5/3, "6" for
+/// 6/3, "7" for 7/5/3, "6/5" for 6/5/3, "4/3" for 6/4/3 and "4/2" or "2" for 6/4/2.
+/// Otherwise a third and a fifth are implied unless a second, fourth or sixth takes
+/// their place.
+///
+/// ### Examples
+/// ```
+/// use resonata::{notes::*, keys::*, chords::*};
+///
+/// let key = key!("F#").unwrap();
+/// let figured: FiguredBass = "C4 6/5".parse().unwrap();
+/// assert_eq!(figured.realized(&key), ["C", "E", "G", "A"].map(|n| n.parse().unwrap()));
+///
+/// let figured: FiguredBass = "C4 #6/4/2".parse().unwrap();
+/// assert_eq!(figured.realized(&key), ["C", "D", "F#", "A#"].map(|n| n.parse().unwrap()));
+/// assert_eq!(figured.to_string(), "C4 #6/4/2");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FiguredBass {
+    bass: PitchedNote,
+    figures: Vec<Figure>,
+}
+
+impl Figure {
+    /// Creates a figure from the interval above the bass, an accidental, and whether
+    /// it is slashed. A figure with no number alters the third.
+    ///
+    /// ### Failures
+    /// Returns an error if the number is not from 2 to 9, or if a figure has neither a
+    /// number nor an accidental.
+    pub fn new(number: Option<u8>, accidental: Option<Accidental>, slashed: bool) -> Result<Self> {
+        let figure = Figure { number, accidental, slashed };
+        let valid = match number {
+            Some(number) => (2..=9).contains(&number),
+            None => accidental.is_some() || slashed,
+        };
+        if !valid {
+            nope!(ChordError::InvalidFigure(figure.to_string()));
+        }
+        Ok(figure)
+    }
+
+    /// Returns the interval above the bass, which is 3 for a lone accidental.
+    pub fn number(&self) -> u8 {
+        self.number.unwrap_or(3)
+    }
+
+    /// Returns true if the figure is a lone accidental, with no number.
+    pub fn is_accidental_only(&self) -> bool {
+        self.number.is_none()
+    }
+
+    /// Returns the accidental of the figure, if any.
+    pub fn accidental(&self) -> Option<Accidental> {
+        self.accidental
+    }
+
+    /// Returns true if the figure is slashed, raising it by a semitone.
+    pub fn is_slashed(&self) -> bool {
+        self.slashed
+    }
+
+    /// Returns the note of the figure above the bass in the key.
+    fn realized(&self, bass: Note, key: &Key) -> Note {
+        let name = bass.name() + (self.number() as i32 - 1);
+        let in_key = key.pitch(name).accidental().to_semitones();
+        let mut semitones = match self.accidental {
+            Some(Accidental::Natural) => 0,
+            Some(accidental) => in_key + accidental.to_semitones(),
+            None => in_key,
+        };
+        if self.slashed {
+            semitones += 1;
+        }
+        Note::new(name, Accidental::from_semitones(semitones))
+    }
+}
+
+impl FiguredBass {
+    /// Creates a figured bass from a bass note and its figures, from the top down.
+    pub fn new(bass: PitchedNote, figures: Vec<Figure>) -> Self {
+        FiguredBass { bass, figures }
+    }
+
+    /// Finds the figures of a chord over a bass note in a key, abbreviated as they
+    /// usually are. Ninths are figured as 9 unless they form a 4/2 chord.
+    ///
+    /// ### Failures
+    /// Returns an error if the bass is not a note of the chord.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::{notes::*, keys::*, chords::*};
+    ///
+    /// let key = key!("Bb Eb").unwrap();
+    /// let bass = pnote!("A2").unwrap();
+    /// let figured = FiguredBass::from_chord(&chord!("F7").unwrap(), bass, &key).unwrap();
+    /// assert_eq!(figured.to_string(), "A2 6/5");
+    ///
+    /// let bass = pnote!("D3").unwrap();
+    /// let figured = FiguredBass::from_chord(&chord!("D").unwrap(), bass, &key).unwrap();
+    /// assert_eq!(figured.to_string(), "D3 #");
+    /// let figured = FiguredBass::from_chord(&chord!("Bbmaj7").unwrap(), bass, &key).unwrap();
+    /// assert_eq!(figured.to_string(), "D3 6/5");
+    /// assert!(FiguredBass::from_chord(&chord!("C").unwrap(), bass, &key).is_err());
+    ///
+    /// let bass = pnote!("F2").unwrap();
+    /// let figured = FiguredBass::from_chord(&chord!("Bbmaj7").unwrap(), bass, &key).unwrap();
+    /// assert_eq!(figured.to_string(), "F2 4/3");
+    /// ```
+    pub fn from_chord(chord: &Chord, bass: PitchedNote, key: &Key) -> Result<Self> {
+        let bass_note = Note::from(bass);
+        if !chord.contains(bass_note) {
+            nope!(ChordError::BassNotInChord(bass_note));
+        }
+        let mut intervals: Vec<(u8, Option<Accidental>)> = Vec::new();
+        for note in chord.notes() {
+            let number = (note.name() as i32 - bass_note.name() as i32).rem_euclid(7) as u8 + 1;
+            if number == 1 {
+                continue;
+            }
+            let in_key = key.pitch(note.name()).accidental();
+            let accidental = match note.accidental() {
+                accidental if accidental == in_key => None,
+                Accidental::Natural => Some(Accidental::Natural),
+                accidental => Some(Accidental::from_semitones(
+                    accidental.to_semitones() - in_key.to_semitones(),
+                )),
+            };
+            intervals.push((number, accidental));
+        }
+        let numbers = intervals.iter().map(|(number, _)| *number).collect::<Vec<_>>();
+        if numbers.contains(&2) && !numbers.contains(&4) {
+            for interval in intervals.iter_mut().filter(|(number, _)| *number == 2) {
+                interval.0 = 9;
+            }
+        }
+        intervals.sort_by_key(|(number, _)| std::cmp::Reverse(*number));
+        intervals.dedup_by_key(|(number, _)| *number);
+
+        // Leave out the intervals that the abbreviated figures imply, unless altered.
+        let numbers = intervals.iter().map(|(number, _)| *number).collect::<Vec<_>>();
+        let implied = implied(&abbreviated(&numbers));
+        let mut figures = Vec::new();
+        for (number, accidental) in intervals {
+            let figure = match (implied.contains(&number), accidental) {
+                (false, _) => Figure { number: Some(number), accidental, slashed: false },
+                (true, None) => continue,
+                (true, Some(_)) if number == 3 => {
+                    Figure { number: None, accidental, slashed: false }
+                }
+                (true, Some(_)) => Figure { number: Some(number), accidental, slashed: false },
+            };
+            figures.push(figure);
+        }
+        Ok(FiguredBass { bass, figures })
+    }
+
+    /// Returns the bass note.
+    pub fn bass(&self) -> PitchedNote {
+        self.bass
+    }
+
+    /// Returns the figures, from the top down.
+    pub fn figures(&self) -> &Vec<Figure> {
+        &self.figures
+    }
+
+    /// Returns every interval above the bass that the figures stand for, from the top
+    /// down, with the abbreviations filled in.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::chords::*;
+    ///
+    /// assert_eq!("C3".parse::<FiguredBass>().unwrap().intervals(), vec![5, 3]);
+    /// assert_eq!("C3 4/3".parse::<FiguredBass>().unwrap().intervals(), vec![6, 4, 3]);
+    /// assert_eq!("C3 7/#".parse::<FiguredBass>().unwrap().intervals(), vec![7, 5, 3]);
+    /// assert_eq!("C3 9".parse::<FiguredBass>().unwrap().intervals(), vec![9, 5, 3]);
+    /// ```
+    pub fn intervals(&self) -> Vec<u8> {
+        let numbers = self.figures.iter().map(|figure| figure.number()).collect::<Vec<_>>();
+        let mut intervals = numbers.clone();
+        intervals.extend(implied(&numbers));
+        intervals.sort_by_key(|number| std::cmp::Reverse(*number));
+        intervals.dedup();
+        intervals
+    }
+
+    /// Realizes the figures in a key, and returns the bass followed by the notes
+    /// above it from the lowest interval up.
+    pub fn realized(&self, key: &Key) -> Vec<Note> {
+        let mut notes = vec![Note::from(self.bass)];
+        notes.extend(self.realized_intervals(key).into_iter().map(|(_, note)| note));
+        notes
+    }
+
+    /// Returns each interval above the bass with its note, from the lowest up.
+    fn realized_intervals(&self, key: &Key) -> Vec<(u8, Note)> {
+        let bass = Note::from(self.bass);
+        let mut notes = Vec::new();
+        for number in self.intervals().into_iter().rev() {
+            let figure = self
+                .figures
+                .iter()
+                .find(|figure| figure.number() == number)
+                .copied()
+                .unwrap_or(Figure { number: Some(number), accidental: None, slashed: false });
+            notes.push((number, figure.realized(bass, key)));
+        }
+        notes
+    }
+
+    /// Realizes the figures in four parts: the bass, and three upper voices in close
+    /// position with the lowest at or above the given note. Triads double the bass,
+    /// and chords of five notes leave out the fifth.
+    ///
+    /// ### Failures
+    /// Returns an error if a voice is out of range.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::{notes::*, keys::*, chords::*};
+    ///
+    /// let key = key!("Bb Eb").unwrap();
+    /// let figured: FiguredBass = "A2 6/5".parse().unwrap();
+    /// let voiced = figured.voiced(&key, pnote!("C4").unwrap()).unwrap();
+    /// assert_eq!(voiced, ["A2", "C4", "Eb4", "F4"].map(|n| n.parse().unwrap()));
+    ///
+    /// let figured: FiguredBass = "G2 6/4".parse().unwrap();
+    /// let voiced = figured.voiced(&key, pnote!("C4").unwrap()).unwrap();
+    /// assert_eq!(voiced, ["G2", "C4", "Eb4", "G4"].map(|n| n.parse().unwrap()));
+    /// ```
+    pub fn voiced(&self, key: &Key, lowest: PitchedNote) -> Result<Vec<PitchedNote>> {
+        let bass = Note::from(self.bass);
+        let pitch_class = |note: &Note| note.to_chromatic_scale_degree().rem_euclid(12);
+        let mut intervals = self.realized_intervals(key);
+        intervals.retain(|(_, note)| pitch_class(note) != pitch_class(&bass));
+        if intervals.len() > 3 {
+            intervals.retain(|(number, _)| *number != 5);
+            intervals.truncate(3);
+        }
+        let mut upper = intervals.into_iter().map(|(_, note)| note).collect::<Vec<_>>();
+        while upper.len() < 3 {
+            upper.push(bass);
+        }
+
+        let floor = match lowest.to_midi_number() > self.bass.to_midi_number() {
+            true => lowest.to_midi_number(),
+            false => self.bass.to_midi_number() + 1,
+        };
+        let mut voices = Vec::new();
+        for note in upper {
+            let mut octave = PitchedNote::from_midi_number(floor)?.octave() - 1;
+            let mut pitch = note.with_octave(octave)?;
+            while pitch.to_midi_number() < floor {
+                octave += 1;
+                pitch = note.with_octave(octave)?;
+            }
+            voices.push(pitch);
+        }
+        voices.sort_by_key(|pitch| pitch.to_midi_number());
+        voices.dedup();
+        while voices.len() < 3 {
+            let top = voices[voices.len() - 1];
+            voices.push(Note::from(top).with_octave(top.octave() + 1)?);
+        }
+        Ok([vec![self.bass], voices].concat())
+    }
+}
+
+/// Returns the intervals implied by figures with the given numbers, from the top down.
+fn implied(numbers: &[u8]) -> Vec<u8> {
+    let has = |number: u8| numbers.contains(&number);
+    match numbers {
+        [4, 3] | [4, 2] => return vec![6],
+        [2] => return vec![6, 4],
+        _ => {}
+    }
+    let mut implied = Vec::new();
+    if !has(4) && !has(5) && !has(6) {
+        implied.push(5);
+    }
+    if !has(2) && !has(3) && !has(4) {
+        implied.push(3);
+    }
+    implied
+}
+
+/// Returns the usual abbreviation of a full set of intervals above the bass.
+fn abbreviated(numbers: &[u8]) -> Vec<u8> {
+    match numbers {
+        [6, 4, 3] => return vec![4, 3],
+        [6, 4, 2] => return vec![4, 2],
+        _ => {}
+    }
+    let has = |number: u8| numbers.contains(&number);
+    numbers
+        .iter()
+        .copied()
+        .filter(|number| match number {
+            3 => has(2) || has(4),
+            5 => has(4) || has(6),
+            _ => true,
+        })
+        .collect()
+}
@@ -0,0 +1,271 @@
+use super::*;
+use std::collections::VecDeque;
+
+/// A neo-Riemannian transformation between major and minor triads.
+///
+/// - `P` (parallel) keeps the root and fifth and moves the third: C major and C minor.
+/// - `L` (leading-tone exchange) moves the root of a major triad down a semitone, or the
+///   fifth of a minor triad up one: C major and E minor.
+/// - `R` (relative) moves the fifth of a major triad up a tone, or the root of a minor
+///   triad down one: C major and A minor.
+/// - `N` (Nebenverwandt) is RLP: C major and F minor.
+/// - `S` (slide) is LPR: C major and C♯ minor.
+/// - `H` (hexatonic pole) is LPL: C major and A♭ minor.
+///
+/// Every transformation is its own inverse. Sequences of transformations are written
+/// as strings such as "PLR", and applied from left to right.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Transformation {
+    P,
+    L,
+    R,
+    N,
+    S,
+    H,
+}
+
+/// A position on the Tonnetz, the lattice of pitch classes laid out in perfect fifths
+/// along one axis and major thirds along the other
+///
+/// The Tonnetz wraps around after four fifths and three major thirds, so every
+/// pitch class has one position with a number of fifths from 0 to 3 and a number of
+/// major thirds from 0 to 2, counted from C. Triads are triangles on the Tonnetz, and
+/// [Chord::tonnetz_triangle] gives their vertices without wrapping them, so that
+/// they can be drawn.
+///
+/// ### Examples
+/// ```
+/// use resonata::{notes::*, chords::*};
+///
+/// let e = TonnetzPosition::of_note(note!("E").unwrap());
+/// assert_eq!((e.fifths(), e.thirds()), (0, 1));
+/// assert_eq!(TonnetzPosition::of_note(note!("Fb").unwrap()), e);
+/// assert_eq!(TonnetzPosition::new(1, 1).to_note(), note!("B").unwrap());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TonnetzPosition {
+    fifths: i32,
+    thirds: i32,
+}
+
+impl Transformation {
+    /// Parses a sequence of transformations, such as "PLR", ignoring whitespace.
+    ///
+    /// ### Failures
+    /// Returns an error if a letter is not a transformation.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::chords::*;
+    ///
+    /// let sequence = Transformation::parse_sequence("P L R").unwrap();
+    /// assert_eq!(sequence, vec![Transformation::P, Transformation::L, Transformation::R]);
+    /// assert!(Transformation::parse_sequence("PX").is_err());
+    /// ```
+    pub fn parse_sequence(s: &str) -> Result<Vec<Self>> {
+        s.chars().filter(|c| !c.is_whitespace()).map(|c| c.to_string().parse()).collect()
+    }
+
+    /// Returns the transformation as a sequence of P, L and R, applied from left to right.
+    pub fn to_plr(&self) -> Vec<Transformation> {
+        use Transformation::*;
+        match self {
+            N => vec![R, L, P],
+            S => vec![L, P, R],
+            H => vec![L, P, L],
+            _ => vec![*self],
+        }
+    }
+}
+
+impl TonnetzPosition {
+    /// Creates a position from a number of perfect fifths and major thirds from C.
+    pub fn new(fifths: i32, thirds: i32) -> Self {
+        TonnetzPosition { fifths, thirds }
+    }
+
+    /// Returns the position of a note, with enharmonic notes at the same position.
+    pub fn of_note(note: Note) -> Self {
+        let pitch_class = note.to_chromatic_scale_degree().rem_euclid(12);
+        // Fifths are 3 mod 4 semitones and 1 mod 3, and major thirds 0 mod 4 and 1 mod 3.
+        let fifths = (3 * pitch_class).rem_euclid(4);
+        let thirds = (pitch_class - fifths).rem_euclid(3);
+        TonnetzPosition { fifths, thirds }
+    }
+
+    /// Returns the number of perfect fifths from C.
+    pub fn fifths(&self) -> i32 {
+        self.fifths
+    }
+
+    /// Returns the number of major thirds from C.
+    pub fn thirds(&self) -> i32 {
+        self.thirds
+    }
+
+    /// Returns the position wrapped onto the Tonnetz, with fifths from 0 to 3 and major
+    /// thirds from 0 to 2.
+    pub fn wrapped(&self) -> Self {
+        TonnetzPosition::of_note(self.to_note())
+    }
+
+    /// Returns the note at the position, spelled with sharps.
+    pub fn to_note(&self) -> Note {
+        let pitch_class = (7 * self.fifths + 4 * self.thirds).rem_euclid(12);
+        Note::from_chromatic_scale_degree(pitch_class as u8)
+    }
+}
+
+impl Chord {
+    /// Returns true if the chord is a major or minor triad, with no alterations or bass.
+    pub fn is_major_or_minor_triad(&self) -> bool {
+        matches!(self.chord_type, ChordType::Major | ChordType::Minor)
+            && self.alterations.is_empty()
+            && self.bass.is_none()
+    }
+
+    /// Applies a neo-Riemannian transformation to a major or minor triad. The new root
+    /// is spelled by its interval from the old one, so the result keeps the common tones
+    /// of the triad spelled as they were.
+    ///
+    /// ### Failures
+    /// Returns an error if the chord is not a major or minor triad.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::chords::*;
+    ///
+    /// let c = chord!("C").unwrap();
+    /// assert_eq!(c.transformed(Transformation::L).unwrap(), chord!("Em").unwrap());
+    /// assert_eq!(c.transformed(Transformation::R).unwrap(), chord!("Am").unwrap());
+    /// assert_eq!(c.transformed(Transformation::H).unwrap(), chord!("Abm").unwrap());
+    /// assert_eq!(chord!("Ebm").unwrap().transformed(Transformation::L).unwrap(), chord!("Cb").unwrap());
+    /// assert!(chord!("C7").unwrap().transformed(Transformation::P).is_err());
+    /// ```
+    pub fn transformed(&self, transformation: Transformation) -> Result<Chord> {
+        use Transformation::*;
+        if !self.is_major_or_minor_triad() {
+            nope!(ChordError::InvalidTriad(self.to_string()));
+        }
+        let major = self.chord_type == ChordType::Major;
+        // The interval from the root of a major triad to the root of its image.
+        let interval = match transformation {
+            P => "P1",
+            L => "M3",
+            R => "M6",
+            N => "P4",
+            S => "A1",
+            H => "m6",
+        };
+        let interval = interval.parse::<Interval>()?;
+        let root = match major {
+            true => self.root + interval,
+            false => self.root - interval,
+        };
+        let chord_type = if major { ChordType::Minor } else { ChordType::Major };
+        Ok(Chord::new(root, chord_type))
+    }
+
+    /// Applies a sequence of transformations, such as "PLR", from left to right.
+    ///
+    /// ### Failures
+    /// Returns an error if the sequence cannot be parsed, or if the chord is not a
+    /// major or minor triad.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::chords::*;
+    ///
+    /// let c = chord!("C").unwrap();
+    /// assert_eq!(c.transformed_by("LP").unwrap(), chord!("E").unwrap());
+    /// assert_eq!(c.transformed_by("PLR").unwrap(), chord!("Fm").unwrap());
+    /// assert_eq!(c.transformed_by("RLP").unwrap(), c.transformed(Transformation::N).unwrap());
+    /// ```
+    pub fn transformed_by(&self, sequence: &str) -> Result<Chord> {
+        let mut chord = self.clone();
+        if !chord.is_major_or_minor_triad() {
+            nope!(ChordError::InvalidTriad(self.to_string()));
+        }
+        for transformation in Transformation::parse_sequence(sequence)? {
+            chord = chord.transformed(transformation)?;
+        }
+        Ok(chord)
+    }
+
+    /// Finds a shortest sequence of P, L and R transformations from this triad to
+    /// another, comparing triads by pitch class. Of the shortest sequences, the first
+    /// in the order P, L, R is returned.
+    ///
+    /// ### Failures
+    /// Returns an error if either chord is not a major or minor triad.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::chords::*;
+    ///
+    /// let c = chord!("C").unwrap();
+    /// let path = c.transformation_path(&chord!("Abm").unwrap()).unwrap();
+    /// assert_eq!(path, Transformation::parse_sequence("PLP").unwrap());
+    /// assert!(c.transformation_path(&chord!("B#").unwrap()).unwrap().is_empty());
+    /// ```
+    pub fn transformation_path(&self, target: &Chord) -> Result<Vec<Transformation>> {
+        use Transformation::*;
+        for chord in [self, target] {
+            if !chord.is_major_or_minor_triad() {
+                nope!(ChordError::InvalidTriad(chord.to_string()));
+            }
+        }
+        let key = |chord: &Chord| {
+            (chord.root.to_chromatic_scale_degree().rem_euclid(12), chord.chord_type)
+        };
+        let goal = key(target);
+
+        // A breadth-first search over the 24 major and minor triads.
+        let mut seen = vec![key(self)];
+        let mut queue = VecDeque::from([(self.clone(), Vec::new())]);
+        while let Some((chord, path)) = queue.pop_front() {
+            if key(&chord) == goal {
+                return Ok(path);
+            }
+            for transformation in [P, L, R] {
+                let next = chord.transformed(transformation)?;
+                if !seen.contains(&key(&next)) {
+                    seen.push(key(&next));
+                    queue.push_back((next, [path.clone(), vec![transformation]].concat()));
+                }
+            }
+        }
+        Ok(Vec::new())
+    }
+
+    /// Returns the vertices of a major or minor triad on the Tonnetz, as the positions
+    /// of its root, third and fifth. The root is wrapped onto the Tonnetz, and the third
+    /// and fifth are placed next to it, so a major triad points up and a minor triad
+    /// points down.
+    ///
+    /// ### Failures
+    /// Returns an error if the chord is not a major or minor triad.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::chords::*;
+    ///
+    /// let [root, third, fifth] = chord!("Am").unwrap().tonnetz_triangle().unwrap();
+    /// assert_eq!(root, TonnetzPosition::new(3, 0));
+    /// assert_eq!(third, TonnetzPosition::new(4, -1));
+    /// assert_eq!(fifth, TonnetzPosition::new(4, 0));
+    /// assert_eq!(third.wrapped(), TonnetzPosition::new(0, 0));
+    /// ```
+    pub fn tonnetz_triangle(&self) -> Result<[TonnetzPosition; 3]> {
+        if !self.is_major_or_minor_triad() {
+            nope!(ChordError::InvalidTriad(self.to_string()));
+        }
+        let root = TonnetzPosition::of_note(self.root);
+        let (fifths, thirds) = (root.fifths, root.thirds);
+        let third = match self.chord_type {
+            ChordType::Major => TonnetzPosition::new(fifths, thirds + 1),
+            _ => TonnetzPosition::new(fifths + 1, thirds - 1),
+        };
+        Ok([root, third, TonnetzPosition::new(fifths + 1, thirds)])
+    }
+}
@@ -0,0 +1,271 @@
+#[cfg(test)]
+mod tests {
+    use super::super::*;
+    use crate::{intervals::*, keys::*, scales::*, TransposeUp};
+
+    fn notes(notes: &[&str]) -> Vec<Note> {
+        notes.iter().map(|note| note.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn test_parse_symbols() {
+        let cases = [
+            ("C", ChordType::Major, vec!["C", "E", "G"]),
+            ("Cm", ChordType::Minor, vec!["C", "Eb", "G"]),
+            ("C-7", ChordType::Minor7, vec!["C", "Eb", "G", "Bb"]),
+            ("Cø7", ChordType::HalfDiminished7, vec!["C", "Eb", "Gb", "Bb"]),
+            ("Co7", ChordType::Diminished7, vec!["C", "Eb", "Gb", "Bbb"]),
+            ("C+", ChordType::Augmented, vec!["C", "E", "G#"]),
+            ("Csus", ChordType::Suspended4, vec!["C", "F", "G"]),
+            ("C6/9", ChordType::SixNine, vec!["C", "E", "G", "A", "D"]),
+            ("CmM7", ChordType::MinorMajor7, vec!["C", "Eb", "G", "B"]),
+            ("C13", ChordType::Dominant13, vec!["C", "E", "G", "Bb", "D", "A"]),
+        ];
+        for (symbol, chord_type, expected) in cases {
+            let chord = symbol.parse::<Chord>().unwrap();
+            assert_eq!(chord.chord_type(), chord_type, "{}", symbol);
+            assert_eq!(chord.notes(), notes(&expected), "{}", symbol);
+        }
+
+        let chord = chord!("Cmaj7(#11)").unwrap();
+        assert_eq!(chord.notes(), notes(&["C", "E", "G", "B", "F#"]));
+        let chord = chord!("Cadd9/E").unwrap();
+        assert_eq!(chord.notes(), notes(&["C", "E", "G", "D"]));
+        assert_eq!(chord.bass(), note!("E").unwrap());
+        let chord = chord!("G7alt").unwrap();
+        assert_eq!(chord.notes(), notes(&["G", "B", "Db", "D#", "F", "Ab", "A#"]));
+
+        for symbol in ["", "H7", "Cmaj7#3", "C7b", "Cm7/X", "C/", "C7♭♭♭9", "C7♯𝄪5"] {
+            assert!(symbol.parse::<Chord>().is_err(), "{}", symbol);
+        }
+    }
+
+    #[test]
+    fn test_alterations() {
+        let c7 = chord!("C7").unwrap();
+        let chord = c7.clone().with_alteration("b9".parse().unwrap()).unwrap();
+        assert_eq!(chord.notes(), notes(&["C", "E", "G", "Bb", "Db"]));
+        for degree in [
+            ScaleDegree::new(9, Accidental::Flat(3)).unwrap(),
+            ScaleDegree::new(5, Accidental::Sharp(3)).unwrap(),
+            ScaleDegree::new(13, Accidental::Flat(2)).unwrap(),
+        ] {
+            assert_eq!(
+                c7.clone().with_alteration(degree),
+                Err(ChordError::InvalidChordAlteration(degree.to_string()).into())
+            );
+        }
+    }
+
+    #[test]
+    fn test_symbols_round_trip() {
+        for symbol in ["C", "F♯m7", "B♭maj7♯11", "G7alt", "D♭m7♭5/C♭", "E6/9/G♯", "Am(maj7)"]
+        {
+            let chord = symbol.parse::<Chord>().unwrap();
+            assert_eq!(chord.to_string().parse::<Chord>().unwrap(), chord, "{}", symbol);
+        }
+        assert_eq!(chord!("Bbmaj7#11").unwrap().to_string(), "B♭maj7♯11");
+        assert_eq!(chord!("G7(b9,#9,b5,#5)").unwrap().to_string(), "G7alt");
+        assert_eq!(chord!("Cadd9").unwrap().to_string(), "Cadd9");
+    }
+
+    #[test]
+    fn test_transpose() {
+        let chord = chord!("Dm7/C").unwrap().transposed_up(inv!("m3").unwrap());
+        assert_eq!(chord, chord!("Fm7/Eb").unwrap());
+    }
+
+    #[test]
+    fn test_chord_scales() {
+        let (major, c) = (Scale::major(), note!("C").unwrap());
+        let function = |s: &str| s.parse::<ChordFunction>().unwrap();
+        let degrees =
+            |s: &[&str]| s.iter().map(|d| d.parse().unwrap()).collect::<Vec<ScaleDegree>>();
+
+        let scales = chord!("Cmaj7").unwrap().chord_scales(&function("I"), &major, c).unwrap();
+        assert_eq!(scales[0].scale_type(), Some(ScaleType::Major.into()));
+        assert_eq!(scales[0].tensions(), &degrees(&["9", "13"]));
+        assert_eq!(scales[0].avoid_notes(), &notes(&["F"]));
+        assert_eq!(scales[1].scale_type(), Some(MajorMode::Lydian.into()));
+        assert_eq!(scales[1].tensions(), &degrees(&["9", "#11", "13"]));
+
+        let scales = chord!("Bm7b5").unwrap().chord_scales(&function("vii"), &major, c).unwrap();
+        assert_eq!(scales[0].scale_type(), Some(MajorMode::Locrian.into()));
+        assert_eq!(scales[0].avoid_notes(), &notes(&["C"]));
+
+        // Secondary dominants resolving to minor chords take the ninth and thirteenth
+        // of the minor key.
+        let scales = chord!("E7").unwrap().chord_scales(&function("V/vi"), &major, c).unwrap();
+        assert_eq!(scales[0].scale_type(), Some(HarmonicMinorMode::PhrygianDominant.into()));
+        assert_eq!(scales[0].notes(), &notes(&["E", "F", "G#", "A", "B", "C", "D"]));
+        let scales = chord!("D7").unwrap().chord_scales(&function("V/V"), &major, c).unwrap();
+        assert_eq!(scales[0].scale_type(), Some(MajorMode::Mixolydian.into()));
+
+        // Secondary functions read their degree in the same key as to_chord does.
+        let vii = function("vii/ii");
+        let chord = vii.to_chord(&major, c, true).unwrap();
+        assert_eq!(chord, chord!("C#o7").unwrap());
+        assert_eq!(chord.function_in(&major, c), Some(vii));
+        assert_eq!(
+            chord.chord_scales(&vii, &major, c).unwrap()[0].notes()[0],
+            note!("C#").unwrap()
+        );
+        let invalid = || ChordError::InvalidChordFunction("V/VII".to_string()).into();
+        assert_eq!(function("V/vii").to_chord(&major, c, false), Err(invalid()));
+        let scales = chord!("F#").unwrap().chord_scales(&function("V/vii"), &major, c);
+        assert_eq!(scales, Err(invalid()));
+        assert_eq!(chord!("F#").unwrap().function_in(&major, c).unwrap().to_string(), "♯IV");
+
+        // Backdoor dominant, borrowed from the parallel minor
+        let scales = chord!("Bb7").unwrap().chord_scales(&function("bVII"), &major, c).unwrap();
+        assert_eq!(scales[0].scale_type(), Some(MelodicMinorMode::LydianDominant.into()));
+
+        let altered = chord!("G7alt").unwrap().chord_scales(&function("V"), &major, c).unwrap();
+        assert_eq!(altered[0].scale_type(), Some(MelodicMinorMode::Altered.into()));
+        assert_eq!(altered[0].notes(), &notes(&["G", "Ab", "A#", "B", "Db", "D#", "F"]));
+
+        let minor = Scale::harmonic_minor();
+        let scales =
+            chord!("E7").unwrap().chord_scales(&function("V"), &minor, note!("A").unwrap());
+        assert_eq!(
+            scales.unwrap()[0].scale_type(),
+            Some(HarmonicMinorMode::PhrygianDominant.into())
+        );
+
+        let key = key!("Bb Eb").unwrap();
+        let scales = chord!("Cm7").unwrap().chord_scales_in_key(&function("ii"), &key).unwrap();
+        assert_eq!(scales[0].scale_type(), Some(MajorMode::Dorian.into()));
+        assert!(chord!("C7").unwrap().chord_scales(&function("V"), &major, c).is_err());
+    }
+
+    #[test]
+    fn test_functions() {
+        for function in ["I", "V/V", "bVII", "V/bVI", "#IV"] {
+            let parsed = function.parse::<ChordFunction>().unwrap();
+            assert_eq!(parsed.to_string().parse::<ChordFunction>().unwrap(), parsed);
+        }
+        assert_eq!("v/II".parse::<ChordFunction>(), "V/ii".parse::<ChordFunction>());
+        for function in ["", "VIII", "V/", "V/V/V", "X"] {
+            assert!(function.parse::<ChordFunction>().is_err(), "{}", function);
+        }
+    }
+
+    #[test]
+    fn test_transformations() {
+        use Transformation::*;
+        let pitch_classes = |chord: &Chord| {
+            let mut classes = chord
+                .notes()
+                .iter()
+                .map(|note| note.to_chromatic_scale_degree().rem_euclid(12))
+                .collect::<Vec<_>>();
+            classes.sort();
+            classes
+        };
+        for symbol in ["C", "Am", "F#", "Ebm", "Db", "G#m"] {
+            let chord = symbol.parse::<Chord>().unwrap();
+            for transformation in [P, L, R, N, S, H] {
+                let image = chord.transformed(transformation).unwrap();
+                let common = pitch_classes(&image)
+                    .iter()
+                    .filter(|class| pitch_classes(&chord).contains(class))
+                    .count();
+                let expected = match transformation {
+                    P | L | R => 2,
+                    N | S => 1,
+                    H => 0,
+                };
+                assert_eq!(common, expected, "{} {}", symbol, transformation);
+                assert_eq!(image.transformed(transformation).unwrap(), chord);
+                let mut plr = chord.clone();
+                for step in transformation.to_plr() {
+                    plr = plr.transformed(step).unwrap();
+                }
+                assert_eq!(pitch_classes(&plr), pitch_classes(&image));
+            }
+        }
+
+        let c = chord!("C").unwrap();
+        for symbol in ["C", "Cm", "F#", "Ebm", "G#m", "Bb"] {
+            let target = symbol.parse::<Chord>().unwrap();
+            let path = c.transformation_path(&target).unwrap();
+            assert!(path.len() <= 5);
+            let reached = path.iter().try_fold(c.clone(), |chord, t| chord.transformed(*t));
+            assert_eq!(pitch_classes(&reached.unwrap()), pitch_classes(&target));
+        }
+        assert_eq!(c.transformation_path(&chord!("Em").unwrap()).unwrap(), vec![L]);
+        assert!(c.transformation_path(&chord!("C7").unwrap()).is_err());
+        assert!(c.transformed_by("PQ").is_err());
+
+        for symbol in ["C", "Am", "F#", "Ebm"] {
+            let chord = symbol.parse::<Chord>().unwrap();
+            let triangle = chord.tonnetz_triangle().unwrap();
+            let notes = triangle.iter().map(|position| position.to_note()).collect::<Vec<_>>();
+            let classes = notes
+                .iter()
+                .map(|note| note.to_chromatic_scale_degree().rem_euclid(12))
+                .collect::<Vec<_>>();
+            let expected = chord
+                .notes()
+                .iter()
+                .map(|note| note.to_chromatic_scale_degree().rem_euclid(12))
+                .collect::<Vec<_>>();
+            assert_eq!(classes, expected);
+        }
+    }
+
+    #[test]
+    fn test_figured_bass() {
+        for figured in ["G2", "G2 6/4", "E3 #6/4/2", "C3 7/#", "A3 n6/5", "B2 6\\", "D3 bb7"] {
+            let parsed = figured.parse::<FiguredBass>().unwrap();
+            assert_eq!(parsed.to_string(), figured);
+        }
+        assert_eq!("B2 6+".parse::<FiguredBass>().unwrap().to_string(), "B2 6\\");
+        assert_eq!("B2 6#".parse::<FiguredBass>().unwrap().to_string(), "B2 #6");
+        for figured in ["", "G2 1", "G2 10", "G2 x", "G2 #6b", "G2 6 5", "G2 6//4", "H2 6"] {
+            assert!(figured.parse::<FiguredBass>().is_err(), "{}", figured);
+        }
+
+        // Sharps and flats raise and lower the note of the key, and naturals cancel it.
+        let key = key!("Bb").unwrap();
+        let realized = |figured: &str| figured.parse::<FiguredBass>().unwrap().realized(&key);
+        assert_eq!(realized("C3 7"), notes(&["C", "E", "G", "Bb"]));
+        assert_eq!(realized("C3 n7"), notes(&["C", "E", "G", "B"]));
+        assert_eq!(realized("C3 #7"), notes(&["C", "E", "G", "B"]));
+        assert_eq!(realized("D3 #"), notes(&["D", "F#", "A"]));
+        assert_eq!(realized("D3 b"), notes(&["D", "Fb", "A"]));
+        assert_eq!(realized("G2 6\\"), notes(&["G", "Bb", "E#"]));
+        assert_eq!(realized("E3 4/2"), notes(&["E", "F", "A", "C"]));
+        assert_eq!(realized("E3 2"), realized("E3 4/2"));
+
+        let key = key!("F# C#").unwrap();
+        let pitch_classes = |notes: Vec<Note>| {
+            let mut classes = notes
+                .iter()
+                .map(|note| note.to_chromatic_scale_degree().rem_euclid(12))
+                .collect::<Vec<_>>();
+            classes.sort();
+            classes.dedup();
+            classes
+        };
+        let chords = [("D", "F#2"), ("A7", "G2"), ("B7", "D#3"), ("C#dim7", "E3"), ("E9", "E2")];
+        for (symbol, bass) in chords {
+            let chord = symbol.parse::<Chord>().unwrap();
+            let bass = bass.parse::<PitchedNote>().unwrap();
+            let figured = FiguredBass::from_chord(&chord, bass, &key).unwrap();
+            let reparsed = figured.to_string().parse::<FiguredBass>().unwrap();
+            assert_eq!(pitch_classes(reparsed.realized(&key)), pitch_classes(chord.notes()));
+        }
+        let figured = FiguredBass::from_chord(&chord!("A7").unwrap(), pnote!("G2").unwrap(), &key);
+        assert_eq!(figured.unwrap().to_string(), "G2 4/2");
+        let figured = FiguredBass::from_chord(&chord!("E9").unwrap(), pnote!("E2").unwrap(), &key);
+        assert_eq!(figured.unwrap().to_string(), "E2 9/7/#");
+
+        let figured: FiguredBass = "E2 9/7/#".parse().unwrap();
+        let voiced = figured.voiced(&key, pnote!("C4").unwrap()).unwrap();
+        assert_eq!(voiced, ["E2", "D4", "F#4", "G#4"].map(|n| n.parse().unwrap()));
+        let figured: FiguredBass = "D3".parse().unwrap();
+        let voiced = figured.voiced(&key, pnote!("C3").unwrap()).unwrap();
+        assert_eq!(voiced, ["D3", "F#3", "A3", "D4"].map(|n| n.parse().unwrap()));
+    }
+}
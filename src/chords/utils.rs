@@ -0,0 +1,354 @@
+use super::*;
+use crate::{err, TransposeDown, TransposeUp};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::{
+    fmt::{self, Debug, Display, Formatter},
+    str::FromStr,
+};
+
+lazy_static! {
+    static ref CHORD_RE: Regex =
+        Regex::new("^([A-G][#x𝄪b♯♭♮]*)(.*?)(?:/([A-G][#x𝄪b♯♭♮]*))?$").unwrap();
+    static ref ALTERATION_RE: Regex = Regex::new(r"^(add|b|#)?(\d+)").unwrap();
+    static ref FIGURE_RE: Regex = Regex::new(r"^([#b♯♭n♮]*)(\d?)([#b♯♭n♮]*)([\\+]?)$").unwrap();
+}
+
+/// Accepted spellings of each chord type suffix, after ♭ and ♯ are read as b and #.
+static SUFFIXES: &[(ChordType, &[&str])] = &[
+    (ChordType::Major, &["", "maj", "M"]),
+    (ChordType::Minor, &["m", "min", "-"]),
+    (ChordType::Diminished, &["dim", "o", "°"]),
+    (ChordType::Augmented, &["aug", "+"]),
+    (ChordType::Suspended2, &["sus2"]),
+    (ChordType::Suspended4, &["sus4", "sus"]),
+    (ChordType::Power, &["5"]),
+    (ChordType::Major6, &["6", "maj6", "M6"]),
+    (ChordType::Minor6, &["m6", "min6", "-6"]),
+    (ChordType::SixNine, &["6/9", "69"]),
+    (ChordType::Dominant7, &["7", "dom7"]),
+    (ChordType::Major7, &["maj7", "M7", "Δ7", "Δ"]),
+    (ChordType::Minor7, &["m7", "min7", "-7"]),
+    (ChordType::MinorMajor7, &["m(maj7)", "mmaj7", "mM7", "minmaj7", "-maj7", "-Δ7"]),
+    (ChordType::HalfDiminished7, &["m7b5", "min7b5", "-7b5", "ø7", "ø"]),
+    (ChordType::Diminished7, &["dim7", "o7", "°7"]),
+    (ChordType::Dominant7Sus4, &["7sus4", "7sus"]),
+    (ChordType::Dominant9, &["9"]),
+    (ChordType::Major9, &["maj9", "M9", "Δ9"]),
+    (ChordType::Minor9, &["m9", "min9", "-9"]),
+    (ChordType::Dominant11, &["11"]),
+    (ChordType::Minor11, &["m11", "min11", "-11"]),
+    (ChordType::Dominant13, &["13"]),
+    (ChordType::Major13, &["maj13", "M13", "Δ13"]),
+    (ChordType::Minor13, &["m13", "min13", "-13"]),
+];
+
+/// The alterations written as "alt".
+const ALT: [(u8, i32); 4] = [(5, -1), (5, 1), (9, -1), (9, 1)];
+
+impl FromStr for Chord {
+    type Err = ResonataError;
+
+    /// Parses a chord symbol, such as "C", "F#m7", "Bbmaj7#11", "G7alt" or "C/E".
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let invalid = || ChordError::InvalidChordSymbol(s.to_string());
+        let Some(cap) = CHORD_RE.captures(s.trim()) else {
+            return err!(invalid());
+        };
+        let root = cap[1].parse::<Note>()?;
+        let suffix = cap[2].replace('♭', "b").replace('♯', "#");
+
+        let (chord_type, alias) = SUFFIXES
+            .iter()
+            .flat_map(|(chord_type, aliases)| {
+                aliases.iter().map(move |alias| (*chord_type, *alias))
+            })
+            .filter(|(_, alias)| suffix.starts_with(alias))
+            .max_by_key(|(_, alias)| alias.len())
+            .ok_or_else(invalid)?;
+
+        let mut chord = Chord::new(root, chord_type);
+        let mut rest = suffix[alias.len()..].replace(['(', ')', ',', ' '], "");
+        while !rest.is_empty() {
+            if let Some(tail) = rest.strip_prefix("alt") {
+                for (number, semitones) in ALT {
+                    let degree = ScaleDegree::new(number, Accidental::from_semitones(semitones))?;
+                    chord = chord.with_alteration(degree)?;
+                }
+                rest = tail.to_string();
+                continue;
+            }
+            let cap = ALTERATION_RE.captures(&rest).ok_or_else(invalid)?;
+            let accidental = match cap.get(1).map(|m| m.as_str()) {
+                Some("b") => Accidental::Flat(1),
+                Some("#") => Accidental::Sharp(1),
+                _ => Accidental::Natural,
+            };
+            let number = cap[2].parse::<u8>().map_err(|_| invalid())?;
+            chord = chord.with_alteration(ScaleDegree::new(number, accidental)?)?;
+            rest = rest[cap[0].len()..].to_string();
+        }
+
+        match cap.get(3) {
+            Some(bass) => Ok(chord.with_bass(bass.as_str().parse()?)),
+            None => Ok(chord),
+        }
+    }
+}
+
+impl Display for ChordType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        use ChordType::*;
+        let suffix = match self {
+            Major => "",
+            Minor => "m",
+            Diminished => "dim",
+            Augmented => "aug",
+            Suspended2 => "sus2",
+            Suspended4 => "sus4",
+            Power => "5",
+            Major6 => "6",
+            Minor6 => "m6",
+            SixNine => "6/9",
+            Dominant7 => "7",
+            Major7 => "maj7",
+            Minor7 => "m7",
+            MinorMajor7 => "m(maj7)",
+            HalfDiminished7 => "m7♭5",
+            Diminished7 => "dim7",
+            Dominant7Sus4 => "7sus4",
+            Dominant9 => "9",
+            Major9 => "maj9",
+            Minor9 => "m9",
+            Dominant11 => "11",
+            Minor11 => "m11",
+            Dominant13 => "13",
+            Major13 => "maj13",
+            Minor13 => "m13",
+        };
+        write!(f, "{}", suffix)
+    }
+}
+
+impl Display for Chord {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.root, self.chord_type)?;
+        let alt = ALT.map(|(number, semitones)| {
+            ScaleDegree::new(number, Accidental::from_semitones(semitones)).unwrap()
+        });
+        if self.chord_type == ChordType::Dominant7 && self.alterations == alt {
+            write!(f, "alt")?;
+        } else {
+            for degree in &self.alterations {
+                match degree.is_diatonic() {
+                    true => write!(f, "add{}", degree)?,
+                    false => write!(f, "{}", degree)?,
+                }
+            }
+        }
+        if let Some(bass) = self.bass {
+            write!(f, "/{}", bass)?;
+        }
+        Ok(())
+    }
+}
+
+impl Debug for Chord {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+impl TransposeUp for Chord {
+    type Output = Chord;
+    fn transposed_up(&self, interval: Interval) -> Self::Output {
+        Chord {
+            root: self.root + interval,
+            chord_type: self.chord_type,
+            alterations: self.alterations.clone(),
+            bass: self.bass.map(|bass| bass + interval),
+        }
+    }
+}
+
+impl TransposeDown for Chord {
+    type Output = Chord;
+    fn transposed_down(&self, interval: Interval) -> Self::Output {
+        Chord {
+            root: self.root - interval,
+            chord_type: self.chord_type,
+            alterations: self.alterations.clone(),
+            bass: self.bass.map(|bass| bass - interval),
+        }
+    }
+}
+
+lazy_static! {
+    static ref FUNCTION_RE: Regex = Regex::new("^([b#♭♯]?)([IViv]+)$").unwrap();
+}
+
+static NUMERALS: [&str; 7] = ["I", "II", "III", "IV", "V", "VI", "VII"];
+
+/// Parses a Roman numeral, with an optional flat or sharp, as a scale degree.
+fn parse_numeral(s: &str) -> Option<ScaleDegree> {
+    let cap = FUNCTION_RE.captures(s)?;
+    let accidental = match &cap[1] {
+        "b" | "♭" => Accidental::Flat(1),
+        "#" | "♯" => Accidental::Sharp(1),
+        _ => Accidental::Natural,
+    };
+    let number = NUMERALS.iter().position(|numeral| *numeral == cap[2].to_uppercase())?;
+    ScaleDegree::new(number as u8 + 1, accidental).ok()
+}
+
+impl FromStr for ChordFunction {
+    type Err = ResonataError;
+
+    /// Parses a function such as "V", "bVII" or "V/ii".
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let invalid = || ChordError::InvalidChordFunction(s.to_string());
+        let mut parts = s.trim().split('/');
+        let degree = parts.next().and_then(parse_numeral).ok_or_else(invalid)?;
+        let function = match parts.next() {
+            Some(target) => {
+                ChordFunction::secondary(degree, parse_numeral(target).ok_or_else(invalid)?)
+            }
+            None => ChordFunction::new(degree),
+        };
+        match parts.next() {
+            Some(_) => err!(invalid()),
+            None => Ok(function),
+        }
+    }
+}
+
+impl Display for ChordFunction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let numeral = |degree: ScaleDegree| {
+            format!("{}{}", degree.accidental(), NUMERALS[(degree.number() as usize - 1) % 7])
+        };
+        write!(f, "{}", numeral(self.degree()))?;
+        if self.is_secondary() {
+            write!(f, "/{}", numeral(self.target()))?;
+        }
+        Ok(())
+    }
+}
+
+impl Debug for ChordFunction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+impl FromStr for Transformation {
+    type Err = ResonataError;
+
+    /// Parses a single transformation, "P", "L", "R", "N", "S" or "H".
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.trim().to_uppercase().as_str() {
+            "P" => Ok(Transformation::P),
+            "L" => Ok(Transformation::L),
+            "R" => Ok(Transformation::R),
+            "N" => Ok(Transformation::N),
+            "S" => Ok(Transformation::S),
+            "H" => Ok(Transformation::H),
+            _ => err!(ChordError::InvalidTransformation(s.to_string())),
+        }
+    }
+}
+
+impl Display for Transformation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let letter = match self {
+            Transformation::P => "P",
+            Transformation::L => "L",
+            Transformation::R => "R",
+            Transformation::N => "N",
+            Transformation::S => "S",
+            Transformation::H => "H",
+        };
+        write!(f, "{}", letter)
+    }
+}
+
+/// Parses the accidental of a figure, where a sharp or flat is relative to the key.
+fn parse_figure_accidental(s: &str) -> Option<Option<Accidental>> {
+    let s = s.replace('♯', "#").replace('♭', "b").replace('♮', "n");
+    match s.as_str() {
+        "" => Some(None),
+        "n" => Some(Some(Accidental::Natural)),
+        _ if s.chars().all(|c| c == '#') => Some(Some(Accidental::Sharp(s.len() as u8))),
+        _ if s.chars().all(|c| c == 'b') => Some(Some(Accidental::Flat(s.len() as u8))),
+        _ => None,
+    }
+}
+
+impl FromStr for Figure {
+    type Err = ResonataError;
+
+    /// Parses a figure, such as "6", "#6", "6#", "b7", "n", "#" or a slashed "6\\" or "6+".
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let invalid = || ChordError::InvalidFigure(s.to_string());
+        let Some(cap) = FIGURE_RE.captures(s.trim()) else {
+            return err!(invalid());
+        };
+        let accidental = match (&cap[1], &cap[3]) {
+            (before, "") => parse_figure_accidental(before),
+            ("", after) if !cap[2].is_empty() => parse_figure_accidental(after),
+            _ => None,
+        };
+        let Some(accidental) = accidental else {
+            return err!(invalid());
+        };
+        let number = cap[2].parse::<u8>().ok();
+        Figure::new(number, accidental, !cap[4].is_empty()).map_err(|_| invalid().into())
+    }
+}
+
+impl Display for Figure {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let accidental = match self.accidental() {
+            Some(Accidental::Natural) => "n".to_string(),
+            Some(Accidental::Sharp(n)) => "#".repeat(n as usize),
+            Some(Accidental::Flat(n)) => "b".repeat(n as usize),
+            None => String::new(),
+        };
+        let number = self.is_accidental_only().then(String::new);
+        let number = number.unwrap_or_else(|| self.number().to_string());
+        let slash = if self.is_slashed() { "\\" } else { "" };
+        write!(f, "{}{}{}", accidental, number, slash)
+    }
+}
+
+impl FromStr for FiguredBass {
+    type Err = ResonataError;
+
+    /// Parses a bass note followed by its figures from the top down, separated by
+    /// slashes, such as "G2 6/4" or "D3 #". A bass note alone is a 5/3 chord.
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let mut parts = s.split_whitespace();
+        let Some(bass) = parts.next() else {
+            return err!(ChordError::InvalidFigure(s.to_string()));
+        };
+        let bass = bass.parse::<PitchedNote>()?;
+        let figures = match parts.next() {
+            Some(figures) => figures.split('/').map(|f| f.parse()).collect::<Result<Vec<_>>>()?,
+            None => Vec::new(),
+        };
+        if parts.next().is_some() {
+            return err!(ChordError::InvalidFigure(s.to_string()));
+        }
+        Ok(FiguredBass::new(bass, figures))
+    }
+}
+
+impl Display for FiguredBass {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let figures = self.figures().iter().map(|figure| figure.to_string()).collect::<Vec<_>>();
+        match figures.is_empty() {
+            true => write!(f, "{}", self.bass()),
+            false => write!(f, "{} {}", self.bass(), figures.join("/")),
+        }
+    }
+}
@@ -0,0 +1,214 @@
+use crate::{
+    durations::{Duration, Event},
+    error::{CounterpointError, ResonataError},
+    keys::Key,
+    melodies::Melody,
+    nope,
+    notes::*,
+};
+use rand::{rngs::StdRng, SeedableRng};
+
+mod generator;
+mod rules;
+mod tests;
+mod utils;
+
+type Result<T> = std::result::Result<T, ResonataError>;
+
+/// The five species of counterpoint against a cantus firmus, after Fux.
+///
+/// - `First` sets one note against each note of the cantus.
+/// - `Second` sets two half-measure notes against each, usually after a half rest.
+/// - `Third` sets four quarter-measure notes against each.
+/// - `Fourth` sets half-measure notes after a half rest, tied over the barline so that
+///   the downbeats may be prepared suspensions. A tie is written as the same pitch
+///   repeated across the barline.
+/// - `Fifth`, or florid counterpoint, mixes the rhythms of the other species.
+///
+/// Every species ends on a note as long as a measure. A measure is as long as a note
+/// of the cantus, so a cantus in whole notes gives half notes in second species.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Species {
+    First,
+    Second,
+    Third,
+    Fourth,
+    Fifth,
+}
+
+/// Whether the counterpoint is above or below the cantus firmus.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Placement {
+    Above,
+    Below,
+}
+
+/// A rule of species counterpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Rule {
+    /// The rhythm does not fit the species, or a note crosses a barline.
+    Rhythm,
+    /// The counterpoint does not begin on a perfect consonance; below the cantus only a
+    /// unison or octave will do.
+    BadBeginning,
+    /// The counterpoint does not end on a unison or octave reached by step in
+    /// contrary motion.
+    BadEnding,
+    /// A dissonance that is not a passing or neighbour note on a weak beat, or a
+    /// suspension on a downbeat where the species allows one.
+    Dissonance,
+    /// A suspension that does not resolve down by step to a consonance.
+    UnresolvedSuspension,
+    /// Fifths or octaves in parallel, between consecutive notes or consecutive
+    /// strong beats.
+    ParallelPerfect,
+    /// A fifth or octave approached in similar motion with a leap in the upper voice.
+    HiddenPerfect,
+    /// A unison on a downbeat other than the first or last.
+    Unison,
+    /// The counterpoint crosses to the other side of the cantus.
+    VoiceCrossing,
+    /// The voices are more than a twelfth apart.
+    Spacing,
+    /// A melodic leap of a dissonant or augmented interval, a sixth other than an
+    /// ascending minor sixth, or more than an octave.
+    ForbiddenLeap,
+    /// A leap of a sixth or octave not followed by a step back, or two leaps in the
+    /// same direction spanning more than an octave.
+    UnrecoveredLeap,
+    /// A note repeated other than tied over a barline.
+    RepeatedNote,
+}
+
+/// A broken rule of counterpoint, with the measure it is in, counting from 1, and the
+/// offset of the offending note from the start of that measure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Violation {
+    rule: Rule,
+    measure: usize,
+    offset: Duration,
+}
+
+impl Violation {
+    /// Creates a violation of a rule at a measure and offset.
+    pub fn new(rule: Rule, measure: usize, offset: Duration) -> Self {
+        Violation { rule, measure, offset }
+    }
+
+    /// Returns the rule that was broken.
+    pub fn rule(&self) -> Rule {
+        self.rule
+    }
+
+    /// Returns the measure of the violation, counting from 1.
+    pub fn measure(&self) -> usize {
+        self.measure
+    }
+
+    /// Returns the offset of the violation from the start of its measure.
+    pub fn offset(&self) -> Duration {
+        self.offset
+    }
+}
+
+impl Species {
+    /// Checks a counterpoint against a cantus firmus, and returns the broken rules in
+    /// the order they occur. The cantus must be a line of notes of equal length, each
+    /// of which makes a measure, and the counterpoint must last as long as the cantus.
+    /// Harmonic intervals are measured in semitones, and the perfect fourth counts as
+    /// a dissonance, as it does in two voices.
+    ///
+    /// ### Failures
+    /// Returns an error if the cantus is empty, holds rests or notes of different
+    /// lengths, if the counterpoint has a different length or only rests, or if it has
+    /// notes that are not a whole number of eighths of a measure long.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::{durations::*, melodies::*, counterpoint::*};
+    ///
+    /// let cantus = melody!("C4:1 D4:1 E4:1 D4:1 C4:1").unwrap();
+    /// let counterpoint = melody!("G4:1 A4:1 B4:1 B4:1 C5:1").unwrap();
+    /// let violations = Species::First.check(&cantus, &counterpoint, Placement::Above).unwrap();
+    /// assert_eq!(violations.len(), 3);
+    /// assert_eq!(violations[0].rule(), Rule::ParallelPerfect);
+    /// assert_eq!(violations[0].measure(), 2);
+    /// assert_eq!(violations[2], Violation::new(Rule::RepeatedNote, 4, Duration::zero()));
+    /// assert_eq!(violations[2].to_string(), "measure 4, 0/1: repeated note");
+    ///
+    /// let counterpoint = melody!("G4:1 A4:1 B4:1 C5:1").unwrap();
+    /// assert!(Species::First.check(&cantus, &counterpoint, Placement::Above).is_err());
+    /// ```
+    pub fn check(
+        &self,
+        cantus: &Melody,
+        counterpoint: &Melody,
+        placement: Placement,
+    ) -> Result<Vec<Violation>> {
+        let measure = rules::measure(cantus)?;
+        if counterpoint.duration() != cantus.duration() {
+            nope!(CounterpointError::LengthMismatch(cantus.duration(), counterpoint.duration()));
+        }
+        let cantus = rules::place(cantus, measure)?;
+        let line = rules::place(counterpoint, measure)?;
+        if line.is_empty() {
+            nope!(CounterpointError::EmptyCounterpoint);
+        }
+        let violations = rules::check(&cantus, &line, *self, placement, true);
+        Ok(violations
+            .into_iter()
+            .map(|(tick, rule)| {
+                let (bar, offset) = (tick / rules::TICKS, tick % rules::TICKS);
+                Violation::new(
+                    rule,
+                    bar as usize + 1,
+                    measure.scaled(offset, rules::TICKS).unwrap(),
+                )
+            })
+            .collect())
+    }
+
+    /// Generates a counterpoint to a cantus firmus that breaks none of the rules
+    /// checked by [Species::check], using the pitches of the key. The search is
+    /// random, but the same seed always gives the same counterpoint.
+    ///
+    /// ### Failures
+    /// Returns an error if the cantus is not a line of notes of equal length, or if no
+    /// counterpoint is found within the search limit.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::{keys::*, melodies::*, counterpoint::*};
+    ///
+    /// let cantus = melody!("D4:1 F4:1 E4:1 D4:1 G4:1 F4:1 A4:1 G4:1 F4:1 E4:1 D4:1").unwrap();
+    /// let key = key!("C D E F G A B").unwrap();
+    /// for species in [Species::First, Species::Second, Species::Fourth] {
+    ///     let counterpoint = species.generate(&cantus, &key, Placement::Above, 7).unwrap();
+    ///     assert!(species.check(&cantus, &counterpoint, Placement::Above).unwrap().is_empty());
+    ///     assert_eq!(species.generate(&cantus, &key, Placement::Above, 7).unwrap(), counterpoint);
+    /// }
+    /// ```
+    pub fn generate(
+        &self,
+        cantus: &Melody,
+        key: &Key,
+        placement: Placement,
+        seed: u64,
+    ) -> Result<Melody> {
+        let measure = rules::measure(cantus)?;
+        let cantus = rules::place(cantus, measure)?;
+        let mut rng = StdRng::seed_from_u64(seed);
+        let Some(line) = generator::generate(&cantus, key, *self, placement, &mut rng) else {
+            nope!(CounterpointError::NoCounterpoint);
+        };
+
+        let mut melody = Melody::default();
+        if line[0].start > 0 {
+            melody.push(Event::Rest(measure.scaled(line[0].start, rules::TICKS)?));
+        }
+        for note in line {
+            melody.push(Event::Note(note.pitch, measure.scaled(note.length, rules::TICKS)?));
+        }
+        Ok(melody)
+    }
+}
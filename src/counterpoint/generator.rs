@@ -0,0 +1,118 @@
+use super::{rules::*, *};
+use rand::{seq::SliceRandom, Rng};
+
+/// The number of notes the search may try before giving up.
+const SEARCH_LIMIT: usize = 200_000;
+
+/// The rhythms a measure of florid counterpoint may take, in ticks.
+const FLORID_RHYTHMS: [&[u32]; 5] = [&[4, 4], &[2, 2, 2, 2], &[4, 2, 2], &[2, 2, 4], &[4, 2, 1, 1]];
+
+/// Returns the start and length of every note of the counterpoint, in ticks.
+fn rhythm(species: Species, measures: u32, rng: &mut StdRng) -> Vec<(u32, u32)> {
+    let mut slots = Vec::new();
+    for bar in 0..measures {
+        let lengths: &[u32] = match species {
+            _ if bar == measures - 1 => &[TICKS],
+            Species::First => &[TICKS],
+            Species::Second | Species::Fourth => &[TICKS / 2, TICKS / 2],
+            Species::Third => &[TICKS / 4; 4],
+            // Florid counterpoint begins like fourth species, to allow a suspension into
+            // the second measure, and leaves room for one before the cadence.
+            Species::Fifth if bar == 0 || bar == measures - 2 => &[TICKS / 2, TICKS / 2],
+            Species::Fifth => FLORID_RHYTHMS.choose(rng).unwrap(),
+        };
+        let mut start = bar * TICKS;
+        for length in lengths {
+            slots.push((start, *length));
+            start += length;
+        }
+    }
+    // Second, fourth and fifth species begin after a half rest.
+    if measures > 1 && matches!(species, Species::Second | Species::Fourth | Species::Fifth) {
+        slots.remove(0);
+    }
+    slots
+}
+
+/// Returns the pitches of the key that the counterpoint may take against a note of the
+/// cantus, from a unison to a tenth away on its side.
+fn candidates(cantus: PitchedNote, key: &Key, placement: Placement) -> Vec<PitchedNote> {
+    let midi = cantus.to_midi_number() as i32;
+    let (low, high) = match placement {
+        Placement::Above => (midi, midi + 16),
+        Placement::Below => (midi - 16, midi),
+    };
+    let mut pitches = Vec::new();
+    for octave in 0..=9 {
+        for note in key.pitches() {
+            if let Ok(pitch) = note.with_octave(octave) {
+                if (low..=high).contains(&(pitch.to_midi_number() as i32)) {
+                    pitches.push(pitch);
+                }
+            }
+        }
+    }
+    pitches
+}
+
+/// Searches depth first for a counterpoint that breaks no rules, trying the pitches of
+/// each note in a random order that favours steps.
+pub(super) fn generate(
+    cantus: &[Placed],
+    key: &Key,
+    species: Species,
+    placement: Placement,
+    rng: &mut StdRng,
+) -> Option<Vec<Placed>> {
+    let slots = rhythm(species, cantus.len() as u32, rng);
+    let mut line: Vec<Placed> = Vec::new();
+    let mut options: Vec<Vec<PitchedNote>> = Vec::new();
+    let mut tried = 0;
+
+    loop {
+        if line.len() == slots.len() {
+            return Some(line);
+        }
+        if options.len() == line.len() {
+            let start = slots[line.len()].0;
+            let mut pitches = candidates(cantus[(start / TICKS) as usize].pitch, key, placement);
+            pitches.shuffle(rng);
+            let previous = line.last().map(|note| note.pitch);
+            let mut ranked = pitches
+                .into_iter()
+                .map(|pitch| {
+                    let leap = previous.map_or(0, |previous| previous.semitones_between(&pitch));
+                    let rank = match leap {
+                        0 if start.is_multiple_of(TICKS) => 0,
+                        1 | 2 => 0,
+                        3 | 4 => 1,
+                        _ => 2,
+                    };
+                    (rank + rng.gen_range(0..2), pitch)
+                })
+                .collect::<Vec<_>>();
+            // The best candidate goes last, to be popped first.
+            ranked.sort_by_key(|(rank, _)| std::cmp::Reverse(*rank));
+            options.push(ranked.into_iter().map(|(_, pitch)| pitch).collect());
+        }
+
+        let (start, length) = slots[line.len()];
+        match options.last_mut().unwrap().pop() {
+            Some(pitch) => {
+                tried += 1;
+                if tried > SEARCH_LIMIT {
+                    return None;
+                }
+                line.push(Placed { start, length, pitch });
+                let complete = line.len() == slots.len();
+                if !check(cantus, &line, species, placement, complete).is_empty() {
+                    line.pop();
+                }
+            }
+            None => {
+                options.pop();
+                line.pop()?;
+            }
+        }
+    }
+}
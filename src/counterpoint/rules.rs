@@ -0,0 +1,322 @@
+use super::*;
+use crate::intervals::Quality;
+
+/// The number of ticks in a measure, the finest division of the measure used by the
+/// species.
+pub(super) const TICKS: u32 = 8;
+
+/// A note placed in time, in ticks from the start of the line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct Placed {
+    pub(super) start: u32,
+    pub(super) length: u32,
+    pub(super) pitch: PitchedNote,
+}
+
+impl Placed {
+    fn end(&self) -> u32 {
+        self.start + self.length
+    }
+
+    fn offset(&self) -> u32 {
+        self.start % TICKS
+    }
+
+    fn bar(&self) -> u32 {
+        self.start / TICKS
+    }
+}
+
+/// Returns the length of a measure, the length of every note of the cantus.
+pub(super) fn measure(cantus: &Melody) -> Result<Duration> {
+    let Some(first) = cantus.events().first() else {
+        nope!(CounterpointError::InvalidCantus);
+    };
+    let measure = first.duration();
+    if measure.is_zero() || cantus.events().iter().any(|e| e.is_rest() || e.duration() != measure) {
+        nope!(CounterpointError::InvalidCantus);
+    }
+    Ok(measure)
+}
+
+/// Places the notes of a melody in ticks, skipping rests.
+pub(super) fn place(melody: &Melody, measure: Duration) -> Result<Vec<Placed>> {
+    let mut notes = Vec::new();
+    let mut start = 0;
+    for event in melody.events() {
+        let (numerator, denominator) = event.duration().ratio_to(&measure)?;
+        if !(numerator * TICKS).is_multiple_of(denominator) {
+            nope!(CounterpointError::UnsupportedRhythm(event.duration()));
+        }
+        let length = numerator * TICKS / denominator;
+        if let Event::Note(pitch, _) = event {
+            notes.push(Placed { start, length, pitch: *pitch });
+        }
+        start += length;
+    }
+    Ok(notes)
+}
+
+/// Returns the harmonic interval from the cantus to the counterpoint in semitones,
+/// negative if the voices have crossed.
+fn harmonic(note: &Placed, cantus: &Placed, placement: Placement) -> i32 {
+    match placement {
+        Placement::Above => cantus.pitch.semitones_to(&note.pitch),
+        Placement::Below => note.pitch.semitones_to(&cantus.pitch),
+    }
+}
+
+fn consonant(semitones: i32) -> bool {
+    matches!(semitones.abs() % 12, 0 | 3 | 4 | 7 | 8 | 9)
+}
+
+fn perfect(semitones: i32) -> bool {
+    matches!(semitones.abs() % 12, 0 | 7)
+}
+
+fn motion(from: &Placed, to: &Placed) -> i32 {
+    from.pitch.semitones_to(&to.pitch)
+}
+
+fn is_step(semitones: i32) -> bool {
+    matches!(semitones.abs(), 1 | 2)
+}
+
+/// Returns true if a melodic interval may be sung in strict counterpoint.
+fn allowed_leap(from: &Placed, to: &Placed) -> bool {
+    let semitones = motion(from, to);
+    if !matches!(semitones, -7..=7 | 8 | 12 | -12) || semitones.abs() == 6 {
+        return false;
+    }
+    let (low, high) = match semitones < 0 {
+        true => (to.pitch, from.pitch),
+        false => (from.pitch, to.pitch),
+    };
+    let quality = low.interval_to(&high).quality();
+    !matches!(quality, Quality::Augmented(_) | Quality::Diminished(_))
+}
+
+/// Returns true if a note fits the rhythm of the species, given its neighbours.
+fn fits_rhythm(
+    line: &[Placed],
+    index: usize,
+    species: Species,
+    last_bar: u32,
+    complete: bool,
+) -> bool {
+    let note = &line[index];
+    let (offset, length) = (note.offset(), note.length);
+    if offset + length > TICKS {
+        return false;
+    }
+    if index > 0 && line[index - 1].end() != note.start {
+        return false;
+    }
+    if note.bar() == last_bar {
+        return offset == 0 && length == TICKS;
+    }
+    if index == 0 {
+        // Second and third species may begin after a rest, and fourth species must.
+        let starts: &[u32] = match species {
+            Species::First => &[0],
+            Species::Second | Species::Fifth => &[0, TICKS / 2],
+            Species::Third => &[0, TICKS / 4],
+            Species::Fourth => &[TICKS / 2],
+        };
+        if !starts.contains(&note.start) {
+            return false;
+        }
+    }
+    match species {
+        Species::First => length == TICKS,
+        Species::Second | Species::Fourth => length == TICKS / 2,
+        Species::Third => length == TICKS / 4,
+        Species::Fifth => match length {
+            8 | 4 | 2 => offset % length == 0,
+            1 if offset % 4 == 2 => match line.get(index + 1) {
+                Some(next) => next.length == 1,
+                None => !complete,
+            },
+            1 if offset % 4 == 3 => line[index - 1].length == 1,
+            _ => false,
+        },
+    }
+}
+
+/// Checks the counterpoint against the cantus, and returns the tick of each broken
+/// rule, in order. An incomplete counterpoint is a beginning still being written, and
+/// rules that depend on the notes to come are left until they are written.
+pub(super) fn check(
+    cantus: &[Placed],
+    line: &[Placed],
+    species: Species,
+    placement: Placement,
+    complete: bool,
+) -> Vec<(u32, Rule)> {
+    let mut violations = Vec::new();
+    let last_bar = cantus.len() as u32 - 1;
+    let cantus_at = |note: &Placed| &cantus[(note.bar() as usize).min(cantus.len() - 1)];
+    let ties = matches!(species, Species::Fourth | Species::Fifth);
+
+    for (index, note) in line.iter().enumerate() {
+        let mut broken = |rule| violations.push((note.start, rule));
+        let below = cantus_at(note);
+        let interval = harmonic(note, below, placement);
+        let previous = index.checked_sub(1).map(|i| &line[i]);
+        let next = line.get(index + 1);
+
+        if !fits_rhythm(line, index, species, last_bar, complete) {
+            broken(Rule::Rhythm);
+        }
+        let unison_or_octave = interval % 12 == 0;
+        if index == 0
+            && (interval < 0
+                || !perfect(interval)
+                || (placement == Placement::Below && !unison_or_octave))
+        {
+            broken(Rule::BadBeginning);
+        }
+        if complete && index == line.len() - 1 {
+            let cadence = previous.is_some_and(|previous| {
+                let step = motion(previous, note);
+                let cantus_step = motion(cantus_at(previous), below);
+                is_step(step) && cantus_step.signum() != step.signum()
+            });
+            if !unison_or_octave || !cadence {
+                broken(Rule::BadEnding);
+            }
+        }
+
+        if !consonant(interval) {
+            let tied = previous.is_some_and(|p| p.pitch == note.pitch && p.end() == note.start);
+            let resolved = |next: &Placed| {
+                matches!(motion(note, next), -2 | -1)
+                    && consonant(harmonic(next, cantus_at(next), placement))
+            };
+            let stepwise = |from: Option<&Placed>, to: Option<&Placed>| match (from, to) {
+                (Some(from), Some(to)) => is_step(motion(from, to)),
+                (Some(_), None) => !complete,
+                _ => false,
+            };
+            let passing = match (previous, next) {
+                (Some(previous), Some(next)) => {
+                    motion(previous, note).signum() == motion(note, next).signum()
+                }
+                _ => true,
+            };
+            let through = stepwise(previous, Some(note)) && stepwise(Some(note), next);
+            if note.offset() == 0 && ties && tied {
+                let prepared =
+                    previous.is_some_and(|p| consonant(harmonic(p, cantus_at(p), placement)));
+                if !prepared {
+                    broken(Rule::Dissonance);
+                } else if next.map_or(complete, |next| !resolved(next)) {
+                    broken(Rule::UnresolvedSuspension);
+                }
+            } else {
+                let allowed = note.offset() != 0
+                    && match species {
+                        Species::First | Species::Fourth => false,
+                        Species::Second => through && passing,
+                        Species::Third | Species::Fifth => through,
+                    };
+                if !allowed {
+                    broken(Rule::Dissonance);
+                }
+            }
+        }
+        if interval == 0 && note.offset() == 0 && note.bar() != 0 && note.bar() != last_bar {
+            broken(Rule::Unison);
+        }
+        if interval < 0 {
+            broken(Rule::VoiceCrossing);
+        }
+        if interval > 19 {
+            broken(Rule::Spacing);
+        }
+
+        if let Some(previous) = previous {
+            let step = motion(previous, note);
+            if !allowed_leap(previous, note) {
+                broken(Rule::ForbiddenLeap);
+            }
+            if step == 0 && !(ties && note.offset() == 0) {
+                broken(Rule::RepeatedNote);
+            }
+            if let Some(before) = index.checked_sub(2).map(|i| &line[i]) {
+                let leap = motion(before, previous);
+                let recovered = step != 0 && step.signum() != leap.signum() && is_step(step);
+                let compound = leap.abs() > 2 && step.abs() > 2 && leap.signum() == step.signum();
+                if (leap.abs() >= 8 && !recovered) || (compound && (leap + step).abs() > 12) {
+                    broken(Rule::UnrecoveredLeap);
+                }
+            }
+        }
+    }
+
+    motion_violations(cantus, line, species, placement, &mut violations);
+    violations.sort_by_key(|(tick, _)| *tick);
+    violations.dedup();
+    violations
+}
+
+/// Finds parallel and hidden fifths and octaves, between every pair of consecutive
+/// sonorities and between the strong beats of consecutive measures.
+fn motion_violations(
+    cantus: &[Placed],
+    line: &[Placed],
+    species: Species,
+    placement: Placement,
+    violations: &mut Vec<(u32, Rule)>,
+) {
+    let Some(end) = line.last().map(|note| note.end()) else {
+        return;
+    };
+    let sounding = |tick: u32| {
+        let note = line.iter().find(|note| note.start <= tick && tick < note.end())?;
+        Some((*note, cantus[(tick / TICKS) as usize]))
+    };
+    let compare = |from: (Placed, Placed), to: (Placed, Placed)| {
+        let (voice, cantus) = (motion(&from.0, &to.0), motion(&from.1, &to.1));
+        if voice == 0 || cantus == 0 || voice.signum() != cantus.signum() {
+            return None;
+        }
+        let (before, after) =
+            (harmonic(&from.0, &from.1, placement), harmonic(&to.0, &to.1, placement));
+        let upper = match placement {
+            Placement::Above => voice,
+            Placement::Below => cantus,
+        };
+        match perfect(after) {
+            true if before.rem_euclid(12) == after.rem_euclid(12) => Some(Rule::ParallelPerfect),
+            true if upper.abs() > 2 => Some(Rule::HiddenPerfect),
+            _ => None,
+        }
+    };
+
+    let mut ticks = line.iter().map(|note| note.start).collect::<Vec<_>>();
+    ticks.extend((0..cantus.len() as u32).map(|bar| bar * TICKS).filter(|tick| *tick < end));
+    ticks.sort();
+    ticks.dedup();
+    for pair in ticks.windows(2) {
+        if let (Some(from), Some(to)) = (sounding(pair[0]), sounding(pair[1])) {
+            if let Some(rule) = compare(from, to) {
+                violations.push((pair[1], rule));
+            }
+        }
+    }
+
+    let strong = match species {
+        Species::First => return,
+        Species::Fourth => TICKS / 2,
+        _ => 0,
+    };
+    for bar in 1..cantus.len() as u32 {
+        let (from, to) = ((bar - 1) * TICKS + strong, bar * TICKS + strong);
+        if let (Some(from), Some(to)) = (sounding(from), sounding(to)) {
+            if compare(from, to) == Some(Rule::ParallelPerfect) {
+                violations.push((to.0.start, Rule::ParallelPerfect));
+            }
+        }
+    }
+}
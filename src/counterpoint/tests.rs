@@ -0,0 +1,138 @@
+#[cfg(test)]
+mod tests {
+    use super::super::*;
+    use crate::{
+        dur,
+        error::{CounterpointError, ResonataError},
+        key, melody,
+    };
+
+    fn violations(
+        species: Species,
+        cantus: &str,
+        counterpoint: &str,
+        placement: Placement,
+    ) -> Vec<(Rule, usize, Duration)> {
+        let (cantus, counterpoint) = (cantus.parse().unwrap(), counterpoint.parse().unwrap());
+        species
+            .check(&cantus, &counterpoint, placement)
+            .unwrap()
+            .iter()
+            .map(|violation| (violation.rule(), violation.measure(), violation.offset()))
+            .collect()
+    }
+
+    #[test]
+    fn test_check() {
+        let cantus = "D4:1 F4:1 E4:1 D4:1";
+        let (zero, half) = (Duration::zero(), Duration::half());
+
+        let counterpoint = "r:1/2 A4:1/2 C5:1/2 Bb4:1/2 G4:1/2 B4:1/2 D5:1";
+        assert_eq!(
+            violations(Species::Second, cantus, counterpoint, Placement::Above),
+            vec![
+                (Rule::ParallelPerfect, 2, zero),
+                (Rule::Dissonance, 2, half),
+                (Rule::BadEnding, 4, zero)
+            ]
+        );
+
+        // The fourth above E4 is prepared, but rises instead of resolving.
+        let counterpoint = "r:1/2 D5:1/2 D5:1/2 A4:1/2 A4:1/2 B4:1/2 D5:1";
+        assert_eq!(
+            violations(Species::Fourth, cantus, counterpoint, Placement::Above),
+            vec![(Rule::UnresolvedSuspension, 3, zero), (Rule::BadEnding, 4, zero)]
+        );
+        let counterpoint = "r:1/2 D5:1/2 D5:1/2 A4:1/2 A4:1 D5:1";
+        assert_eq!(
+            violations(Species::Fourth, cantus, counterpoint, Placement::Above),
+            vec![
+                (Rule::Rhythm, 3, zero),
+                (Rule::UnresolvedSuspension, 3, zero),
+                (Rule::BadEnding, 4, zero)
+            ]
+        );
+
+        let counterpoint = "D3:1 D2:1 A3:1 D3:1";
+        assert_eq!(
+            violations(Species::First, cantus, counterpoint, Placement::Below),
+            vec![
+                (Rule::Spacing, 2, zero),
+                (Rule::ForbiddenLeap, 3, zero),
+                (Rule::UnrecoveredLeap, 3, zero),
+                (Rule::BadEnding, 4, zero),
+                (Rule::UnrecoveredLeap, 4, zero)
+            ]
+        );
+        let counterpoint = "A3:1 G4:1 C4:1 D4:1";
+        let found = violations(Species::First, cantus, counterpoint, Placement::Below);
+        assert!(found.contains(&(Rule::BadBeginning, 1, zero)));
+        assert!(found.contains(&(Rule::VoiceCrossing, 2, zero)));
+
+        // A first species line has the wrong rhythm for third species.
+        let counterpoint = "A4:1 A4:1 C5:1 D5:1";
+        let found = violations(Species::Third, cantus, counterpoint, Placement::Above);
+        assert_eq!(found.iter().filter(|(rule, _, _)| *rule == Rule::Rhythm).count(), 3);
+    }
+
+    #[test]
+    fn test_check_errors() {
+        let error = |cantus: &str, counterpoint: &str| {
+            let (cantus, counterpoint) = (cantus.parse().unwrap(), counterpoint.parse().unwrap());
+            Species::First.check(&cantus, &counterpoint, Placement::Above).unwrap_err()
+        };
+        let invalid = ResonataError::from(CounterpointError::InvalidCantus);
+        assert_eq!(error("", ""), invalid);
+        assert_eq!(error("D4:1 r:1", "A4:1 D5:1"), invalid);
+        assert_eq!(error("D4:1 E4:1/2", "A4:1 G4:1/2"), invalid);
+        assert_eq!(
+            error("D4:1 D4:1", "A4:1"),
+            CounterpointError::LengthMismatch(dur!(2, 1).unwrap(), Duration::whole()).into()
+        );
+        let triplet = dur!(1, 3).unwrap();
+        assert_eq!(
+            error("D4:1", "A4:1/3 B4:1/3 C5:1/3"),
+            CounterpointError::UnsupportedRhythm(triplet).into()
+        );
+        assert_eq!(error("D4:1 E4:1", "r:1 r:1"), CounterpointError::EmptyCounterpoint.into());
+    }
+
+    #[test]
+    fn test_generate() {
+        let cantus = melody!("D4:1 F4:1 E4:1 D4:1 G4:1 F4:1 A4:1 G4:1 F4:1 E4:1 D4:1").unwrap();
+        let dorian = key!("C D E F G A B").unwrap();
+        let major_cantus = melody!("G3:1/2 C4:1/2 B3:1/2 E4:1/2 D4:1/2 F#4:1/2 G4:1/2").unwrap();
+        let major = key!("F#").unwrap();
+        let species =
+            [Species::First, Species::Second, Species::Third, Species::Fourth, Species::Fifth];
+        for species in species {
+            for placement in [Placement::Above, Placement::Below] {
+                for (cantus, key) in [(&cantus, &dorian), (&major_cantus, &major)] {
+                    for seed in 0..3 {
+                        let line = species.generate(cantus, key, placement, seed).unwrap();
+                        assert_eq!(line.duration(), cantus.duration());
+                        let found = species.check(cantus, &line, placement).unwrap();
+                        assert!(
+                            found.is_empty(),
+                            "{} {} {}: {:?}",
+                            species,
+                            placement,
+                            line,
+                            found
+                        );
+                        assert_eq!(species.generate(cantus, key, placement, seed).unwrap(), line);
+                    }
+                }
+            }
+        }
+
+        let rest = melody!("D4:1 r:1").unwrap();
+        assert!(Species::First.generate(&rest, &dorian, Placement::Above, 0).is_err());
+        // No line can leave a unison on the last note by step to another unison.
+        let repeated = melody!("D4:1 D4:1").unwrap();
+        assert_eq!(
+            Species::First.generate(&repeated, &dorian, Placement::Below, 0),
+            Err(CounterpointError::NoCounterpoint.into())
+        );
+    }
+}
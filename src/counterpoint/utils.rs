@@ -0,0 +1,56 @@
+use super::*;
+use std::fmt::{self, Display, Formatter};
+
+impl Display for Species {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Species::First => "first species",
+            Species::Second => "second species",
+            Species::Third => "third species",
+            Species::Fourth => "fourth species",
+            Species::Fifth => "fifth species",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl Display for Placement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Placement::Above => "above",
+            Placement::Below => "below",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl Display for Rule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let description = match self {
+            Rule::Rhythm => "rhythm does not fit the species",
+            Rule::BadBeginning => "does not begin on a perfect consonance",
+            Rule::BadEnding => {
+                "does not end on a unison or octave reached by step in contrary motion"
+            }
+            Rule::Dissonance => "dissonance is not a passing or neighbour note or a suspension",
+            Rule::UnresolvedSuspension => "suspension does not resolve down by step",
+            Rule::ParallelPerfect => "parallel fifths or octaves",
+            Rule::HiddenPerfect => "hidden fifths or octaves",
+            Rule::Unison => "unison within the line",
+            Rule::VoiceCrossing => "voices cross",
+            Rule::Spacing => "voices more than a twelfth apart",
+            Rule::ForbiddenLeap => "forbidden leap",
+            Rule::UnrecoveredLeap => "leap not recovered by step",
+            Rule::RepeatedNote => "repeated note",
+        };
+        write!(f, "{}", description)
+    }
+}
+
+impl Display for Violation {
+    /// Formats the violation as its measure, its offset and the broken rule, such as
+    /// "measure 3, 1/2: parallel fifths or octaves".
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "measure {}, {}: {}", self.measure, self.offset, self.rule)
+    }
+}
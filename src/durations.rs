@@ -0,0 +1,429 @@
+use crate::{
+    error::{DurationError, ResonataError},
+    nope,
+    notes::PitchedNote,
+};
+
+pub use crate::dur;
+
+mod tests;
+mod utils;
+
+type Result<T> = std::result::Result<T, ResonataError>;
+
+/// A note value, from a double whole note (breve) to a hundred twenty-eighth note.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(u32)]
+pub enum NoteValue {
+    DoubleWhole = 0,
+    Whole = 1,
+    Half = 2,
+    Quarter = 4,
+    Eighth = 8,
+    Sixteenth = 16,
+    ThirtySecond = 32,
+    SixtyFourth = 64,
+    HundredTwentyEighth = 128,
+}
+
+/// A musical duration
+///
+/// Durations are represented as a fraction of a whole note, so a quarter note
+/// is 1/4 and a dotted half note is 3/4. The fraction is always kept in its
+/// lowest terms, so any combination of note values, dots and tuplets can be
+/// added together without rounding. Only sums whose terms would not fit in 32 bits
+/// are rounded, which `checked_add` reports instead.
+///
+/// Durations can be parsed from a note value and optional dots and tuplet, where
+/// the note value is a number (`4` for a quarter note), a letter (`w`, `h`, `q`, `e`
+/// or `s`) or a fraction of a whole note (`3/16`). A `t` suffix makes a triplet, and
+/// `:n` or `:n:m` makes n notes in the time of m.
+///
+/// A macro is provided to make creating durations easier:
+/// dur!(numerator, denominator)
+///
+/// ### Examples
+/// ```
+/// use resonata::durations::*;
+///
+/// let quarter = dur!(1, 4).unwrap();
+/// assert_eq!(quarter, Duration::quarter());
+/// assert_eq!(quarter + quarter, Duration::half());
+///
+/// let dotted_half = dur!(6, 8).unwrap();
+/// assert_eq!(dotted_half.numerator(), 3);
+/// assert_eq!(dotted_half.denominator(), 4);
+/// assert_eq!("h.".parse::<Duration>().unwrap(), dotted_half);
+/// assert_eq!("3/4".parse::<Duration>().unwrap(), dotted_half);
+///
+/// assert_eq!("q.".parse::<Duration>().unwrap(), dur!(3, 8).unwrap());
+/// assert_eq!("8t".parse::<Duration>().unwrap(), dur!(1, 12).unwrap());
+/// assert_eq!("16:5".parse::<Duration>().unwrap(), dur!(1, 20).unwrap());
+/// assert_eq!("4..".parse::<Duration>().unwrap(), dur!(7, 16).unwrap());
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Duration {
+    numerator: u32,
+    denominator: u32,
+}
+
+/// A macro to create a duration.
+#[macro_export]
+macro_rules! dur {
+    ($numerator:expr, $denominator:expr) => {
+        Duration::new($numerator, $denominator)
+    };
+}
+
+impl Duration {
+    /// Creates a new duration from a fraction of a whole note.
+    /// Returns an error if the denominator is zero.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::durations::*;
+    ///
+    /// let eighth = Duration::new(1, 8).unwrap();
+    /// assert_eq!(eighth, Duration::eighth());
+    ///
+    /// assert!(Duration::new(1, 0).is_err());
+    /// ```
+    pub fn new(numerator: u32, denominator: u32) -> Result<Self> {
+        if denominator == 0 {
+            nope!(DurationError::InvalidDuration(numerator, denominator));
+        }
+        let divisor = utils::gcd(numerator, denominator);
+        Ok(Duration { numerator: numerator / divisor, denominator: denominator / divisor })
+    }
+
+    /// Creates a duration from a note value with the given number of dots.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::durations::*;
+    ///
+    /// assert_eq!(Duration::from_note_value(NoteValue::Quarter, 0), Ok(Duration::quarter()));
+    /// assert_eq!(Duration::from_note_value(NoteValue::Half, 2), dur!(7, 8));
+    /// assert_eq!(Duration::from_note_value(NoteValue::DoubleWhole, 1), dur!(3, 1));
+    /// assert!(Duration::from_note_value(NoteValue::Quarter, 17).is_err());
+    /// ```
+    ///
+    /// ### Failures
+    /// Returns an error if there are more dots than [Duration::dotted] allows.
+    pub fn from_note_value(value: NoteValue, dots: u8) -> Result<Self> {
+        let base = match value {
+            NoteValue::DoubleWhole => Duration { numerator: 2, denominator: 1 },
+            value => Duration { numerator: 1, denominator: value as u32 },
+        };
+        base.dotted(dots)
+    }
+
+    /// Returns a duration of zero length, such as that of a grace note.
+    pub fn zero() -> Self {
+        Duration { numerator: 0, denominator: 1 }
+    }
+
+    /// Returns a whole note duration.
+    pub fn whole() -> Self {
+        Duration { numerator: 1, denominator: 1 }
+    }
+
+    /// Returns a half note duration.
+    pub fn half() -> Self {
+        Duration { numerator: 1, denominator: 2 }
+    }
+
+    /// Returns a quarter note duration.
+    pub fn quarter() -> Self {
+        Duration { numerator: 1, denominator: 4 }
+    }
+
+    /// Returns an eighth note duration.
+    pub fn eighth() -> Self {
+        Duration { numerator: 1, denominator: 8 }
+    }
+
+    /// Returns a sixteenth note duration.
+    pub fn sixteenth() -> Self {
+        Duration { numerator: 1, denominator: 16 }
+    }
+
+    /// Returns this duration with the given number of dots added. Each dot adds
+    /// half the value of the one before it.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::durations::*;
+    ///
+    /// assert_eq!(Duration::quarter().dotted(1).unwrap(), dur!(3, 8).unwrap());
+    /// assert_eq!(Duration::quarter().dotted(2).unwrap(), dur!(7, 16).unwrap());
+    /// assert_eq!(Duration::quarter().dotted(0).unwrap(), Duration::quarter());
+    /// ```
+    pub fn dotted(&self, dots: u8) -> Result<Self> {
+        if dots > 16 {
+            nope!(DurationError::DurationOverflow);
+        }
+        self.scaled((1 << (dots + 1)) - 1, 1 << dots)
+    }
+
+    /// Returns this duration as part of a tuplet of the given number of notes
+    /// played in the time of `normal` notes. A triplet is `tuplet(3, 2)`.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::durations::*;
+    ///
+    /// let triplet = Duration::eighth().tuplet(3, 2).unwrap();
+    /// assert_eq!(triplet, dur!(1, 12).unwrap());
+    /// assert_eq!(triplet + triplet + triplet, Duration::quarter());
+    ///
+    /// let septuplet = Duration::sixteenth().tuplet(7, 4).unwrap();
+    /// assert_eq!(septuplet, dur!(1, 28).unwrap());
+    ///
+    /// assert!(Duration::eighth().tuplet(0, 2).is_err());
+    /// ```
+    pub fn tuplet(&self, actual: u32, normal: u32) -> Result<Self> {
+        if actual == 0 || normal == 0 {
+            nope!(DurationError::InvalidTuplet(actual, normal));
+        }
+        self.scaled(normal, actual)
+    }
+
+    /// Returns the length of the duration in seconds at the given tempo.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::durations::*;
+    ///
+    /// let tempo = Tempo::new(Duration::quarter(), 120.0).unwrap();
+    /// assert_eq!(Duration::quarter().to_seconds(&tempo), 0.5);
+    /// assert_eq!(Duration::whole().to_seconds(&tempo), 2.0);
+    ///
+    /// let tempo = Tempo::new(dur!(3, 8).unwrap(), 60.0).unwrap();
+    /// assert_eq!(Duration::eighth().to_seconds(&tempo), 1.0 / 3.0);
+    /// ```
+    pub fn to_seconds(&self, tempo: &Tempo) -> f64 {
+        self.to_whole_notes() * tempo.seconds_per_whole_note()
+    }
+
+    /// Returns this duration multiplied by the given fraction.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::durations::*;
+    ///
+    /// let eighth = Duration::eighth();
+    /// assert_eq!(eighth.scaled(3, 1).unwrap(), dur!(3, 8).unwrap());
+    /// assert_eq!(eighth.scaled(1, 2).unwrap(), Duration::sixteenth());
+    /// assert!(eighth.scaled(1, 0).is_err());
+    /// ```
+    pub fn scaled(&self, numerator: u32, denominator: u32) -> Result<Self> {
+        let n = self.numerator as u64 * numerator as u64;
+        let d = self.denominator as u64 * denominator as u64;
+        if d == 0 {
+            nope!(DurationError::InvalidDuration(numerator, denominator));
+        }
+        let divisor = utils::gcd_u64(n, d);
+        let (n, d) = (n / divisor, d / divisor);
+        if n > u32::MAX as u64 || d > u32::MAX as u64 {
+            nope!(DurationError::DurationOverflow);
+        }
+        Duration::new(n as u32, d as u32)
+    }
+
+    /// Returns the sum of this duration and the given one, or an error where `+` would
+    /// panic.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::durations::*;
+    ///
+    /// let sum = Duration::eighth().checked_add(dur!(1, 12).unwrap()).unwrap();
+    /// assert_eq!(sum, dur!(5, 24).unwrap());
+    /// assert!(dur!(1, 65537).unwrap().checked_add(dur!(1, 65539).unwrap()).is_err());
+    /// ```
+    ///
+    /// ### Failures
+    /// Returns an error if the sum cannot be written as a fraction of 32-bit integers.
+    pub fn checked_add(&self, other: Duration) -> Result<Self> {
+        let (n, d) = utils::sum_terms(self, &other);
+        let (numerator, denominator) =
+            utils::reduce(n, d).ok_or(DurationError::DurationOverflow)?;
+        Ok(Duration { numerator, denominator })
+    }
+
+    /// Returns this duration less the given one, saturating at zero, or an error where
+    /// `-` would panic.
+    ///
+    /// ### Failures
+    /// Returns an error if the difference cannot be written as a fraction of 32-bit
+    /// integers.
+    pub fn checked_sub(&self, other: Duration) -> Result<Self> {
+        let (n, d) = utils::difference_terms(self, &other);
+        let (numerator, denominator) =
+            utils::reduce(n, d).ok_or(DurationError::DurationOverflow)?;
+        Ok(Duration { numerator, denominator })
+    }
+
+    /// Returns the number of times the given duration fits into this one,
+    /// as a fraction in lowest terms. A ratio that cannot be written as a fraction of
+    /// 32-bit integers is rounded to the nearest one that can.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::durations::*;
+    ///
+    /// assert_eq!(Duration::half().ratio_to(&Duration::eighth()), Ok((4, 1)));
+    /// assert_eq!(dur!(3, 16).unwrap().ratio_to(&Duration::eighth()), Ok((3, 2)));
+    /// assert!(Duration::half().ratio_to(&Duration::zero()).is_err());
+    /// ```
+    ///
+    /// ### Failures
+    /// Returns an error if the given duration is zero.
+    pub fn ratio_to(&self, other: &Duration) -> Result<(u32, u32)> {
+        if other.numerator == 0 {
+            nope!(DurationError::DivisionByZero);
+        }
+        let n = self.numerator as u64 * other.denominator as u64;
+        let d = self.denominator as u64 * other.numerator as u64;
+        Ok(utils::nearest(n, d))
+    }
+
+    /// Returns the duration as a decimal number of whole notes.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::durations::*;
+    ///
+    /// assert_eq!(dur!(3, 8).unwrap().to_whole_notes(), 0.375);
+    /// ```
+    pub fn to_whole_notes(&self) -> f64 {
+        self.numerator as f64 / self.denominator as f64
+    }
+
+    /// Returns true if the duration has no length.
+    pub fn is_zero(&self) -> bool {
+        self.numerator == 0
+    }
+
+    /// Returns the numerator of the duration, in lowest terms.
+    pub fn numerator(&self) -> u32 {
+        self.numerator
+    }
+
+    /// Returns the denominator of the duration, in lowest terms.
+    pub fn denominator(&self) -> u32 {
+        self.denominator
+    }
+}
+
+/// A tempo, given as a number of beats of a certain length per minute.
+///
+/// ### Examples
+/// ```
+/// use resonata::durations::*;
+///
+/// let tempo = Tempo::quarter(90.0).unwrap();
+/// assert_eq!(tempo.beat(), Duration::quarter());
+/// assert_eq!(tempo.bpm(), 90.0);
+/// assert_eq!(tempo.to_string(), "1/4=90");
+/// assert_eq!("3/8=120".parse::<Tempo>(), Tempo::new(dur!(3, 8).unwrap(), 120.0));
+/// assert!(Tempo::quarter(0.0).is_err());
+/// assert!(Tempo::quarter(f64::NAN).is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tempo {
+    beat: Duration,
+    bpm: f64,
+}
+
+impl Tempo {
+    /// Creates a tempo of the given number of beats per minute.
+    ///
+    /// ### Failures
+    /// Returns an error if the beat has no length, or if the number of beats per minute
+    /// is not a finite, positive number.
+    pub fn new(beat: Duration, bpm: f64) -> Result<Self> {
+        if beat == Duration::zero() || bpm <= 0.0 || !bpm.is_finite() {
+            nope!(DurationError::InvalidTempo(format!("{}={}", beat, bpm)));
+        }
+        Ok(Tempo { beat, bpm })
+    }
+
+    /// Creates a tempo of the given number of quarter notes per minute.
+    ///
+    /// ### Failures
+    /// Returns an error if the number of beats per minute is not a finite, positive
+    /// number.
+    pub fn quarter(bpm: f64) -> Result<Self> {
+        Tempo::new(Duration::quarter(), bpm)
+    }
+
+    /// Returns the length of a whole note in seconds.
+    pub fn seconds_per_whole_note(&self) -> f64 {
+        60.0 / (self.bpm * self.beat.to_whole_notes())
+    }
+
+    /// Returns the length of a beat.
+    pub fn beat(&self) -> Duration {
+        self.beat
+    }
+
+    /// Returns the number of beats per minute.
+    pub fn bpm(&self) -> f64 {
+        self.bpm
+    }
+}
+
+/// A note or rest with a duration.
+///
+/// ### Examples
+/// ```
+/// use resonata::{notes::*, durations::*};
+///
+/// let events = vec![
+///     Event::Note(pnote!("C4").unwrap(), "q.".parse().unwrap()),
+///     Event::Note(pnote!("D4").unwrap(), "8".parse().unwrap()),
+///     Event::Rest("h".parse().unwrap()),
+/// ];
+/// assert_eq!(events[0].pitch(), Some(pnote!("C4").unwrap()));
+/// assert_eq!(events[2].pitch(), None);
+/// assert_eq!(events.iter().map(|event| event.duration()).sum::<Duration>(), Duration::whole());
+/// assert_eq!(events[1].to_string(), "D4:1/8");
+/// assert_eq!("r:h".parse::<Event>().unwrap(), events[2]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event {
+    Note(PitchedNote, Duration),
+    Rest(Duration),
+}
+
+impl Event {
+    /// Returns the duration of the event.
+    pub fn duration(&self) -> Duration {
+        match self {
+            Event::Note(_, duration) | Event::Rest(duration) => *duration,
+        }
+    }
+
+    /// Returns the pitch of the event, or `None` for a rest.
+    pub fn pitch(&self) -> Option<PitchedNote> {
+        match self {
+            Event::Note(note, _) => Some(*note),
+            Event::Rest(_) => None,
+        }
+    }
+
+    /// Returns true if the event is a rest.
+    pub fn is_rest(&self) -> bool {
+        matches!(self, Event::Rest(_))
+    }
+
+    /// Returns this event with its duration replaced.
+    pub fn with_duration(&self, duration: Duration) -> Self {
+        match self {
+            Event::Note(note, _) => Event::Note(*note, duration),
+            Event::Rest(_) => Event::Rest(duration),
+        }
+    }
+}
@@ -0,0 +1,97 @@
+#[cfg(test)]
+mod tests {
+    use super::super::*;
+
+    #[test]
+    fn test_arithmetic() {
+        let triplet = Duration::eighth().tuplet(3, 2).unwrap();
+        assert_eq!(triplet + Duration::eighth(), dur!(5, 24).unwrap());
+        assert_eq!(Duration::half() - Duration::eighth(), dur!(3, 8).unwrap());
+        assert_eq!(Duration::eighth() - Duration::half(), Duration::zero());
+        assert!(Duration::sixteenth() < triplet);
+        assert!(Duration::quarter().dotted(1).unwrap() > Duration::quarter());
+        assert_eq!(vec![Duration::quarter(); 4].into_iter().sum::<Duration>(), Duration::whole());
+    }
+
+    #[test]
+    fn test_overflow() {
+        let (a, b) = (dur!(1, 65537).unwrap(), dur!(1, 65539).unwrap());
+        assert_eq!(a.checked_add(b), Err(DurationError::DurationOverflow.into()));
+        assert_eq!(a.checked_sub(b), Err(DurationError::DurationOverflow.into()));
+        assert_eq!(a.checked_add(a), Ok(dur!(2, 65537).unwrap()));
+
+        let result = std::panic::catch_unwind(|| a + b);
+        assert!(result.is_err());
+        let result = std::panic::catch_unwind(|| a - b);
+        assert!(result.is_err());
+
+        let long = dur!(u32::MAX, 1).unwrap();
+        assert_eq!(long.ratio_to(&dur!(1, 2).unwrap()), Ok((u32::MAX, 1)));
+        assert_eq!(a.ratio_to(&Duration::zero()), Err(DurationError::DivisionByZero.into()));
+        let (n, d) = a.ratio_to(&b).unwrap();
+        assert!((n as f64 / d as f64 - 65539.0 / 65537.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_note_values() {
+        let values = [
+            (NoteValue::Whole, "w"),
+            (NoteValue::Half, "h"),
+            (NoteValue::Quarter, "q"),
+            (NoteValue::Eighth, "e"),
+            (NoteValue::Sixteenth, "s"),
+        ];
+        for (value, letter) in values {
+            let duration = Duration::from_note_value(value, 0).unwrap();
+            assert_eq!(letter.parse::<Duration>().unwrap(), duration);
+            assert_eq!(format!("{}", value as u32).parse::<Duration>().unwrap(), duration);
+        }
+        assert_eq!(Duration::from_note_value(NoteValue::HundredTwentyEighth, 0), dur!(1, 128));
+        assert_eq!(
+            Duration::from_note_value(NoteValue::Quarter, 17),
+            Err(DurationError::DurationOverflow.into())
+        );
+        assert_eq!("0".parse::<Duration>().unwrap(), dur!(2, 1).unwrap());
+    }
+
+    #[test]
+    fn test_parse_tuplets() {
+        assert_eq!("q.t".parse::<Duration>().unwrap(), Duration::quarter());
+        assert_eq!("8:3".parse::<Duration>().unwrap(), dur!(1, 12).unwrap());
+        assert_eq!("8:2".parse::<Duration>().unwrap(), dur!(3, 16).unwrap());
+        assert_eq!("16:7".parse::<Duration>().unwrap(), dur!(1, 28).unwrap());
+        assert_eq!("16:6:4".parse::<Duration>().unwrap(), dur!(1, 24).unwrap());
+        assert_eq!("3/16.".parse::<Duration>().unwrap(), dur!(9, 32).unwrap());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!("".parse::<Duration>().is_err());
+        assert!("q..x".parse::<Duration>().is_err());
+        assert!("1/0".parse::<Duration>().is_err());
+        assert!("8:0".parse::<Duration>().is_err());
+        assert!("z".parse::<Duration>().is_err());
+        assert!("q=".parse::<Tempo>().is_err());
+        assert!("q=-60".parse::<Tempo>().is_err());
+        assert!("C4".parse::<Event>().is_err());
+    }
+
+    #[test]
+    fn test_seconds() {
+        let tempo = "q.=40".parse::<Tempo>().unwrap();
+        assert_eq!(
+            Duration::from_note_value(NoteValue::Quarter, 1).unwrap().to_seconds(&tempo),
+            1.5
+        );
+        let events = ["C4:q", "r:8", "E4:8t", "F4:8t", "G4:8t"]
+            .iter()
+            .map(|s| s.parse::<Event>().unwrap())
+            .collect::<Vec<_>>();
+        let tempo = Tempo::quarter(60.0).unwrap();
+        let total: f64 = events.iter().map(|event| event.duration().to_seconds(&tempo)).sum();
+        assert!((total - 2.5).abs() < 1e-9);
+        assert!(Tempo::new(Duration::zero(), 60.0).is_err());
+        assert!(Tempo::quarter(-60.0).is_err());
+        assert!(Tempo::quarter(f64::INFINITY).is_err());
+    }
+}
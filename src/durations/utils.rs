@@ -0,0 +1,242 @@
+use super::*;
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::{
+    cmp::Ordering,
+    fmt::{self, Debug, Display, Formatter},
+    iter::Sum,
+    ops::{Add, AddAssign, Sub},
+    str::FromStr,
+};
+
+/// Returns the greatest common divisor of two numbers, treating gcd(0, 0) as 1
+/// so that it is always safe to divide by.
+pub(crate) fn gcd(a: u32, b: u32) -> u32 {
+    gcd_u64(a as u64, b as u64) as u32
+}
+
+pub(crate) fn gcd_u64(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a.max(1)
+}
+
+/// Reduces a fraction to its lowest terms, if they fit in a duration.
+pub(crate) fn reduce(n: u64, d: u64) -> Option<(u32, u32)> {
+    let divisor = gcd_u64(n, d);
+    Some((u32::try_from(n / divisor).ok()?, u32::try_from(d / divisor).ok()?))
+}
+
+/// Returns the fraction nearest to n/d whose terms fit in a duration, which is n/d
+/// itself when it fits. Otherwise it is the last convergent of the continued fraction
+/// of n/d that fits, or the semiconvergent after it, whichever is nearer.
+pub(crate) fn nearest(n: u64, d: u64) -> (u32, u32) {
+    if let Some(fraction) = reduce(n, d) {
+        return fraction;
+    }
+    let max = u32::MAX as u64;
+    let error = |p: u64, q: u64| (p as i128 * d as i128 - n as i128 * q as i128).unsigned_abs();
+    let (mut p0, mut q0, mut p1, mut q1) = (0, 1, 1, 0);
+    let (mut x, mut y) = (n, d);
+    while y != 0 {
+        let a = x / y;
+        let fits = |previous: u64, last: u64| match last {
+            0 => u64::MAX,
+            _ => (max - previous) / last,
+        };
+        let t = fits(p0, p1).min(fits(q0, q1));
+        if a > t {
+            let (p, q) = (t * p1 + p0, t * q1 + q0);
+            // Compare |p/q - n/d| with |p1/q1 - n/d| without dividing.
+            if q1 == 0 || error(p, q) * (q1 as u128) < error(p1, q1) * (q as u128) {
+                return (p as u32, q as u32);
+            }
+            break;
+        }
+        (p0, q0, p1, q1) = (p1, q1, a * p1 + p0, a * q1 + q0);
+        (x, y) = (y, x - a * y);
+    }
+    (p1 as u32, q1 as u32)
+}
+
+/// Returns the unreduced terms of the sum of two durations.
+pub(crate) fn sum_terms(lhs: &Duration, rhs: &Duration) -> (u64, u64) {
+    let n = lhs.numerator as u64 * rhs.denominator as u64
+        + rhs.numerator as u64 * lhs.denominator as u64;
+    (n, lhs.denominator as u64 * rhs.denominator as u64)
+}
+
+/// Returns the unreduced terms of the difference of two durations, saturating at zero.
+pub(crate) fn difference_terms(lhs: &Duration, rhs: &Duration) -> (u64, u64) {
+    let n = (lhs.numerator as u64 * rhs.denominator as u64)
+        .saturating_sub(rhs.numerator as u64 * lhs.denominator as u64);
+    (n, lhs.denominator as u64 * rhs.denominator as u64)
+}
+
+impl Add for Duration {
+    type Output = Self;
+    /// Adds the given duration.
+    ///
+    /// ### Panics
+    /// Panics if the sum cannot be written as a fraction of 32-bit integers; use
+    /// `checked_add` to catch it.
+    fn add(self, rhs: Self) -> Self::Output {
+        self.checked_add(rhs).expect("duration overflow in addition")
+    }
+}
+
+impl AddAssign for Duration {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for Duration {
+    type Output = Self;
+    /// Subtracts the given duration, saturating at zero.
+    ///
+    /// ### Panics
+    /// Panics if the difference cannot be written as a fraction of 32-bit integers; use
+    /// `checked_sub` to catch it.
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.checked_sub(rhs).expect("duration overflow in subtraction")
+    }
+}
+
+impl Sum for Duration {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Duration::zero(), |acc, d| acc + d)
+    }
+}
+
+impl PartialOrd for Duration {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Duration {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let lhs = self.numerator as u64 * other.denominator as u64;
+        let rhs = other.numerator as u64 * self.denominator as u64;
+        lhs.cmp(&rhs)
+    }
+}
+
+impl Display for Duration {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.numerator, self.denominator)
+    }
+}
+
+impl Debug for Duration {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+/// Returns the number of notes a tuplet of the given size is played in the time of,
+/// which is the largest power of two below it, except for duplets and quadruplets.
+fn default_tuplet_normal(actual: u32) -> u32 {
+    match actual {
+        0 | 1 => 1,
+        2 | 4 => 3,
+        n => 1 << (n - 1).ilog2(),
+    }
+}
+
+lazy_static! {
+    static ref DURATION_RE: Regex =
+        Regex::new(r"^(?:(\d+)/(\d+)|(\d+)|([whqes]))(\.*)(?:(t)|:(\d+)(?::(\d+))?)?$").unwrap();
+}
+
+impl FromStr for Duration {
+    type Err = ResonataError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let invalid = || DurationError::InvalidDurationFormat(s.to_string());
+        let cap = DURATION_RE.captures(s.trim()).ok_or_else(invalid)?;
+        let number = |i: usize| -> std::result::Result<u32, DurationError> {
+            cap[i].parse().map_err(|_| invalid())
+        };
+
+        let base = if cap.get(1).is_some() {
+            Duration::new(number(1)?, number(2)?)?
+        } else if cap.get(3).is_some() {
+            match number(3)? {
+                0 => Duration::from_note_value(NoteValue::DoubleWhole, 0)?,
+                n => Duration::new(1, n)?,
+            }
+        } else {
+            let value = match &cap[4] {
+                "w" => NoteValue::Whole,
+                "h" => NoteValue::Half,
+                "q" => NoteValue::Quarter,
+                "e" => NoteValue::Eighth,
+                _ => NoteValue::Sixteenth,
+            };
+            Duration::from_note_value(value, 0)?
+        };
+
+        let duration = base.dotted(cap[5].len() as u8)?;
+        if cap.get(6).is_some() {
+            duration.tuplet(3, 2)
+        } else if cap.get(7).is_some() {
+            let actual = number(7)?;
+            let normal = match cap.get(8) {
+                Some(_) => number(8)?,
+                None => default_tuplet_normal(actual),
+            };
+            duration.tuplet(actual, normal)
+        } else {
+            Ok(duration)
+        }
+    }
+}
+
+impl FromStr for Tempo {
+    type Err = ResonataError;
+
+    /// Parses a tempo such as `1/4=120` or `q.=60`.
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let invalid = || DurationError::InvalidTempoFormat(s.to_string());
+        let (beat, bpm) = s.split_once('=').ok_or_else(invalid)?;
+        let beat = beat.parse::<Duration>()?;
+        let bpm = bpm.trim().parse::<f64>().map_err(|_| invalid())?;
+        if bpm <= 0.0 || !bpm.is_finite() {
+            nope!(invalid());
+        }
+        Tempo::new(beat, bpm)
+    }
+}
+
+impl Display for Tempo {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}={}", self.beat, self.bpm)
+    }
+}
+
+impl Display for Event {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Event::Note(note, duration) => write!(f, "{}:{}", note, duration),
+            Event::Rest(duration) => write!(f, "r:{}", duration),
+        }
+    }
+}
+
+impl FromStr for Event {
+    type Err = ResonataError;
+
+    /// Parses an event such as `C#4:1/8` or `r:q.`, where `r` is a rest.
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let (pitch, duration) =
+            s.split_once(':').ok_or_else(|| DurationError::InvalidDurationFormat(s.to_string()))?;
+        let duration = duration.parse::<Duration>()?;
+        match pitch.trim() {
+            "r" => Ok(Event::Rest(duration)),
+            pitch => Ok(Event::Note(pitch.parse()?, duration)),
+        }
+    }
+}
@@ -1,4 +1,8 @@
-use crate::intervals::*;
+use crate::{
+    durations::Duration,
+    intervals::*,
+    notes::{Note, PitchedNote},
+};
 use thiserror::Error;
 
 pub use crate::{err, nope, yep};
@@ -56,6 +60,170 @@ pub enum ScaleError {
     InvalidScale,
     #[error("Invalid scale format")]
     NoRootSpecified,
+    #[error("Note {0} is not in the scale")]
+    NoteNotInScale(Note),
+    #[error("Invalid scale degree")]
+    InvalidScaleDegree(u8),
+    #[error("Invalid scale degree format")]
+    InvalidScaleDegreeFormat(String),
+    #[error("Unknown scale name")]
+    UnknownScaleName(String),
+    #[error("Invalid scale number")]
+    InvalidScaleNumber(u16),
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum DurationError {
+    #[error("Invalid duration")]
+    InvalidDuration(u32, u32),
+    #[error("Duration overflow")]
+    DurationOverflow,
+    #[error("Invalid duration format")]
+    InvalidDurationFormat(String),
+    #[error("Invalid tuplet")]
+    InvalidTuplet(u32, u32),
+    #[error("Invalid tempo format")]
+    InvalidTempoFormat(String),
+    #[error("Invalid tempo {0}")]
+    InvalidTempo(String),
+    #[error("Cannot divide by a zero duration")]
+    DivisionByZero,
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum TimeSignatureError {
+    #[error("Invalid time signature")]
+    InvalidTimeSignature(String),
+    #[error("Invalid beat grouping")]
+    InvalidBeatGrouping(Vec<u32>, u32),
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum ChordError {
+    #[error("Invalid chord symbol")]
+    InvalidChordSymbol(String),
+    #[error("Invalid chord alteration")]
+    InvalidChordAlteration(String),
+    #[error("Invalid chord function")]
+    InvalidChordFunction(String),
+    #[error("Chord root {0} is not on the degree of its function")]
+    ChordNotOnDegree(Note),
+    #[error("Chord {0} is not a major or minor triad")]
+    InvalidTriad(String),
+    #[error("Invalid transformation")]
+    InvalidTransformation(String),
+    #[error("Invalid figure {0}")]
+    InvalidFigure(String),
+    #[error("Bass {0} is not in the chord")]
+    BassNotInChord(Note),
+    #[error("No chord type has the thirds stacked on {0}")]
+    UnknownChordType(String),
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum FretboardError {
+    #[error("Invalid tuning")]
+    InvalidTuning(String),
+    #[error("No fret {1} on string {0}")]
+    InvalidPosition(usize, u8),
+    #[error("Pitch {0} is not on the fretboard")]
+    PitchNotOnFretboard(PitchedNote),
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum KeyboardError {
+    #[error("Lowest key {0} is above highest key {1}")]
+    InvalidRange(PitchedNote, PitchedNote),
+    #[error("Pitch {0} is not on the keyboard")]
+    PitchOutOfRange(PitchedNote),
+    #[error("Invalid fingering")]
+    InvalidFingering(Vec<u8>),
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum CounterpointError {
+    #[error("The cantus firmus must be notes of equal length")]
+    InvalidCantus,
+    #[error("Counterpoint lasts {1}, but the cantus firmus lasts {0}")]
+    LengthMismatch(Duration, Duration),
+    #[error("The counterpoint has no notes")]
+    EmptyCounterpoint,
+    #[error("Duration {0} is not a whole number of eighths of a measure")]
+    UnsupportedRhythm(Duration),
+    #[error("No counterpoint found")]
+    NoCounterpoint,
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum HarmonyError {
+    #[error("Soprano has {1} notes, but there are {0} chords")]
+    SopranoLengthMismatch(usize, usize),
+    #[error("Soprano note {0} is not in its chord")]
+    SopranoNotInChord(Note),
+    #[error("Invalid cadence: {0}")]
+    InvalidCadence(String),
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum GeneratorError {
+    #[error("No note of the scale between {0} and {1}")]
+    InvalidRange(PitchedNote, PitchedNote),
+    #[error("Invalid weights")]
+    InvalidWeights,
+    #[error("The contour cannot be followed within the range at note {0}")]
+    ContourOutOfRange(usize),
+    #[error("No transition from {0}")]
+    NoTransition(String),
+    #[error("Invalid Markov chain order {0}")]
+    InvalidOrder(usize),
+    #[error("The Markov chain has not been trained")]
+    Untrained,
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum ExerciseError {
+    #[error("Invalid answer: {0}")]
+    InvalidAnswer(String),
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum AudioError {
+    #[error("Invalid sample rate {0}")]
+    InvalidSampleRate(u32),
+    #[error("Sample rates {0} and {1} do not match")]
+    SampleRateMismatch(u32, u32),
+    #[error("Invalid envelope")]
+    InvalidEnvelope,
+    #[error("Invalid gain {0}")]
+    InvalidGain(String),
+    #[error("Invalid reference frequency {0}")]
+    InvalidReference(String),
+    #[error("Cannot render {0} seconds of sound")]
+    InvalidLength(String),
+    #[error("Invalid WAV file: {0}")]
+    InvalidWav(String),
+    #[error("A buffer of {0} samples is too long for a WAV file")]
+    BufferTooLong(usize),
+    #[error("I/O error: {0}")]
+    Io(String),
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum NotationError {
+    #[error("Invalid header field on line {0}")]
+    InvalidHeader(usize),
+    #[error("Invalid key signature")]
+    InvalidKeySignature(String),
+    #[error("Invalid token on line {0}")]
+    InvalidToken(usize, String),
+    #[error("Missing key signature")]
+    MissingKeySignature,
+    #[error("Missing exclusive interpretation")]
+    MissingExclusiveInterpretation,
+    #[error("Wrong number of spines on line {0}")]
+    SpineMismatch(usize),
+    #[error("Wrong number of strings on line {0}")]
+    StringMismatch(usize),
 }
 
 #[derive(Error, Debug, PartialEq, Eq)]
@@ -68,6 +236,28 @@ pub enum ResonataError {
     ScaleError(#[from] ScaleError),
     #[error("Key error: {0}")]
     KeyError(#[from] KeyError),
+    #[error("Duration error: {0}")]
+    DurationError(#[from] DurationError),
+    #[error("Time signature error: {0}")]
+    TimeSignatureError(#[from] TimeSignatureError),
+    #[error("Chord error: {0}")]
+    ChordError(#[from] ChordError),
+    #[error("Fretboard error: {0}")]
+    FretboardError(#[from] FretboardError),
+    #[error("Keyboard error: {0}")]
+    KeyboardError(#[from] KeyboardError),
+    #[error("Notation error: {0}")]
+    NotationError(#[from] NotationError),
+    #[error("Counterpoint error: {0}")]
+    CounterpointError(#[from] CounterpointError),
+    #[error("Harmony error: {0}")]
+    HarmonyError(#[from] HarmonyError),
+    #[error("Generator error: {0}")]
+    GeneratorError(#[from] GeneratorError),
+    #[error("Exercise error: {0}")]
+    ExerciseError(#[from] ExerciseError),
+    #[error("Audio error: {0}")]
+    AudioError(#[from] AudioError),
 }
 
 /// A macro to create a `ResonataError` from a `NoteError`, `IntervalError` or `ScaleError`
@@ -0,0 +1,639 @@
+use crate::{
+    chords::{Chord, ChordFunction, ChordType},
+    durations::{Duration, Event},
+    error::{ExerciseError, ResonataError},
+    generators::MelodyGenerator,
+    harmony::Cadence,
+    intervals::Interval,
+    melodies::Melody,
+    notes::*,
+    scales::{HarmonicMinorMode, MajorMode, MelodicMinorMode, Scale, ScaleEnumType, ScaleType},
+};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+mod tests;
+mod utils;
+
+type Result<T> = std::result::Result<T, ResonataError>;
+
+/// How hard an exercise is. Harder exercises draw their answers from more and less
+/// familiar material, are played in less obvious ways, and offer more choices.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Difficulty {
+    Beginner,
+    Intermediate,
+    Advanced,
+}
+
+/// The kind of an ear-training exercise, by what is to be identified.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExerciseKind {
+    Interval,
+    ChordQuality,
+    Scale,
+    Cadence,
+    Dictation,
+}
+
+/// The answer to an exercise, or one of its choices.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Answer {
+    Interval(Interval),
+    ChordQuality(ChordType),
+    Scale(ScaleEnumType),
+    Cadence(Cadence),
+    Melody(Melody),
+}
+
+/// An ear-training exercise: something to play, and the answer to identify in it.
+///
+/// The prompt is a list of sonorities, each a group of notes sounding together for a
+/// duration, played one after the other. Exercises with a fixed set of answers offer
+/// choices, the answer among distractors, in order from the simplest. Dictation
+/// exercises offer none, and are answered with the melody that was played.
+///
+/// Answers are checked by name, so an augmented fourth is not a diminished fifth and a
+/// melody must be spelled as it was played, unless the exercise accepts enharmonic
+/// equivalents, in which case any answer that sounds the same is right. Names of the
+/// same scale, such as "Aeolian" and "minor", are always accepted.
+///
+/// ### Examples
+/// ```
+/// use resonata::{exercises::*, intervals::*};
+///
+/// let exercise = ExerciseGenerator::new(Difficulty::Intermediate).generate(ExerciseKind::Interval, 3).unwrap();
+/// assert_eq!(exercise.choices().len(), 4);
+/// assert!(exercise.choices().contains(exercise.answer()));
+///
+/// let Answer::Interval(interval) = exercise.answer() else { panic!() };
+/// let answer = exercise.parse_answer(&interval.to_string()).unwrap();
+/// assert!(exercise.check(&answer));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Exercise {
+    kind: ExerciseKind,
+    difficulty: Difficulty,
+    prompt: Vec<(Vec<PitchedNote>, Duration)>,
+    key: Option<(Scale, Note)>,
+    answer: Answer,
+    choices: Vec<Answer>,
+    enharmonic: bool,
+}
+
+/// Generates graded ear-training exercises from a seed.
+///
+/// - Interval exercises play two notes, rising at first, then rising or falling, and
+///   at the advanced level together, with compound intervals.
+/// - Chord quality exercises play a chord, first broken and then in root position,
+///   then in root position, and at the advanced level inverted, with seventh and
+///   suspended chords.
+/// - Scale exercises play a scale rising from its root, with the major and minor
+///   scales at first, then the modes of the major scale, and then more.
+/// - Cadence exercises play a short four-part phrase in a major key ending in a
+///   cadence, and at the advanced level in minor keys too.
+/// - Dictation exercises play a melody in a key, longer and with more leaps and
+///   rhythms at higher levels.
+///
+/// The same seed always gives the same exercise.
+///
+/// ### Examples
+/// ```
+/// use resonata::{exercises::*, chords::*};
+///
+/// let generator = ExerciseGenerator::new(Difficulty::Beginner);
+/// let exercise = generator.generate(ExerciseKind::ChordQuality, 0).unwrap();
+/// let choices = [Answer::ChordQuality(ChordType::Major), Answer::ChordQuality(ChordType::Minor)];
+/// assert_eq!(exercise.choices(), &choices);
+/// assert_eq!(exercise.prompt().last().unwrap().0.len(), 3);
+/// assert_eq!(generator.generate(ExerciseKind::ChordQuality, 0).unwrap(), exercise);
+///
+/// let exercise = generator.generate(ExerciseKind::Dictation, 0).unwrap();
+/// assert!(exercise.choices().is_empty());
+/// let Answer::Melody(melody) = exercise.answer() else { panic!() };
+/// assert_eq!(melody.len(), exercise.prompt().len());
+/// assert!(exercise.check(&exercise.parse_answer(&melody.to_string()).unwrap()));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExerciseGenerator {
+    difficulty: Difficulty,
+    choices: usize,
+    enharmonic: bool,
+}
+
+/// Roots with no accidentals, for beginners.
+const NATURAL_ROOTS: [&str; 7] = ["C", "D", "E", "F", "G", "A", "B"];
+
+/// The usual spelling of each pitch class, as roots.
+const ROOTS: [&str; 12] = ["C", "Db", "D", "Eb", "E", "F", "F#", "G", "Ab", "A", "Bb", "B"];
+
+/// The tonics of major keys with up to three sharps or flats.
+const MAJOR_TONICS: [&str; 7] = ["C", "G", "D", "A", "F", "Bb", "Eb"];
+
+/// The tonics of minor keys with up to three sharps or flats.
+const MINOR_TONICS: [&str; 7] = ["A", "E", "B", "F#", "D", "G", "C"];
+
+const BEGINNER_INTERVALS: [&str; 5] = ["m3", "M3", "P4", "P5", "P8"];
+
+const INTERMEDIATE_INTERVALS: [&str; 12] =
+    ["m2", "M2", "m3", "M3", "P4", "A4", "P5", "m6", "M6", "m7", "M7", "P8"];
+
+const ADVANCED_INTERVALS: [&str; 19] = [
+    "m2", "M2", "m3", "M3", "P4", "A4", "d5", "P5", "m6", "M6", "m7", "M7", "P8", "m9", "M9",
+    "m10", "M10", "P11", "P12",
+];
+
+impl Exercise {
+    /// Returns the kind of the exercise.
+    pub fn kind(&self) -> ExerciseKind {
+        self.kind
+    }
+
+    /// Returns the difficulty of the exercise.
+    pub fn difficulty(&self) -> Difficulty {
+        self.difficulty
+    }
+
+    /// Returns what to play, as groups of notes sounding together, with their
+    /// durations, in order.
+    pub fn prompt(&self) -> &Vec<(Vec<PitchedNote>, Duration)> {
+        &self.prompt
+    }
+
+    /// Returns the scale and tonic of the key the prompt is in, for exercises played
+    /// in a key.
+    pub fn key(&self) -> Option<(&Scale, Note)> {
+        self.key.as_ref().map(|(scale, tonic)| (scale, *tonic))
+    }
+
+    /// Returns the answer.
+    pub fn answer(&self) -> &Answer {
+        &self.answer
+    }
+
+    /// Returns the answers to choose from, which are empty for dictation.
+    pub fn choices(&self) -> &Vec<Answer> {
+        &self.choices
+    }
+
+    /// Returns true if answers that sound the same as the answer are accepted.
+    pub fn accepts_enharmonic(&self) -> bool {
+        self.enharmonic
+    }
+
+    /// Returns true if the answer given is right.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::exercises::*;
+    ///
+    /// let generator = ExerciseGenerator::new(Difficulty::Advanced);
+    /// let exercise = (0..).map(|seed| generator.generate(ExerciseKind::Interval, seed).unwrap())
+    ///     .find(|exercise| exercise.answer().to_string() == "A4")
+    ///     .unwrap();
+    /// assert!(exercise.check(&exercise.parse_answer("A4").unwrap()));
+    /// assert!(!exercise.check(&exercise.parse_answer("d5").unwrap()));
+    ///
+    /// let exercise = exercise.with_enharmonic(true);
+    /// assert!(exercise.check(&exercise.parse_answer("d5").unwrap()));
+    /// assert!(!exercise.check(&exercise.parse_answer("P5").unwrap()));
+    /// ```
+    pub fn check(&self, answer: &Answer) -> bool {
+        self.answer.matches(answer, self.enharmonic)
+    }
+
+    /// Returns the exercise, accepting answers that sound the same as the answer or not.
+    pub fn with_enharmonic(mut self, enharmonic: bool) -> Self {
+        self.enharmonic = enharmonic;
+        self
+    }
+
+    /// Parses an answer to the exercise. Intervals are written as "m3" or "P12",
+    /// chord qualities by name or as chord symbol suffixes, such as "minor seventh" or
+    /// "m7", scales and cadences by name, and melodies as "C4:1/4 D4:1/8".
+    ///
+    /// ### Failures
+    /// Returns an error if the answer cannot be read as an answer to the exercise.
+    pub fn parse_answer(&self, s: &str) -> Result<Answer> {
+        let invalid = || ExerciseError::InvalidAnswer(s.to_string());
+        let answer = match self.kind {
+            ExerciseKind::Interval => Answer::Interval(s.trim().parse().map_err(|_| invalid())?),
+            ExerciseKind::ChordQuality => {
+                Answer::ChordQuality(utils::parse_quality(s).ok_or_else(invalid)?)
+            }
+            ExerciseKind::Scale => Answer::Scale(s.parse().map_err(|_| invalid())?),
+            ExerciseKind::Cadence => Answer::Cadence(s.parse().map_err(|_| invalid())?),
+            ExerciseKind::Dictation => Answer::Melody(s.parse().map_err(|_| invalid())?),
+        };
+        Ok(answer)
+    }
+}
+
+impl Answer {
+    /// Returns true if the answers are the same, or if they sound the same and
+    /// enharmonic equivalents are accepted. Scales are the same if they have the same
+    /// steps, so names of the same scale always match.
+    fn matches(&self, other: &Answer, enharmonic: bool) -> bool {
+        match (self, other) {
+            (Answer::Interval(a), Answer::Interval(b)) => {
+                // Intervals compare equal by their semitones.
+                let spelled = a.quality() == b.quality() && a.size() == b.size();
+                a == b && (enharmonic || spelled && a.octaves() == b.octaves())
+            }
+            (Answer::ChordQuality(a), Answer::ChordQuality(b)) => {
+                let classes = |chord_type: &ChordType, root: i32| {
+                    let mut classes = vec![root];
+                    classes.extend(chord_type.intervals().iter().map(|i| root + i.to_semitones()));
+                    let mut classes = classes.iter().map(|c| c.rem_euclid(12)).collect::<Vec<_>>();
+                    classes.sort();
+                    classes.dedup();
+                    classes
+                };
+                a == b || enharmonic && (0..12).any(|root| classes(a, 0) == classes(b, root))
+            }
+            // Scales compare equal by the semitones of their intervals.
+            (Answer::Scale(a), Answer::Scale(b)) => a.to_scale() == b.to_scale(),
+            (Answer::Cadence(a), Answer::Cadence(b)) => a == b,
+            (Answer::Melody(a), Answer::Melody(b)) => {
+                let same = |a: &Event, b: &Event| match (a.pitch(), b.pitch()) {
+                    (Some(a), Some(b)) => a.to_midi_number() == b.to_midi_number(),
+                    (a, b) => a == b,
+                };
+                a == b
+                    || enharmonic
+                        && a.len() == b.len()
+                        && a.events()
+                            .iter()
+                            .zip(b.events())
+                            .all(|(a, b)| a.duration() == b.duration() && same(a, b))
+            }
+            _ => false,
+        }
+    }
+}
+
+impl ExerciseGenerator {
+    /// Creates a generator of exercises of the given difficulty, offering three choices
+    /// to beginners, four at the intermediate level and five at the advanced level,
+    /// and accepting no enharmonic equivalents.
+    pub fn new(difficulty: Difficulty) -> Self {
+        let choices = match difficulty {
+            Difficulty::Beginner => 3,
+            Difficulty::Intermediate => 4,
+            Difficulty::Advanced => 5,
+        };
+        ExerciseGenerator { difficulty, choices, enharmonic: false }
+    }
+
+    /// Sets how many choices exercises offer, including the answer. Exercises offer
+    /// fewer if there are not enough answers to choose from at their difficulty.
+    pub fn with_choices(mut self, choices: usize) -> Self {
+        self.choices = choices.max(1);
+        self
+    }
+
+    /// Sets whether exercises accept answers that sound the same as the answer. If they
+    /// do, no such answer is offered as a distractor.
+    pub fn with_enharmonic(mut self, enharmonic: bool) -> Self {
+        self.enharmonic = enharmonic;
+        self
+    }
+
+    /// Returns the difficulty of the exercises.
+    pub fn difficulty(&self) -> Difficulty {
+        self.difficulty
+    }
+
+    /// Returns how many choices exercises offer.
+    pub fn choices(&self) -> usize {
+        self.choices
+    }
+
+    /// Returns true if exercises accept enharmonic equivalents.
+    pub fn accepts_enharmonic(&self) -> bool {
+        self.enharmonic
+    }
+
+    /// Generates an exercise of the given kind from a seed.
+    ///
+    /// ### Failures
+    /// Returns an error if the notes of the exercise cannot be spelled in range.
+    pub fn generate(&self, kind: ExerciseKind, seed: u64) -> Result<Exercise> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let (prompt, key, answer, pool) = match kind {
+            ExerciseKind::Interval => self.interval(&mut rng)?,
+            ExerciseKind::ChordQuality => self.chord_quality(&mut rng)?,
+            ExerciseKind::Scale => self.scale(&mut rng)?,
+            ExerciseKind::Cadence => self.cadence(&mut rng)?,
+            ExerciseKind::Dictation => self.dictation(&mut rng)?,
+        };
+        let choices = self.choose(&answer, pool, &mut rng);
+        Ok(Exercise {
+            kind,
+            difficulty: self.difficulty,
+            prompt,
+            key,
+            answer,
+            choices,
+            enharmonic: self.enharmonic,
+        })
+    }
+
+    /// Picks the distractors from the pool, none of them matching the answer or each
+    /// other, and returns them with the answer in the order of the pool.
+    fn choose(&self, answer: &Answer, pool: Vec<Answer>, rng: &mut StdRng) -> Vec<Answer> {
+        if pool.is_empty() {
+            return Vec::new();
+        }
+        let mut indices = (0..pool.len()).collect::<Vec<_>>();
+        indices.shuffle(rng);
+        let mut chosen = vec![pool.iter().position(|a| a.matches(answer, false)).unwrap()];
+        for index in indices {
+            if chosen.len() == self.choices {
+                break;
+            }
+            if !chosen.iter().any(|i| pool[*i].matches(&pool[index], self.enharmonic)) {
+                chosen.push(index);
+            }
+        }
+        chosen.sort();
+        chosen.into_iter().map(|index| pool[index].clone()).collect()
+    }
+
+    fn interval(&self, rng: &mut StdRng) -> Result<Generated> {
+        let pool = match self.difficulty {
+            Difficulty::Beginner => &BEGINNER_INTERVALS[..],
+            Difficulty::Intermediate => &INTERMEDIATE_INTERVALS[..],
+            Difficulty::Advanced => &ADVANCED_INTERVALS[..],
+        };
+        let pool = pool.iter().map(|s| s.parse::<Interval>()).collect::<Result<Vec<_>>>()?;
+        let interval = *pool.choose(rng).unwrap();
+
+        let octave = if interval.octaves() > 0 { 3 } else { 4 };
+        let low = self.root(rng).with_octave(octave)?;
+        let high = (low + interval)?;
+        let prompt = match (self.difficulty, rng.gen_range(0..3)) {
+            (Difficulty::Beginner, _) | (_, 0) => melodic(&[low, high], Duration::half()),
+            (Difficulty::Advanced, 2) => vec![(vec![low, high], Duration::whole())],
+            _ => melodic(&[high, low], Duration::half()),
+        };
+        Ok((
+            prompt,
+            None,
+            Answer::Interval(interval),
+            pool.into_iter().map(Answer::Interval).collect(),
+        ))
+    }
+
+    fn chord_quality(&self, rng: &mut StdRng) -> Result<Generated> {
+        use ChordType::*;
+        let pool = match self.difficulty {
+            Difficulty::Beginner => vec![Major, Minor],
+            Difficulty::Intermediate => {
+                vec![Major, Minor, Diminished, Augmented, Dominant7, Major7, Minor7]
+            }
+            Difficulty::Advanced => vec![
+                Major,
+                Minor,
+                Diminished,
+                Augmented,
+                Suspended2,
+                Suspended4,
+                Major6,
+                Minor6,
+                Dominant7,
+                Major7,
+                Minor7,
+                MinorMajor7,
+                HalfDiminished7,
+                Diminished7,
+            ],
+        };
+        let chord_type = *pool.choose(rng).unwrap();
+        let mut notes = Chord::new(self.root(rng), chord_type).notes();
+        let octave = match self.difficulty {
+            Difficulty::Advanced => {
+                let inversion = rng.gen_range(0..notes.len());
+                notes.rotate_left(inversion);
+                3
+            }
+            _ => 4,
+        };
+        let pitches = stacked(&notes, octave)?;
+        let mut prompt = match self.difficulty {
+            Difficulty::Beginner => melodic(&pitches, Duration::quarter()),
+            _ => Vec::new(),
+        };
+        prompt.push((pitches, Duration::whole()));
+        let pool = pool.into_iter().map(Answer::ChordQuality).collect();
+        Ok((prompt, None, Answer::ChordQuality(chord_type), pool))
+    }
+
+    fn scale(&self, rng: &mut StdRng) -> Result<Generated> {
+        let modes = [
+            MajorMode::Ionian,
+            MajorMode::Dorian,
+            MajorMode::Phrygian,
+            MajorMode::Lydian,
+            MajorMode::Mixolydian,
+            MajorMode::Aeolian,
+            MajorMode::Locrian,
+        ];
+        let mut pool: Vec<ScaleEnumType> = match self.difficulty {
+            Difficulty::Beginner => vec![
+                ScaleType::Major.into(),
+                ScaleType::Minor.into(),
+                ScaleType::HarmonicMinor.into(),
+                ScaleType::MelodicMinor.into(),
+            ],
+            _ => modes.into_iter().map(ScaleEnumType::from).collect(),
+        };
+        if self.difficulty == Difficulty::Advanced {
+            pool.extend::<[ScaleEnumType; 10]>([
+                ScaleType::HarmonicMinor.into(),
+                ScaleType::MelodicMinor.into(),
+                HarmonicMinorMode::PhrygianDominant.into(),
+                MelodicMinorMode::LydianDominant.into(),
+                MelodicMinorMode::Altered.into(),
+                ScaleType::MajorPentatonic.into(),
+                ScaleType::MinorPentatonic.into(),
+                ScaleType::MinorBlues.into(),
+                ScaleType::WholeTone.into(),
+                ScaleType::Diminished.into(),
+            ]);
+        }
+        let scale_type = *pool.choose(rng).unwrap();
+        let root = self.root(rng);
+        let mut notes = scale_type.to_scale().to_notes(root);
+        notes.push(root);
+        let prompt = melodic(&stacked(&notes, 4)?, Duration::quarter());
+        Ok((prompt, None, Answer::Scale(scale_type), pool.into_iter().map(Answer::Scale).collect()))
+    }
+
+    fn cadence(&self, rng: &mut StdRng) -> Result<Generated> {
+        use Cadence::*;
+        let pool = match self.difficulty {
+            Difficulty::Beginner => vec![PerfectAuthentic, Half, Plagal],
+            Difficulty::Intermediate => {
+                vec![PerfectAuthentic, ImperfectAuthentic, Half, Plagal, Deceptive]
+            }
+            Difficulty::Advanced => {
+                vec![PerfectAuthentic, ImperfectAuthentic, Half, Plagal, Deceptive, PhrygianHalf]
+            }
+        };
+        let cadence = *pool.choose(rng).unwrap();
+        let minor =
+            cadence == PhrygianHalf || self.difficulty == Difficulty::Advanced && rng.gen_bool(0.5);
+        let (scale, tonics) = match minor {
+            true => (Scale::harmonic_minor(), MINOR_TONICS),
+            false => (Scale::major(), MAJOR_TONICS),
+        };
+        let tonic: Note = tonics.choose(rng).unwrap().parse()?;
+
+        let functions: &[&str] = match cadence {
+            PerfectAuthentic | ImperfectAuthentic => &["I", "IV", "V", "I"],
+            Half => &["I", "IV", "V"],
+            Plagal => &["I", "VI", "IV", "I"],
+            Deceptive => &["I", "IV", "V", "VI"],
+            PhrygianHalf => &["I", "IV", "V"],
+        };
+        let mut chords = functions
+            .iter()
+            .map(|function| function.parse::<ChordFunction>()?.to_chord(&scale, tonic, false))
+            .collect::<Result<Vec<_>>>()?;
+        if cadence == PhrygianHalf {
+            let iv = chords[1].clone();
+            chords[1] = iv.clone().with_bass(iv.notes()[1]);
+        }
+
+        // The soprano moves to the nearest chord tone, and ends on the tonic in a
+        // perfect authentic cadence and on the third in an imperfect one.
+        let mut soprano = tonic.with_octave(5)?;
+        let mut prompt = Vec::new();
+        for (i, chord) in chords.iter().enumerate() {
+            let last = i == chords.len() - 1;
+            let tones = match (cadence, last) {
+                (PerfectAuthentic, true) => vec![tonic],
+                (ImperfectAuthentic, true) => vec![chord.notes()[1]],
+                _ => chord.notes(),
+            };
+            soprano = tones
+                .iter()
+                .flat_map(|note| (4..=5).filter_map(|octave| note.with_octave(octave).ok()))
+                .filter(|pitch| (67..=79).contains(&pitch.to_midi_number()))
+                .min_by_key(|pitch| pitch.semitones_between(&soprano).abs())
+                .unwrap_or(soprano);
+            let duration = if last { Duration::whole() } else { Duration::half() };
+            prompt.push((voiced(chord, soprano)?, duration));
+        }
+        let pool = pool.into_iter().map(Answer::Cadence).collect();
+        Ok((prompt, Some((scale, tonic)), Answer::Cadence(cadence), pool))
+    }
+
+    fn dictation(&self, rng: &mut StdRng) -> Result<Generated> {
+        let (scale, tonics) = match (self.difficulty, rng.gen_bool(0.5)) {
+            (Difficulty::Beginner, _) | (_, false) => (Scale::major(), MAJOR_TONICS),
+            (Difficulty::Intermediate, true) => (Scale::minor(), MINOR_TONICS),
+            (Difficulty::Advanced, true) => (Scale::harmonic_minor(), MINOR_TONICS),
+        };
+        let tonic: Note = tonics.choose(rng).unwrap().parse()?;
+        let home = tonic.with_octave(4)?;
+        let quarter = Duration::quarter();
+        let (length, low, high, steps, rhythm) = match self.difficulty {
+            Difficulty::Beginner => {
+                let high = (home + "P8".parse::<Interval>()?)?;
+                (4, home, high, vec![0.0, 4.0, 1.0], vec![(quarter, 1.0)])
+            }
+            Difficulty::Intermediate => {
+                let (low, high) =
+                    ((home - "P4".parse::<Interval>()?)?, (home + "P8".parse::<Interval>()?)?);
+                let rhythm =
+                    vec![(quarter, 3.0), (Duration::half(), 1.0), (Duration::eighth(), 2.0)];
+                (6, low, high, vec![0.5, 4.0, 2.0, 1.0], rhythm)
+            }
+            Difficulty::Advanced => {
+                let (low, high) =
+                    ((home - "P5".parse::<Interval>()?)?, (home + "M10".parse::<Interval>()?)?);
+                let rhythm = vec![
+                    (quarter, 3.0),
+                    (Duration::half(), 1.0),
+                    (Duration::eighth(), 3.0),
+                    (quarter.dotted(1)?, 1.0),
+                ];
+                (8, low, high, vec![0.5, 3.0, 2.0, 1.5, 1.0, 0.5], rhythm)
+            }
+        };
+        let melody = MelodyGenerator::new(scale.clone(), tonic, low, high)?
+            .with_steps(steps)?
+            .with_rhythm(rhythm)?
+            .generate(length, rng.gen())?;
+        let prompt = melody
+            .events()
+            .iter()
+            .map(|event| (event.pitch().into_iter().collect(), event.duration()))
+            .collect();
+        Ok((prompt, Some((scale, tonic)), Answer::Melody(melody), Vec::new()))
+    }
+
+    /// Returns a random root, with no accidentals for beginners.
+    fn root(&self, rng: &mut StdRng) -> Note {
+        let roots = match self.difficulty {
+            Difficulty::Beginner => &NATURAL_ROOTS[..],
+            _ => &ROOTS[..],
+        };
+        roots.choose(rng).unwrap().parse().unwrap()
+    }
+}
+
+/// The prompt, key, answer and pool of answers of a generated exercise.
+type Generated = (Vec<(Vec<PitchedNote>, Duration)>, Option<(Scale, Note)>, Answer, Vec<Answer>);
+
+/// Returns the pitches played one after the other, each for the duration.
+fn melodic(pitches: &[PitchedNote], duration: Duration) -> Vec<(Vec<PitchedNote>, Duration)> {
+    pitches.iter().map(|pitch| (vec![*pitch], duration)).collect()
+}
+
+/// Returns the notes stacked upwards from the first, in the given octave, each above
+/// the one before.
+fn stacked(notes: &[Note], octave: i8) -> Result<Vec<PitchedNote>> {
+    let mut pitches: Vec<PitchedNote> = Vec::new();
+    for note in notes {
+        let mut pitch = note.with_octave(octave)?;
+        while pitches.last().is_some_and(|last| pitch.to_midi_number() <= last.to_midi_number()) {
+            pitch = note.with_octave(pitch.octave() + 1)?;
+        }
+        pitches.push(pitch);
+    }
+    Ok(pitches)
+}
+
+/// Returns the chord in four parts from the bass up, with its bass note between E2 and
+/// D#3, the soprano given, and the other chord tones filling the inner voices below it,
+/// doubling the root if the chord has only three notes.
+fn voiced(chord: &Chord, soprano: PitchedNote) -> Result<Vec<PitchedNote>> {
+    let bass = (2..=3)
+        .filter_map(|octave| chord.bass().with_octave(octave).ok())
+        .find(|pitch| (40..52).contains(&pitch.to_midi_number()))
+        .map_or_else(|| chord.bass().with_octave(3), Ok)?;
+    let soprano_class = Note::from(soprano).to_chromatic_scale_degree().rem_euclid(12);
+    let mut inner = chord
+        .notes()
+        .into_iter()
+        .filter(|note| note.to_chromatic_scale_degree().rem_euclid(12) != soprano_class)
+        .collect::<Vec<_>>();
+    inner.push(chord.root());
+    inner.truncate(2);
+
+    let mut voices = vec![soprano];
+    for note in inner {
+        let above = voices.last().unwrap().to_midi_number();
+        let pitch = (1..=6)
+            .filter_map(|octave| note.with_octave(octave).ok())
+            .filter(|pitch| pitch.to_midi_number() < above)
+            .max_by_key(|pitch| pitch.to_midi_number())
+            .map_or_else(|| note.with_octave(3), Ok)?;
+        voices.push(pitch);
+    }
+    voices.push(bass);
+    voices.reverse();
+    Ok(voices)
+}
@@ -0,0 +1,189 @@
+#[cfg(test)]
+mod tests {
+    use super::super::*;
+    use crate::{
+        harmony::{HarmonicSegmenter, Progression},
+        time,
+        time_signatures::TimeSignature,
+    };
+
+    const DIFFICULTIES: [Difficulty; 3] =
+        [Difficulty::Beginner, Difficulty::Intermediate, Difficulty::Advanced];
+
+    fn exercises(kind: ExerciseKind, enharmonic: bool) -> Vec<Exercise> {
+        let mut exercises = Vec::new();
+        for difficulty in DIFFICULTIES {
+            let generator = ExerciseGenerator::new(difficulty).with_enharmonic(enharmonic);
+            for seed in 0..30 {
+                let exercise = generator.generate(kind, seed).unwrap();
+                assert_eq!(exercise.kind(), kind);
+                assert_eq!(exercise.difficulty(), difficulty);
+                assert!(exercise.check(&exercise.answer().clone()));
+                if kind != ExerciseKind::Dictation {
+                    let choices = exercise.choices();
+                    assert!(choices.contains(exercise.answer()));
+                    assert!(choices.len() <= generator.choices());
+                    let right = choices.iter().filter(|choice| exercise.check(choice)).count();
+                    assert_eq!(right, 1, "{:?}", choices);
+                }
+                exercises.push(exercise);
+            }
+        }
+        exercises
+    }
+
+    fn midi(pitches: &[PitchedNote]) -> Vec<i32> {
+        pitches.iter().map(|pitch| pitch.to_midi_number() as i32).collect()
+    }
+
+    #[test]
+    fn test_interval_exercises() {
+        for exercise in exercises(ExerciseKind::Interval, false) {
+            let Answer::Interval(interval) = exercise.answer() else { panic!() };
+            let pitches = exercise.prompt().iter().flat_map(|(p, _)| p.clone()).collect::<Vec<_>>();
+            let (low, high) = match pitches[0].to_midi_number() < pitches[1].to_midi_number() {
+                true => (pitches[0], pitches[1]),
+                false => (pitches[1], pitches[0]),
+            };
+            assert_eq!(low.interval_to(&high).to_string(), interval.to_string());
+            if exercise.difficulty() == Difficulty::Beginner {
+                assert_eq!(pitches[0], low);
+                assert_eq!(exercise.choices().len(), 3);
+            }
+        }
+        let exercises = exercises(ExerciseKind::Interval, true);
+        assert!(exercises.iter().all(|exercise| exercise.accepts_enharmonic()));
+
+        let exercise = &exercises[0];
+        assert_eq!(exercise.parse_answer(" M3 "), Ok(Answer::Interval("M3".parse().unwrap())));
+        assert_eq!(
+            exercise.parse_answer("third"),
+            Err(ExerciseError::InvalidAnswer("third".to_string()).into())
+        );
+    }
+
+    #[test]
+    fn test_chord_quality_exercises() {
+        for exercise in exercises(ExerciseKind::ChordQuality, false) {
+            let Answer::ChordQuality(chord_type) = exercise.answer() else { panic!() };
+            let (pitches, duration) = exercise.prompt().last().unwrap();
+            assert_eq!(*duration, Duration::whole());
+            let pitches = midi(pitches);
+            assert!(pitches.windows(2).all(|pair| pair[0] < pair[1]));
+            let classes = |root: i32| {
+                let mut classes = vec![root];
+                classes.extend(chord_type.intervals().iter().map(|i| root + i.to_semitones()));
+                let mut classes = classes.iter().map(|c| c.rem_euclid(12)).collect::<Vec<_>>();
+                classes.sort();
+                classes
+            };
+            let mut played = pitches.iter().map(|p| p.rem_euclid(12)).collect::<Vec<_>>();
+            played.sort();
+            assert!((0..12).any(|root| classes(root) == played), "{:?}", exercise);
+            if exercise.difficulty() != Difficulty::Advanced {
+                assert_eq!(played, classes(pitches[0]));
+            }
+        }
+
+        let generator = ExerciseGenerator::new(Difficulty::Advanced);
+        let exercise = (0..)
+            .map(|seed| generator.generate(ExerciseKind::ChordQuality, seed).unwrap())
+            .find(|exercise| exercise.answer() == &Answer::ChordQuality(ChordType::Major6))
+            .unwrap();
+        assert!(exercise.check(&exercise.parse_answer("Major sixth").unwrap()));
+        assert!(exercise.check(&exercise.parse_answer("6").unwrap()));
+        let minor_seventh = exercise.parse_answer("m7").unwrap();
+        assert_eq!(minor_seventh.to_string(), "minor seventh");
+        assert!(!exercise.check(&minor_seventh));
+        assert!(exercise.clone().with_enharmonic(true).check(&minor_seventh));
+        assert!(exercise.parse_answer("7b9").is_err());
+        assert!(exercise.parse_answer("7/E").is_err());
+    }
+
+    #[test]
+    fn test_scale_exercises() {
+        for exercise in exercises(ExerciseKind::Scale, false) {
+            let Answer::Scale(scale_type) = exercise.answer() else { panic!() };
+            let pitches = exercise.prompt().iter().flat_map(|(p, _)| p.clone()).collect::<Vec<_>>();
+            let steps = midi(&pitches).windows(2).map(|pair| pair[1] - pair[0]).collect::<Vec<_>>();
+            assert_eq!(steps, scale_type.to_scale().to_steps());
+        }
+
+        let generator = ExerciseGenerator::new(Difficulty::Beginner);
+        let exercise = (0..)
+            .map(|seed| generator.generate(ExerciseKind::Scale, seed).unwrap())
+            .find(|exercise| exercise.answer() == &Answer::Scale(ScaleType::Minor.into()))
+            .unwrap();
+        assert!(exercise.check(&exercise.parse_answer("aeolian").unwrap()));
+        assert!(!exercise.check(&exercise.parse_answer("dorian").unwrap()));
+    }
+
+    #[test]
+    fn test_cadence_exercises() {
+        let segmenter = HarmonicSegmenter::new(time!(4, 4).unwrap());
+        for exercise in exercises(ExerciseKind::Cadence, false) {
+            let Answer::Cadence(cadence) = exercise.answer() else { panic!() };
+            // Each chord is labelled on its own.
+            let mut chords = Vec::new();
+            for (pitches, duration) in exercise.prompt() {
+                assert_eq!(pitches.len(), 4);
+                assert!(midi(pitches).windows(2).all(|pair| pair[0] <= pair[1]));
+                let voices = pitches
+                    .iter()
+                    .map(|pitch| Melody::from_notes(&[*pitch], *duration))
+                    .collect::<Vec<_>>();
+                chords.push(segmenter.segment(&voices)[0].chord().unwrap().clone());
+            }
+            let soprano = exercise.prompt().iter().map(|(p, _)| Note::from(p[3])).collect();
+            let (scale, tonic) = exercise.key().unwrap();
+            let progression =
+                Progression::new(chords, scale.clone(), tonic).with_soprano(soprano).unwrap();
+            let cadences = progression.cadences();
+            assert_eq!(cadences.last(), Some(&(exercise.prompt().len() - 1, *cadence)));
+        }
+
+        let exercise = ExerciseGenerator::new(Difficulty::Advanced)
+            .generate(ExerciseKind::Cadence, 0)
+            .unwrap();
+        assert_eq!(exercise.choices().len(), 5);
+        assert!(exercise.parse_answer("Deceptive cadence").is_ok());
+        assert!(exercise.parse_answer("interrupted").is_err());
+    }
+
+    #[test]
+    fn test_dictation_exercises() {
+        for exercise in exercises(ExerciseKind::Dictation, false) {
+            let Answer::Melody(melody) = exercise.answer() else { panic!() };
+            let length = match exercise.difficulty() {
+                Difficulty::Beginner => 4,
+                Difficulty::Intermediate => 6,
+                Difficulty::Advanced => 8,
+            };
+            assert_eq!(melody.len(), length);
+            assert!(exercise.choices().is_empty());
+            let (scale, tonic) = exercise.key().unwrap();
+            let notes = scale.to_notes(tonic);
+            assert!(melody.notes().iter().all(|note| notes.contains(&Note::from(*note))));
+            let played = exercise.prompt().iter().map(|(p, d)| (p[0], *d)).collect::<Vec<_>>();
+            let events = melody.events().iter().map(|e| (e.pitch().unwrap(), e.duration()));
+            assert_eq!(played, events.collect::<Vec<_>>());
+
+            // Respelling the first note is only right when enharmonics are accepted.
+            let mut respelled = Melody::default();
+            for (i, (pitch, duration)) in played.iter().enumerate() {
+                let pitch = match i {
+                    0 => pitch.to_enharmonic_equivalent(1).unwrap(),
+                    _ => *pitch,
+                };
+                respelled.push(Event::Note(pitch, *duration));
+            }
+            let respelled = Answer::Melody(respelled);
+            assert!(!exercise.check(&respelled));
+            assert!(exercise.clone().with_enharmonic(true).check(&respelled));
+
+            let mut shorter = melody.clone();
+            shorter.push(Event::Note(played[0].0, Duration::quarter()));
+            assert!(!exercise.clone().with_enharmonic(true).check(&Answer::Melody(shorter)));
+        }
+    }
+}
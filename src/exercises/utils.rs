@@ -0,0 +1,83 @@
+use super::*;
+use std::fmt::{self, Display, Formatter};
+
+/// The name of each chord quality, as offered in exercises.
+static QUALITIES: [(ChordType, &str); 25] = [
+    (ChordType::Major, "major"),
+    (ChordType::Minor, "minor"),
+    (ChordType::Diminished, "diminished"),
+    (ChordType::Augmented, "augmented"),
+    (ChordType::Suspended2, "suspended second"),
+    (ChordType::Suspended4, "suspended fourth"),
+    (ChordType::Power, "power chord"),
+    (ChordType::Major6, "major sixth"),
+    (ChordType::Minor6, "minor sixth"),
+    (ChordType::SixNine, "six-nine"),
+    (ChordType::Dominant7, "dominant seventh"),
+    (ChordType::Major7, "major seventh"),
+    (ChordType::Minor7, "minor seventh"),
+    (ChordType::MinorMajor7, "minor major seventh"),
+    (ChordType::HalfDiminished7, "half-diminished seventh"),
+    (ChordType::Diminished7, "diminished seventh"),
+    (ChordType::Dominant7Sus4, "dominant seventh suspended fourth"),
+    (ChordType::Dominant9, "dominant ninth"),
+    (ChordType::Major9, "major ninth"),
+    (ChordType::Minor9, "minor ninth"),
+    (ChordType::Dominant11, "dominant eleventh"),
+    (ChordType::Minor11, "minor eleventh"),
+    (ChordType::Dominant13, "dominant thirteenth"),
+    (ChordType::Major13, "major thirteenth"),
+    (ChordType::Minor13, "minor thirteenth"),
+];
+
+/// Parses a chord quality from its name, ignoring case, or from its chord symbol suffix.
+pub(super) fn parse_quality(s: &str) -> Option<ChordType> {
+    let name = s.trim().to_lowercase();
+    if let Some((chord_type, _)) = QUALITIES.iter().find(|(_, n)| *n == name) {
+        return Some(*chord_type);
+    }
+    let chord = format!("C{}", s.trim()).parse::<Chord>().ok()?;
+    match chord.alterations().is_empty() && chord.bass() == chord.root() {
+        true => Some(chord.chord_type()),
+        false => None,
+    }
+}
+
+impl Display for Difficulty {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Difficulty::Beginner => "beginner",
+            Difficulty::Intermediate => "intermediate",
+            Difficulty::Advanced => "advanced",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl Display for ExerciseKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ExerciseKind::Interval => "interval identification",
+            ExerciseKind::ChordQuality => "chord quality identification",
+            ExerciseKind::Scale => "scale identification",
+            ExerciseKind::Cadence => "cadence identification",
+            ExerciseKind::Dictation => "melodic dictation",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Interval(interval) => write!(f, "{}", interval),
+            Answer::ChordQuality(chord_type) => {
+                let (_, name) = QUALITIES.iter().find(|(t, _)| t == chord_type).unwrap();
+                write!(f, "{}", name)
+            }
+            Answer::Scale(scale_type) => write!(f, "{}", scale_type),
+            Answer::Cadence(cadence) => write!(f, "{}", cadence),
+            Answer::Melody(melody) => write!(f, "{}", melody),
+        }
+    }
+}
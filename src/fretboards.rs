@@ -0,0 +1,317 @@
+use crate::{
+    chords::Chord,
+    error::{FretboardError, ResonataError},
+    nope,
+    notes::*,
+    scales::Scale,
+};
+use std::ops::RangeInclusive;
+
+pub use crate::tuning;
+pub use voicings::{CagedShape, Voicing, VoicingOptions};
+
+mod tests;
+mod utils;
+mod voicings;
+
+type Result<T> = std::result::Result<T, ResonataError>;
+
+/// The open string pitches of a fretted instrument
+///
+/// Strings are listed in the order they are strung, starting from the string that is
+/// lowest on the page in tablature, which is the lowest sounding string on most
+/// instruments. Re-entrant tunings, such as the ukulele's, list a higher string first.
+///
+/// A macro is provided to make creating tunings easier:
+/// tuning!(string)
+///
+/// ### Examples
+/// ```
+/// use resonata::{notes::*, fretboards::*};
+///
+/// let tuning = tuning!("E2 A2 D3 G3 B3 E4").unwrap();
+/// assert_eq!(tuning, Tuning::standard());
+/// assert_eq!(tuning.strings()[0], pnote!("E2").unwrap());
+/// assert_eq!(tuning!("drop d").unwrap(), Tuning::drop_d());
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct Tuning {
+    strings: Vec<PitchedNote>,
+}
+
+/// A string and fret on a fretboard. Strings are numbered from 0, in the order of the
+/// tuning, and fret 0 is the open string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Position {
+    string: usize,
+    fret: u8,
+}
+
+/// The fretboard of a fretted instrument, given by its tuning and number of frets
+///
+/// ### Examples
+/// ```
+/// use resonata::{notes::*, scales::*, fretboards::*};
+///
+/// let fretboard = Fretboard::new(Tuning::standard(), 22);
+/// assert_eq!(fretboard.pitch_at(Position::new(1, 3)).unwrap(), pnote!("C3").unwrap());
+///
+/// let positions = fretboard.positions_of_pitch(pnote!("E4").unwrap());
+/// assert_eq!(positions, vec![
+///     Position::new(1, 19),
+///     Position::new(2, 14),
+///     Position::new(3, 9),
+///     Position::new(4, 5),
+///     Position::new(5, 0),
+/// ]);
+///
+/// // Notes are spelled as the scale spells them.
+/// let notes = fretboard.scale_positions(&Scale::major(), note!("Cb").unwrap());
+/// assert_eq!(notes[0], (Position::new(0, 0), pnote!("Fb2").unwrap()));
+/// assert!(notes.iter().all(|(_, pitch)| pitch.accidental() != Accidental::Sharp(1)));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fretboard {
+    tuning: Tuning,
+    frets: u8,
+}
+
+/// A macro to create a tuning.
+#[macro_export]
+macro_rules! tuning {
+    ($str:literal) => {
+        $str.parse::<Tuning>()
+    };
+}
+
+impl Tuning {
+    /// Creates a tuning from its open string pitches, in string order.
+    ///
+    /// ### Failures
+    /// Returns an error if there are no strings.
+    pub fn new(strings: Vec<PitchedNote>) -> Result<Self> {
+        if strings.is_empty() {
+            nope!(FretboardError::InvalidTuning(String::new()));
+        }
+        Ok(Tuning { strings })
+    }
+
+    /// Six string guitar in standard tuning, E2 A2 D3 G3 B3 E4.
+    pub fn standard() -> Self {
+        tuning!("E2 A2 D3 G3 B3 E4").unwrap()
+    }
+
+    /// Six string guitar in drop D tuning, D2 A2 D3 G3 B3 E4.
+    pub fn drop_d() -> Self {
+        tuning!("D2 A2 D3 G3 B3 E4").unwrap()
+    }
+
+    /// Six string guitar in DADGAD tuning, D2 A2 D3 G3 A3 D4.
+    pub fn dadgad() -> Self {
+        tuning!("D2 A2 D3 G3 A3 D4").unwrap()
+    }
+
+    /// Seven string guitar in standard tuning, B1 E2 A2 D3 G3 B3 E4.
+    pub fn seven_string() -> Self {
+        tuning!("B1 E2 A2 D3 G3 B3 E4").unwrap()
+    }
+
+    /// Four string bass in standard tuning, E1 A1 D2 G2.
+    pub fn bass() -> Self {
+        tuning!("E1 A1 D2 G2").unwrap()
+    }
+
+    /// Ukulele in re-entrant standard tuning, G4 C4 E4 A4.
+    pub fn ukulele() -> Self {
+        tuning!("G4 C4 E4 A4").unwrap()
+    }
+
+    /// Returns the open string pitches, in string order.
+    pub fn strings(&self) -> &Vec<PitchedNote> {
+        &self.strings
+    }
+
+    /// Returns the number of strings.
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    /// Returns true if the tuning has no strings, which never happens for a tuning
+    /// made with `Tuning::new`.
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty()
+    }
+}
+
+impl Position {
+    /// Creates a position from a string, counting from 0, and a fret.
+    pub fn new(string: usize, fret: u8) -> Self {
+        Position { string, fret }
+    }
+
+    /// Returns the string of the position.
+    pub fn string(&self) -> usize {
+        self.string
+    }
+
+    /// Returns the fret of the position, where 0 is the open string.
+    pub fn fret(&self) -> u8 {
+        self.fret
+    }
+}
+
+impl Fretboard {
+    /// Creates a fretboard from a tuning and a number of frets.
+    pub fn new(tuning: Tuning, frets: u8) -> Self {
+        Fretboard { tuning, frets }
+    }
+
+    /// Returns the tuning of the fretboard.
+    pub fn tuning(&self) -> &Tuning {
+        &self.tuning
+    }
+
+    /// Returns the number of frets.
+    pub fn frets(&self) -> u8 {
+        self.frets
+    }
+
+    /// Returns every position on the fretboard, string by string.
+    pub fn positions(&self) -> Vec<Position> {
+        (0..self.tuning.len())
+            .flat_map(|string| (0..=self.frets).map(move |fret| Position::new(string, fret)))
+            .collect()
+    }
+
+    /// Returns the pitch sounding at a position, spelled with sharps.
+    ///
+    /// ### Failures
+    /// Returns an error if the position is not on the fretboard.
+    pub fn pitch_at(&self, position: Position) -> Result<PitchedNote> {
+        PitchedNote::from_midi_number(self.midi_number_at(position)?)
+    }
+
+    /// Returns every position where the given pitch sounds, string by string.
+    pub fn positions_of_pitch(&self, pitch: PitchedNote) -> Vec<Position> {
+        let midi = pitch.to_midi_number();
+        self.positions()
+            .into_iter()
+            .filter(|position| self.midi_number_at(*position).ok() == Some(midi))
+            .collect()
+    }
+
+    /// Returns every position where any of the given notes sounds, in any octave, with
+    /// the pitch spelled as the note it matches, string by string.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::{notes::*, fretboards::*};
+    ///
+    /// let fretboard = Fretboard::new(Tuning::bass(), 5);
+    /// let notes = fretboard.positions_of_notes(&[note!("Ab").unwrap()]);
+    /// assert_eq!(notes, vec![
+    ///     (Position::new(0, 4), pnote!("Ab1").unwrap()),
+    ///     (Position::new(3, 1), pnote!("Ab2").unwrap()),
+    /// ]);
+    /// ```
+    pub fn positions_of_notes(&self, notes: &[Note]) -> Vec<(Position, PitchedNote)> {
+        self.positions()
+            .into_iter()
+            .filter_map(|position| {
+                let midi = self.midi_number_at(position).ok()?;
+                Some((position, spelled_pitch(midi, notes)?))
+            })
+            .collect()
+    }
+
+    /// Returns every position of the notes of a scale on the given root, spelled as
+    /// in the scale.
+    pub fn scale_positions(&self, scale: &Scale, root: Note) -> Vec<(Position, PitchedNote)> {
+        self.positions_of_notes(&scale.to_notes(root))
+    }
+
+    /// Returns every position of the notes of a chord, spelled as in the chord.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::{notes::*, chords::*, fretboards::*};
+    ///
+    /// let fretboard = Fretboard::new(Tuning::standard(), 3);
+    /// let positions = fretboard.chord_positions(&chord!("E7").unwrap());
+    /// let on_g_string = positions.iter().filter(|(p, _)| p.string() == 3).collect::<Vec<_>>();
+    /// assert_eq!(on_g_string, vec![
+    ///     &(Position::new(3, 1), pnote!("G#3").unwrap()),
+    /// ]);
+    /// ```
+    pub fn chord_positions(&self, chord: &Chord) -> Vec<(Position, PitchedNote)> {
+        self.positions_of_notes(&chord.notes())
+    }
+
+    /// Draws the given notes on the fretboard over a range of frets, with the highest
+    /// string at the top as in tablature. Notes are shown with their spelling, so the
+    /// same pattern reads differently in different keys.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::{notes::*, scales::*, fretboards::*};
+    ///
+    /// let fretboard = Fretboard::new(Tuning::bass(), 20);
+    /// let notes = Scale::minor_pentatonic().to_notes(note!("G").unwrap());
+    /// assert_eq!(fretboard.pattern(&notes, 1..=5), [
+    ///     "     1    2    3    4    5",
+    ///     "G2 |----|----|-B♭-|----|-C--|",
+    ///     "D2 |----|----|-F--|----|-G--|",
+    ///     "A1 |-B♭-|----|-C--|----|-D--|",
+    ///     "E1 |-F--|----|-G--|----|----|",
+    /// ].join("\n"));
+    /// ```
+    pub fn pattern(&self, notes: &[Note], frets: RangeInclusive<u8>) -> String {
+        let labels = self.tuning.strings.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let width = labels.iter().map(|label| label.chars().count()).max().unwrap_or(0);
+        let found = self.positions_of_notes(notes);
+
+        let mut lines = vec![format!(
+            "{}{}",
+            " ".repeat(width + 2),
+            frets.clone().map(|fret| format!(" {:<4}", fret)).collect::<String>().trim_end()
+        )];
+        for (string, label) in labels.iter().enumerate().rev() {
+            let mut line = format!("{}{} |", label, " ".repeat(width - label.chars().count()));
+            for fret in frets.clone() {
+                let pitch = found.iter().find(|(p, _)| *p == Position::new(string, fret));
+                let name =
+                    pitch.map(|(_, pitch)| Note::from(*pitch).to_string()).unwrap_or_default();
+                line.push_str(&format!(
+                    "-{}{}|",
+                    name,
+                    "-".repeat(3 - name.chars().count().min(3))
+                ));
+            }
+            lines.push(line);
+        }
+        lines.join("\n")
+    }
+
+    /// Returns the MIDI number sounding at a position.
+    fn midi_number_at(&self, position: Position) -> Result<u8> {
+        match self.tuning.strings.get(position.string) {
+            Some(open) if position.fret <= self.frets => {
+                let midi = open.to_midi_number() as u32 + position.fret as u32;
+                match midi {
+                    0..=127 => Ok(midi as u8),
+                    _ => nope!(FretboardError::InvalidPosition(position.string, position.fret)),
+                }
+            }
+            _ => nope!(FretboardError::InvalidPosition(position.string, position.fret)),
+        }
+    }
+}
+
+/// Spells a MIDI number as the first of the given notes with its pitch class, if any.
+fn spelled_pitch(midi: u8, notes: &[Note]) -> Option<PitchedNote> {
+    let midi = midi as i32;
+    let note =
+        notes.iter().find(|note| (midi - note.to_chromatic_scale_degree()).rem_euclid(12) == 0)?;
+    let octave = (midi - note.to_chromatic_scale_degree()) / 12 - 1;
+    note.with_octave(octave as i8).ok()
+}
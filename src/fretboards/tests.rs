@@ -0,0 +1,108 @@
+#[cfg(test)]
+mod tests {
+    use super::super::*;
+    use crate::chords::*;
+
+    #[test]
+    fn test_tunings() {
+        let cases = [
+            (Tuning::standard(), "E2 A2 D3 G3 B3 E4"),
+            (Tuning::drop_d(), "D2 A2 D3 G3 B3 E4"),
+            (Tuning::dadgad(), "D2 A2 D3 G3 A3 D4"),
+            (Tuning::seven_string(), "B1 E2 A2 D3 G3 B3 E4"),
+            (Tuning::bass(), "E1 A1 D2 G2"),
+            (Tuning::ukulele(), "G4 C4 E4 A4"),
+        ];
+        for (tuning, pitches) in cases {
+            assert_eq!(tuning.to_string(), pitches);
+            assert_eq!(pitches.parse::<Tuning>().unwrap(), tuning);
+        }
+        assert_eq!("Drop-D".parse::<Tuning>().unwrap(), Tuning::drop_d());
+        assert_eq!("7 string".parse::<Tuning>().unwrap(), Tuning::seven_string());
+        assert!("".parse::<Tuning>().is_err());
+        assert!("E2 H2".parse::<Tuning>().is_err());
+        assert!(Tuning::new(Vec::new()).is_err());
+    }
+
+    #[test]
+    fn test_pitches() {
+        let fretboard = Fretboard::new(Tuning::standard(), 22);
+        assert_eq!(fretboard.positions().len(), 6 * 23);
+        assert_eq!(fretboard.pitch_at(Position::new(0, 0)).unwrap(), pnote!("E2").unwrap());
+        assert_eq!(fretboard.pitch_at(Position::new(5, 22)).unwrap(), pnote!("D6").unwrap());
+        assert_eq!(fretboard.pitch_at(Position::new(4, 1)).unwrap(), pnote!("C4").unwrap());
+        assert!(fretboard.pitch_at(Position::new(5, 23)).is_err());
+        assert!(fretboard.pitch_at(Position::new(6, 0)).is_err());
+
+        // The ukulele's re-entrant G string is higher than its C string.
+        let ukulele = Fretboard::new(Tuning::ukulele(), 12);
+        let positions = ukulele.positions_of_pitch(pnote!("G4").unwrap());
+        assert_eq!(positions, vec![Position::new(0, 0), Position::new(1, 7), Position::new(2, 3)]);
+    }
+
+    #[test]
+    fn test_spelling() {
+        let fretboard = Fretboard::new(Tuning::drop_d(), 12);
+        let found = fretboard.positions_of_notes(&[note!("B#").unwrap(), note!("Cb").unwrap()]);
+        assert!(found.iter().any(|(_, pitch)| *pitch == pnote!("B#2").unwrap()));
+        assert!(found.iter().any(|(_, pitch)| *pitch == pnote!("Cb3").unwrap()));
+        for (position, pitch) in &found {
+            let sounding = fretboard.pitch_at(*position).unwrap();
+            assert_eq!(sounding.to_midi_number(), pitch.to_midi_number());
+        }
+
+        let positions = fretboard.chord_positions(&chord!("C#maj7").unwrap());
+        let names = positions.iter().map(|(_, pitch)| Note::from(*pitch)).collect::<Vec<_>>();
+        assert!(names.contains(&note!("B#").unwrap()) && names.contains(&note!("E#").unwrap()));
+        assert!(!names.contains(&note!("F").unwrap()));
+    }
+
+    #[test]
+    fn test_voicings() {
+        let fretboard = Fretboard::new(Tuning::standard(), 15);
+        let chord = chord!("G7").unwrap();
+        let voicings = fretboard.voicings(&chord, &VoicingOptions::new());
+        assert_eq!(voicings[0].to_string(), "320001");
+        assert_eq!(voicings[0].shape(), Some(CagedShape::G));
+        assert_eq!(voicings, fretboard.voicings(&chord, &VoicingOptions::new()));
+        assert!(voicings.windows(2).all(|pair| pair[0].difficulty() <= pair[1].difficulty()));
+        for voicing in &voicings {
+            let bass = voicing.pitches().iter().min_by_key(|pitch| pitch.to_midi_number());
+            assert_eq!(Note::from(*bass.unwrap()), note!("G").unwrap());
+            assert!(voicing.pitches().iter().all(|pitch| chord.contains(Note::from(*pitch))));
+            assert!(voicing.fingers() <= 4 && voicing.span() <= 4);
+        }
+
+        let options = VoicingOptions::new()
+            .with_open_strings(false)
+            .with_barres(false)
+            .with_muted_strings(false)
+            .with_max_fingers(3);
+        assert!(fretboard.voicings(&chord!("F").unwrap(), &options).is_empty());
+        let options = options.with_muted_strings(true);
+        let voicings = fretboard.voicings(&chord, &options);
+        assert!(!voicings.is_empty());
+        for voicing in &voicings {
+            assert!(voicing.frets().iter().flatten().all(|fret| *fret > 0));
+            assert!(voicing.barre().is_none() && voicing.fingers() <= 3);
+        }
+
+        // A slash chord is voiced over its bass, and the fifth of a seventh chord may go.
+        let voicings = fretboard.voicings(&chord!("C/E").unwrap(), &VoicingOptions::new());
+        assert_eq!(voicings[0].to_string(), "032010");
+        let voicings = fretboard.voicings(&chord!("Cmaj7").unwrap(), &VoicingOptions::new());
+        assert!(voicings.iter().any(|voicing| voicing.to_string() == "x3x453"));
+        assert!(voicings.iter().any(|voicing| voicing.to_string() == "x-x-10-12-12-12"));
+    }
+
+    #[test]
+    fn test_diagram() {
+        let fretboard = Fretboard::new(Tuning::standard(), 15);
+        let voicings = fretboard.voicings(&chord!("Cmaj7").unwrap(), &VoicingOptions::new());
+        let voicing = voicings.iter().find(|v| v.to_string() == "x-x-10-12-12-12").unwrap();
+        assert_eq!(
+            voicing.diagram(),
+            ["| | O | | | 10fr", "| | | | | |", "| | | O O O", "| | | | | |"].join("\n")
+        );
+    }
+}
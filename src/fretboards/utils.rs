@@ -0,0 +1,73 @@
+use super::*;
+use std::{
+    fmt::{self, Debug, Display, Formatter},
+    str::FromStr,
+};
+
+impl FromStr for Tuning {
+    type Err = ResonataError;
+
+    /// Parses a tuning from whitespace separated pitches in string order, such as
+    /// "E2 A2 D3 G3 B3 E4", or from the name of a common tuning, such as "drop D".
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let name = s.to_lowercase().replace([' ', '-', '_'], "");
+        match name.as_str() {
+            "standard" | "guitar" => return Ok(Tuning::standard()),
+            "dropd" => return Ok(Tuning::drop_d()),
+            "dadgad" => return Ok(Tuning::dadgad()),
+            "sevenstring" | "7string" => return Ok(Tuning::seven_string()),
+            "bass" => return Ok(Tuning::bass()),
+            "ukulele" => return Ok(Tuning::ukulele()),
+            _ => (),
+        }
+        let strings = s.split_whitespace().map(|pitch| pitch.parse::<PitchedNote>());
+        match strings.collect::<Result<Vec<_>>>() {
+            Ok(strings) if !strings.is_empty() => Tuning::new(strings),
+            _ => nope!(FretboardError::InvalidTuning(s.to_string())),
+        }
+    }
+}
+
+impl Display for Tuning {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let strings = self.strings.iter().map(|pitch| pitch.to_string()).collect::<Vec<_>>();
+        write!(f, "{}", strings.join(" "))
+    }
+}
+
+impl Debug for Tuning {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+impl Display for Voicing {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let frets = self
+            .frets()
+            .iter()
+            .map(|fret| fret.map_or("x".to_string(), |fret| fret.to_string()))
+            .collect::<Vec<_>>();
+        let separator = if self.frets().iter().flatten().any(|fret| *fret > 9) { "-" } else { "" };
+        write!(f, "{}", frets.join(separator))
+    }
+}
+
+impl Debug for Voicing {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+impl Display for CagedShape {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            CagedShape::C => "C",
+            CagedShape::A => "A",
+            CagedShape::G => "G",
+            CagedShape::E => "E",
+            CagedShape::D => "D",
+        };
+        write!(f, "{} shape", name)
+    }
+}
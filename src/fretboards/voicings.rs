@@ -0,0 +1,533 @@
+use super::*;
+use std::collections::BTreeSet;
+
+/// The five chord shapes of the CAGED system, named for the open chords they come from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum CagedShape {
+    C,
+    A,
+    G,
+    E,
+    D,
+}
+
+/// Limits on the voicings generated by [Fretboard::voicings].
+///
+/// By default a voicing spans at most four frets, uses at most four fingers, and may
+/// use open strings, barres and muted strings.
+///
+/// ### Examples
+/// ```
+/// use resonata::fretboards::*;
+///
+/// let options = VoicingOptions::new().with_open_strings(false).with_max_fingers(3);
+/// assert_eq!(options.max_fingers(), 3);
+/// assert!(!options.open_strings());
+/// assert!(options.barres());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct VoicingOptions {
+    max_span: u8,
+    max_fingers: u8,
+    open_strings: bool,
+    barres: bool,
+    muted_strings: bool,
+}
+
+/// A way of playing a chord on a fretboard, with a fret or a mute for every string
+///
+/// A voicing is written with one character per string, in string order, where "x" is a
+/// muted string, as in "x32010". Voicings reaching past the ninth fret separate the
+/// strings with dashes, as in "x-10-12-12-12-10".
+///
+/// ### Examples
+/// ```
+/// use resonata::{notes::*, chords::*, fretboards::*};
+///
+/// let fretboard = Fretboard::new(Tuning::standard(), 15);
+/// let voicings = fretboard.voicings(&chord!("F").unwrap(), &VoicingOptions::new());
+/// let barre = voicings.iter().find(|v| v.to_string() == "133211").unwrap();
+/// assert_eq!(barre.barre(), Some(1));
+/// assert_eq!(barre.fingers(), 4);
+/// assert_eq!(barre.shape(), Some(CagedShape::E));
+/// assert_eq!(barre.pitches()[0], pnote!("F2").unwrap());
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct Voicing {
+    frets: Vec<Option<u8>>,
+    pitches: Vec<PitchedNote>,
+    fingers: u8,
+    barre: Option<u8>,
+    difficulty: u32,
+    shape: Option<CagedShape>,
+}
+
+impl Default for VoicingOptions {
+    fn default() -> Self {
+        VoicingOptions {
+            max_span: 4,
+            max_fingers: 4,
+            open_strings: true,
+            barres: true,
+            muted_strings: true,
+        }
+    }
+}
+
+impl VoicingOptions {
+    /// Creates the default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the options with the given largest number of frets a voicing may span,
+    /// counting both ends and leaving out open strings.
+    pub fn with_max_span(mut self, max_span: u8) -> Self {
+        self.max_span = max_span.max(1);
+        self
+    }
+
+    /// Returns the options with the given largest number of fretting fingers.
+    pub fn with_max_fingers(mut self, max_fingers: u8) -> Self {
+        self.max_fingers = max_fingers;
+        self
+    }
+
+    /// Returns the options allowing or forbidding open strings.
+    pub fn with_open_strings(mut self, open_strings: bool) -> Self {
+        self.open_strings = open_strings;
+        self
+    }
+
+    /// Returns the options allowing or forbidding barres.
+    pub fn with_barres(mut self, barres: bool) -> Self {
+        self.barres = barres;
+        self
+    }
+
+    /// Returns the options allowing or forbidding muted strings.
+    pub fn with_muted_strings(mut self, muted_strings: bool) -> Self {
+        self.muted_strings = muted_strings;
+        self
+    }
+
+    /// Returns the largest number of frets a voicing may span.
+    pub fn max_span(&self) -> u8 {
+        self.max_span
+    }
+
+    /// Returns the largest number of fretting fingers.
+    pub fn max_fingers(&self) -> u8 {
+        self.max_fingers
+    }
+
+    /// Returns true if open strings are allowed.
+    pub fn open_strings(&self) -> bool {
+        self.open_strings
+    }
+
+    /// Returns true if barres are allowed.
+    pub fn barres(&self) -> bool {
+        self.barres
+    }
+
+    /// Returns true if muted strings are allowed.
+    pub fn muted_strings(&self) -> bool {
+        self.muted_strings
+    }
+}
+
+impl Voicing {
+    /// Returns the fret played on each string, in string order, or None for a muted string.
+    pub fn frets(&self) -> &Vec<Option<u8>> {
+        &self.frets
+    }
+
+    /// Returns the sounding pitches, in string order, spelled as in the chord.
+    pub fn pitches(&self) -> &Vec<PitchedNote> {
+        &self.pitches
+    }
+
+    /// Returns the number of fretting fingers needed, counting a barre as one finger.
+    pub fn fingers(&self) -> u8 {
+        self.fingers
+    }
+
+    /// Returns the fret of the barre, if the voicing needs one.
+    pub fn barre(&self) -> Option<u8> {
+        self.barre
+    }
+
+    /// Returns the number of frets between the lowest and highest fretted notes,
+    /// counting both, or 0 if every string is open or muted.
+    pub fn span(&self) -> u8 {
+        let fretted = self.frets.iter().flatten().filter(|fret| **fret > 0);
+        match (fretted.clone().min(), fretted.max()) {
+            (Some(low), Some(high)) => high - low + 1,
+            _ => 0,
+        }
+    }
+
+    /// Returns the difficulty of the voicing. Lower is easier. It counts one for each
+    /// finger, each fret spanned past the first, every five frets up the neck and each
+    /// muted string, plus two for a barre, two more for each muted string between
+    /// sounding strings, and one for each chord tone left out.
+    pub fn difficulty(&self) -> u32 {
+        self.difficulty
+    }
+
+    /// Returns the CAGED shape of the voicing, if it is played in standard guitar tuning
+    /// and follows the shape of a major or minor triad.
+    pub fn shape(&self) -> Option<CagedShape> {
+        self.shape
+    }
+
+    /// Draws the voicing as a chord diagram, with the lowest string on the left. Muted
+    /// strings are marked "x" and open strings "o" above the nut, and a barre joins the
+    /// strings it holds down. A voicing that does not fit in the first four frets is
+    /// drawn from its lowest fret, which is labelled to the right.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::{chords::*, fretboards::*};
+    ///
+    /// let fretboard = Fretboard::new(Tuning::standard(), 15);
+    /// let voicings = fretboard.voicings(&chord!("C").unwrap(), &VoicingOptions::new());
+    /// assert_eq!(voicings[0].diagram(), [
+    ///     "x     o   o",
+    ///     "===========",
+    ///     "| | | | O |",
+    ///     "| | O | | |",
+    ///     "| O | | | |",
+    ///     "| | | | | |",
+    /// ].join("\n"));
+    ///
+    /// let options = VoicingOptions::new().with_open_strings(false).with_muted_strings(false);
+    /// let voicings = fretboard.voicings(&chord!("G").unwrap(), &options);
+    /// assert_eq!(voicings[0].diagram(), [
+    ///     "O-O-O-O-O-O 3fr",
+    ///     "| | | O | |",
+    ///     "| O O | | |",
+    ///     "| | | | | |",
+    /// ].join("\n"));
+    /// ```
+    pub fn diagram(&self) -> String {
+        let fretted = self.frets.iter().flatten().filter(|fret| **fret > 0);
+        let highest = fretted.clone().max().copied().unwrap_or(0);
+        let start = match highest {
+            0..=4 => 1,
+            _ => fretted.min().copied().unwrap_or(1),
+        };
+        let rows = 4.max(highest.saturating_sub(start) + 1);
+
+        let mut lines = Vec::new();
+        if start == 1 {
+            let marks = self.frets.iter().map(|fret| match fret {
+                None => "x",
+                Some(0) => "o",
+                Some(_) => " ",
+            });
+            lines.push(marks.collect::<Vec<_>>().join(" ").trim_end().to_string());
+            lines.push("=".repeat(self.frets.len() * 2 - 1));
+        }
+        for fret in start..start + rows {
+            let barred = match self.barre {
+                Some(barre) if barre == fret => {
+                    let first = self.frets.iter().position(|f| *f == Some(fret));
+                    let last = self.frets.iter().rposition(|f| *f == Some(fret));
+                    first.zip(last)
+                }
+                _ => None,
+            };
+            let mut line = String::new();
+            for (string, played) in self.frets.iter().enumerate() {
+                let held = barred.is_some_and(|(first, last)| (first..=last).contains(&string));
+                if string > 0 {
+                    let joined =
+                        barred.is_some_and(|(first, last)| first < string && string <= last);
+                    line.push(if joined { '-' } else { ' ' });
+                }
+                line.push(if held || *played == Some(fret) { 'O' } else { '|' });
+            }
+            if fret == start && start > 1 {
+                line.push_str(&format!(" {}fr", start));
+            }
+            lines.push(line);
+        }
+        lines.join("\n")
+    }
+}
+
+impl Fretboard {
+    /// Returns every playable voicing of a chord within the given limits, easiest first.
+    /// Every voicing has the chord's bass as its lowest note, sounds on at least three
+    /// strings, and contains every chord tone, except that the perfect fifth of a chord of
+    /// four or more notes, and a ninth or eleventh under a higher extension, may be left
+    /// out. Voicings of equal difficulty are ordered by the number of muted strings, then
+    /// by position up the neck, then by their frets, so the order is always the same.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::{chords::*, fretboards::*};
+    ///
+    /// let fretboard = Fretboard::new(Tuning::standard(), 15);
+    /// let voicings = fretboard.voicings(&chord!("C").unwrap(), &VoicingOptions::new());
+    /// assert_eq!(voicings[0].to_string(), "x32010");
+    /// assert_eq!(voicings[0].shape(), Some(CagedShape::C));
+    ///
+    /// let options = VoicingOptions::new().with_open_strings(false).with_muted_strings(false);
+    /// let voicings = fretboard.voicings(&chord!("G").unwrap(), &options);
+    /// assert_eq!(voicings[0].to_string(), "355433");
+    /// assert_eq!(voicings[0].shape(), Some(CagedShape::E));
+    /// ```
+    pub fn voicings(&self, chord: &Chord, options: &VoicingOptions) -> Vec<Voicing> {
+        let mut notes = chord.notes();
+        if !chord.contains(chord.bass()) {
+            notes.push(chord.bass());
+        }
+        let classes = notes.iter().map(pitch_class).collect::<Vec<_>>();
+        let required = required_pitch_classes(chord);
+
+        let mut candidates = BTreeSet::new();
+        let highest_window = self.frets.saturating_sub(options.max_span - 1).max(1);
+        for window in 1..=highest_window {
+            let window = window..=(window + options.max_span - 1).min(self.frets);
+            let choices = (0..self.tuning.len())
+                .map(|string| {
+                    let mut choices = Vec::new();
+                    if options.muted_strings {
+                        choices.push(None);
+                    }
+                    let frets = options.open_strings.then_some(0).into_iter().chain(window.clone());
+                    for fret in frets {
+                        let position = Position::new(string, fret);
+                        if let Ok(midi) = self.midi_number_at(position) {
+                            if classes.contains(&((midi % 12) as i32)) {
+                                choices.push(Some(fret));
+                            }
+                        }
+                    }
+                    choices
+                })
+                .collect::<Vec<_>>();
+            let mut frets = Vec::new();
+            collect_frets(&choices, &mut frets, &mut candidates);
+        }
+
+        let mut voicings = candidates
+            .into_iter()
+            .filter_map(|frets| self.voicing(chord, &notes, &required, frets, options))
+            .collect::<Vec<_>>();
+        voicings.sort_by_key(|voicing| {
+            let lowest = voicing.frets.iter().flatten().filter(|fret| **fret > 0).min().copied();
+            let muted = voicing.frets.iter().filter(|fret| fret.is_none()).count();
+            (voicing.difficulty, muted, lowest.unwrap_or(0), voicing.frets.clone())
+        });
+        voicings
+    }
+
+    /// Checks a candidate voicing against the chord and the options, and works out how
+    /// to finger it.
+    fn voicing(
+        &self,
+        chord: &Chord,
+        notes: &[Note],
+        required: &[(i32, bool)],
+        frets: Vec<Option<u8>>,
+        options: &VoicingOptions,
+    ) -> Option<Voicing> {
+        let sounding = frets
+            .iter()
+            .enumerate()
+            .filter_map(|(string, fret)| Some((string, (*fret)?)))
+            .collect::<Vec<_>>();
+        if sounding.len() < 3.min(self.tuning.len()) {
+            return None;
+        }
+        let midis = sounding
+            .iter()
+            .map(|(string, fret)| self.midi_number_at(Position::new(*string, *fret)).ok())
+            .collect::<Option<Vec<_>>>()?;
+        let bass = midis.iter().min()?;
+        if (*bass % 12) as i32 != pitch_class(&chord.bass()) {
+            return None;
+        }
+        let present = |class: i32| midis.iter().any(|midi| (midi % 12) as i32 == class);
+        if required.iter().any(|(class, optional)| !optional && !present(*class)) {
+            return None;
+        }
+        let missing = required.iter().filter(|(class, _)| !present(*class)).count() as u32;
+
+        let fretted = sounding.iter().filter(|(_, fret)| *fret > 0).collect::<Vec<_>>();
+        let span =
+            match (fretted.iter().map(|(_, f)| *f).min(), fretted.iter().map(|(_, f)| *f).max()) {
+                (Some(low), Some(high)) => high - low + 1,
+                _ => 0,
+            };
+        if span > options.max_span {
+            return None;
+        }
+
+        let barre = match options.barres {
+            true => barre(&frets),
+            false => None,
+        };
+        let plain = (fretted.len() as u8, false);
+        let barred = barre.map(|fret| {
+            let above = fretted.iter().filter(|(_, f)| *f > fret).count() as u8;
+            (above + 1, true)
+        });
+        let (fingers, barred) = [Some(plain), barred]
+            .into_iter()
+            .flatten()
+            .filter(|(fingers, _)| *fingers <= options.max_fingers)
+            .min_by_key(|(fingers, barred)| *fingers as u32 + 2 * *barred as u32)?;
+
+        let first = sounding.first()?.0;
+        let last = sounding.last()?.0;
+        let muted = frets.iter().filter(|fret| fret.is_none()).count() as u32;
+        let inner_muted = frets[first..=last].iter().filter(|fret| fret.is_none()).count() as u32;
+        let lowest = fretted.iter().map(|(_, fret)| *fret).min().unwrap_or(0);
+        let difficulty = fingers as u32
+            + span.saturating_sub(1) as u32
+            + lowest as u32 / 5
+            + 2 * barred as u32
+            + muted
+            + 2 * inner_muted
+            + missing;
+
+        let pitches =
+            midis.iter().map(|midi| spelled_pitch(*midi, notes)).collect::<Option<_>>()?;
+        let shape = self.caged_shape(chord, &frets);
+        Some(Voicing {
+            frets,
+            pitches,
+            fingers,
+            barre: if barred { barre } else { None },
+            difficulty,
+            shape,
+        })
+    }
+
+    /// Finds the CAGED shape whose template, moved up to the chord's root, shares the most
+    /// strings with the voicing, with at least three shared and at most one string
+    /// differing.
+    fn caged_shape(&self, chord: &Chord, frets: &[Option<u8>]) -> Option<CagedShape> {
+        if self.tuning != Tuning::standard() {
+            return None;
+        }
+        let third = chord.intervals().iter().find_map(|i| match i.to_semitones() {
+            3 => Some(true),
+            4 => Some(false),
+            _ => None,
+        })?;
+        let root = pitch_class(&chord.root());
+
+        CAGED_TEMPLATES
+            .iter()
+            .flat_map(|(shape, template_root, major, minor)| {
+                let template = if third { minor } else { major };
+                let offset = (root - template_root).rem_euclid(12);
+                [offset, offset + 12].map(|offset| {
+                    let (mut shared, mut differing) = (0, 0);
+                    for (fret, template) in frets.iter().zip(template.iter()) {
+                        match (fret, template) {
+                            (Some(fret), Some(t)) if *fret as i32 == *t as i32 + offset => {
+                                shared += 1
+                            }
+                            (Some(_), _) => differing += 1,
+                            _ => (),
+                        }
+                    }
+                    (*shape, shared, differing)
+                })
+            })
+            .filter(|(_, shared, differing)| *shared >= 3 && *differing <= 1)
+            .max_by_key(|(shape, shared, differing)| (*shared, -(*differing), -(*shape as i32)))
+            .map(|(shape, _, _)| shape)
+    }
+}
+
+/// The open chord shapes in standard tuning, with the pitch class of their root and
+/// their major and minor forms.
+type Template = [Option<u8>; 6];
+static CAGED_TEMPLATES: [(CagedShape, i32, Template, Template); 5] = [
+    (
+        CagedShape::C,
+        0,
+        [None, Some(3), Some(2), Some(0), Some(1), Some(0)],
+        [None, Some(3), Some(1), Some(0), Some(1), Some(3)],
+    ),
+    (
+        CagedShape::A,
+        9,
+        [None, Some(0), Some(2), Some(2), Some(2), Some(0)],
+        [None, Some(0), Some(2), Some(2), Some(1), Some(0)],
+    ),
+    (
+        CagedShape::G,
+        7,
+        [Some(3), Some(2), Some(0), Some(0), Some(0), Some(3)],
+        [Some(3), Some(1), Some(0), Some(0), Some(3), Some(3)],
+    ),
+    (
+        CagedShape::E,
+        4,
+        [Some(0), Some(2), Some(2), Some(1), Some(0), Some(0)],
+        [Some(0), Some(2), Some(2), Some(0), Some(0), Some(0)],
+    ),
+    (
+        CagedShape::D,
+        2,
+        [None, None, Some(0), Some(2), Some(3), Some(2)],
+        [None, None, Some(0), Some(2), Some(3), Some(1)],
+    ),
+];
+
+fn pitch_class(note: &Note) -> i32 {
+    note.to_chromatic_scale_degree().rem_euclid(12)
+}
+
+/// Returns the pitch classes of the chord, each with whether it may be left out.
+fn required_pitch_classes(chord: &Chord) -> Vec<(i32, bool)> {
+    let intervals = chord.intervals();
+    let highest = intervals.iter().map(|i| i.to_semitones()).max().unwrap_or(0);
+    let mut classes = vec![(pitch_class(&chord.root()), false)];
+    for interval in &intervals {
+        let semitones = interval.to_semitones();
+        let optional = (semitones == 7 && intervals.len() >= 3)
+            || ([14, 17].contains(&semitones) && semitones < highest);
+        classes.push((pitch_class(&(chord.root() + *interval)), optional));
+    }
+    classes
+}
+
+/// Adds every combination of the choices for each string to the candidates.
+fn collect_frets(
+    choices: &[Vec<Option<u8>>],
+    frets: &mut Vec<Option<u8>>,
+    candidates: &mut BTreeSet<Vec<Option<u8>>>,
+) {
+    match choices.split_first() {
+        Some((first, rest)) => {
+            for fret in first {
+                frets.push(*fret);
+                collect_frets(rest, frets, candidates);
+                frets.pop();
+            }
+        }
+        None => {
+            candidates.insert(frets.clone());
+        }
+    }
+}
+
+/// Returns the lowest fretted fret, if a barre could hold it down on two or more strings
+/// without stopping an open string or sounding a muted one.
+fn barre(frets: &[Option<u8>]) -> Option<u8> {
+    let fret = frets.iter().flatten().filter(|fret| **fret > 0).min().copied()?;
+    let first = frets.iter().position(|f| *f == Some(fret))?;
+    let last = frets.iter().rposition(|f| *f == Some(fret))?;
+    let covered = frets[first..=last].iter().all(|f| matches!(f, Some(f) if *f >= fret));
+    (first < last && covered).then_some(fret)
+}
@@ -0,0 +1,438 @@
+use crate::{
+    chords::ChordFunction,
+    durations::{Duration, Event},
+    error::{GeneratorError, KeyError, ResonataError},
+    harmony::Progression,
+    keys::Key,
+    melodies::{Contour, Melody},
+    nope,
+    notes::*,
+    scales::Scale,
+};
+use rand::{
+    distributions::{Distribution, WeightedError, WeightedIndex},
+    rngs::StdRng,
+    Rng, SeedableRng,
+};
+
+pub use markov::{MarkovChain, Transitions};
+
+mod markov;
+mod tests;
+
+type Result<T> = std::result::Result<T, ResonataError>;
+
+/// Generates random melodies in a key, within a range of pitches.
+///
+/// Each note moves from the last by a number of steps of the scale, drawn from the
+/// weights of the generator, and in the direction given by its contour, or in either
+/// direction if it has none. Melodies start on the tonic nearest the middle of the
+/// range, and take their rhythm from weighted durations. The same seed always gives
+/// the same melody.
+///
+/// ### Examples
+/// ```
+/// use resonata::{notes::*, durations::*, generators::*, melodies::*, scales::*};
+///
+/// let (low, high) = (pnote!("C4").unwrap(), pnote!("C5").unwrap());
+/// let generator = MelodyGenerator::new(Scale::major(), note!("C").unwrap(), low, high)
+///     .unwrap()
+///     .with_steps(vec![0.0, 3.0, 1.0])
+///     .unwrap()
+///     .with_contour(vec![Contour::Up, Contour::Up, Contour::Down]);
+///
+/// let melody = generator.generate(8, 42).unwrap();
+/// assert_eq!(melody.len(), 8);
+/// assert_eq!(melody.contour()[..3], [Contour::Up, Contour::Up, Contour::Down]);
+/// assert!(melody.semitone_steps().iter().all(|step| (1..=4).contains(&step.abs())));
+/// assert_eq!(melody.duration(), dur!(2, 1).unwrap());
+/// assert_eq!(generator.generate(8, 42).unwrap(), melody);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct MelodyGenerator {
+    scale: Scale,
+    tonic: Note,
+    low: PitchedNote,
+    high: PitchedNote,
+    steps: Vec<f64>,
+    contour: Vec<Contour>,
+    rhythm: Vec<(Duration, f64)>,
+}
+
+/// Generates random chord progressions in a key from a table of transitions between
+/// chord functions.
+///
+/// Each chord follows the last with a weight given by the table, and the chords are
+/// built on their functions by [ChordFunction::to_chord]. The default table follows
+/// common practice functional harmony: tonic chords move anywhere, predominants to
+/// dominants, and dominants back to the tonic, with the odd deceptive move to vi and
+/// an applied dominant of V. The same seed always gives the same progression.
+///
+/// ### Examples
+/// ```
+/// use resonata::{notes::*, chords::*, generators::*, scales::*};
+///
+/// let generator = ProgressionGenerator::new(Scale::major(), note!("C").unwrap());
+/// let progression = generator.generate(8, 7).unwrap();
+/// assert_eq!(progression.chords().len(), 8);
+/// assert_eq!(progression.chords()[0], chord!("C").unwrap());
+/// assert_eq!(generator.generate(8, 7).unwrap(), progression);
+///
+/// let function = |s: &str| s.parse::<ChordFunction>().unwrap();
+/// let generator = ProgressionGenerator::new(Scale::major(), note!("F").unwrap())
+///     .with_transitions(vec![
+///         (function("I"), function("IV"), 1.0),
+///         (function("IV"), function("V"), 1.0),
+///         (function("V"), function("I"), 1.0),
+///     ])
+///     .with_sevenths(true);
+/// let chords = generator.generate(4, 0).unwrap().chords().clone();
+/// assert_eq!(chords, ["Fmaj7", "Bbmaj7", "C7", "Fmaj7"].map(|s| s.parse::<Chord>().unwrap()));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProgressionGenerator {
+    scale: Scale,
+    tonic: Note,
+    start: ChordFunction,
+    transitions: Vec<(ChordFunction, ChordFunction, f64)>,
+    sevenths: bool,
+}
+
+/// The default transitions of a progression generator, as functions and weights.
+const FUNCTIONAL_HARMONY: [(&str, &str, f64); 26] = [
+    ("I", "ii", 2.0),
+    ("I", "iii", 1.0),
+    ("I", "IV", 3.0),
+    ("I", "V", 3.0),
+    ("I", "vi", 2.0),
+    ("I", "vii", 1.0),
+    ("I", "V/V", 1.0),
+    ("ii", "V", 4.0),
+    ("ii", "vii", 1.0),
+    ("ii", "V/V", 1.0),
+    ("iii", "IV", 2.0),
+    ("iii", "vi", 3.0),
+    ("IV", "I", 2.0),
+    ("IV", "ii", 1.0),
+    ("IV", "V", 3.0),
+    ("IV", "vii", 1.0),
+    ("IV", "V/V", 1.0),
+    ("V", "I", 4.0),
+    ("V", "vi", 1.0),
+    ("vi", "ii", 3.0),
+    ("vi", "IV", 3.0),
+    ("vi", "V/V", 1.0),
+    ("vii", "I", 3.0),
+    ("vii", "vi", 1.0),
+    ("V/V", "V", 4.0),
+    ("V/V", "I", 1.0),
+];
+
+impl MelodyGenerator {
+    /// Creates a generator of melodies in the key of the scale built on the tonic,
+    /// between the lowest and highest pitches given. By default, notes move by step
+    /// or skip, with the odd repeated note or leap, in quarter notes.
+    ///
+    /// ### Failures
+    /// Returns an error if no note of the scale is within the range.
+    pub fn new(scale: Scale, tonic: Note, low: PitchedNote, high: PitchedNote) -> Result<Self> {
+        let generator = MelodyGenerator {
+            scale,
+            tonic,
+            low,
+            high,
+            steps: vec![1.0, 8.0, 4.0, 2.0, 1.0],
+            contour: Vec::new(),
+            rhythm: vec![(Duration::quarter(), 1.0)],
+        };
+        if generator.pitches().is_empty() {
+            nope!(GeneratorError::InvalidRange(low, high));
+        }
+        Ok(generator)
+    }
+
+    /// Creates a generator of melodies in a key, using the tonic and scale type of the
+    /// key, between the lowest and highest pitches given.
+    ///
+    /// ### Failures
+    /// Returns an error if the key is not a known scale type, or if no note of the key
+    /// is within the range.
+    pub fn in_key(key: &Key, low: PitchedNote, high: PitchedNote) -> Result<Self> {
+        match (key.root(), key.to_scale_type()) {
+            (Some(tonic), Some(scale_type)) => {
+                MelodyGenerator::new(scale_type.to_scale(), tonic, low, high)
+            }
+            _ => nope!(KeyError::InvalidKey),
+        }
+    }
+
+    /// Sets the weight of each melodic interval, in steps of the scale, from a repeated
+    /// note at index zero. A weight of zero rules an interval out.
+    ///
+    /// ### Failures
+    /// Returns an error if a weight is negative or not finite, or if no weight is above
+    /// zero.
+    pub fn with_steps(mut self, steps: Vec<f64>) -> Result<Self> {
+        if !are_valid_weights(steps.iter().copied()) {
+            nope!(GeneratorError::InvalidWeights);
+        }
+        self.steps = steps;
+        Ok(self)
+    }
+
+    /// Sets the contour of the melody, which is followed from the first note and
+    /// repeated if the melody is longer. An empty contour lets notes move either way.
+    pub fn with_contour(mut self, contour: Vec<Contour>) -> Self {
+        self.contour = contour;
+        self
+    }
+
+    /// Sets the durations the notes may take, with their weights.
+    ///
+    /// ### Failures
+    /// Returns an error if a duration is zero, if a weight is negative or not finite,
+    /// or if no weight is above zero.
+    pub fn with_rhythm(mut self, rhythm: Vec<(Duration, f64)>) -> Result<Self> {
+        if rhythm.iter().any(|(duration, _)| duration.is_zero())
+            || !are_valid_weights(rhythm.iter().map(|(_, weight)| *weight))
+        {
+            nope!(GeneratorError::InvalidWeights);
+        }
+        self.rhythm = rhythm;
+        Ok(self)
+    }
+
+    /// Returns the scale of the key.
+    pub fn scale(&self) -> &Scale {
+        &self.scale
+    }
+
+    /// Returns the tonic of the key.
+    pub fn tonic(&self) -> Note {
+        self.tonic
+    }
+
+    /// Returns the lowest and highest pitches a melody may reach.
+    pub fn range(&self) -> (PitchedNote, PitchedNote) {
+        (self.low, self.high)
+    }
+
+    /// Returns the weight of each melodic interval, in steps of the scale.
+    pub fn steps(&self) -> &Vec<f64> {
+        &self.steps
+    }
+
+    /// Returns the contour melodies follow.
+    pub fn contour(&self) -> &Vec<Contour> {
+        &self.contour
+    }
+
+    /// Returns the durations notes may take, with their weights.
+    pub fn rhythm(&self) -> &Vec<(Duration, f64)> {
+        &self.rhythm
+    }
+
+    /// Generates a melody of the given number of notes from a seed.
+    ///
+    /// ### Failures
+    /// Returns an error if the contour cannot be followed within the range by the
+    /// intervals allowed.
+    pub fn generate(&self, length: usize, seed: u64) -> Result<Melody> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let pitches = self.pitches();
+        let middle = (self.low.to_midi_number() as i32 + self.high.to_midi_number() as i32) / 2;
+        let distance = |index: &usize| (pitches[*index].to_midi_number() as i32 - middle).abs();
+        let tonics = (0..pitches.len()).filter(|i| Note::from(pitches[*i]) == self.tonic);
+        let mut index = match tonics.min_by_key(distance) {
+            Some(index) => index,
+            None => (0..pitches.len()).min_by_key(distance).unwrap(),
+        };
+
+        let durations = WeightedIndex::new(self.rhythm.iter().map(|(_, weight)| *weight))
+            .map_err(|_| GeneratorError::InvalidWeights)?;
+        let mut melody = Melody::default();
+        for note in 0..length {
+            if note > 0 {
+                let direction = match self.contour.is_empty() {
+                    true => None,
+                    false => Some(self.contour[(note - 1) % self.contour.len()]),
+                };
+                let mut moves = Vec::new();
+                for (size, weight) in self.steps.iter().enumerate() {
+                    let (up, down) = (index + size, index.checked_sub(size));
+                    let allowed = match (size, direction) {
+                        (0, None | Some(Contour::Repeat)) => vec![(index, *weight)],
+                        (0, _) | (_, Some(Contour::Repeat)) => vec![],
+                        (_, Some(Contour::Up)) => vec![(up, *weight)],
+                        (_, Some(Contour::Down)) => {
+                            down.map(|i| (i, *weight)).into_iter().collect()
+                        }
+                        (_, None) => {
+                            let down = down.map(|i| (i, *weight / 2.0));
+                            [(up, *weight / 2.0)].into_iter().chain(down).collect()
+                        }
+                    };
+                    moves.extend(
+                        allowed.into_iter().filter(|(i, w)| *i < pitches.len() && *w > 0.0),
+                    );
+                }
+                let Ok(choice) = WeightedIndex::new(moves.iter().map(|(_, weight)| *weight)) else {
+                    nope!(GeneratorError::ContourOutOfRange(note));
+                };
+                index = moves[choice.sample(&mut rng)].0;
+            }
+            let duration = self.rhythm[durations.sample(&mut rng)].0;
+            melody.push(Event::Note(pitches[index], duration));
+        }
+        Ok(melody)
+    }
+
+    /// Returns the notes of the scale within the range, from the lowest.
+    fn pitches(&self) -> Vec<PitchedNote> {
+        let (low, high) = (self.low.to_midi_number(), self.high.to_midi_number());
+        let mut pitches = Vec::new();
+        for octave in -1..=9 {
+            for note in self.scale.to_notes(self.tonic) {
+                if let Ok(pitch) = note.with_octave(octave) {
+                    if (low..=high).contains(&pitch.to_midi_number()) {
+                        pitches.push(pitch);
+                    }
+                }
+            }
+        }
+        pitches.sort_by_key(|pitch| pitch.to_midi_number());
+        pitches.dedup_by_key(|pitch| pitch.to_midi_number());
+        pitches
+    }
+}
+
+impl ProgressionGenerator {
+    /// Creates a generator of progressions in the key of the scale built on the tonic,
+    /// with the default table of transitions, starting on I, in triads.
+    pub fn new(scale: Scale, tonic: Note) -> Self {
+        let transitions = FUNCTIONAL_HARMONY
+            .iter()
+            .map(|(from, to, weight)| (from.parse().unwrap(), to.parse().unwrap(), *weight))
+            .collect();
+        ProgressionGenerator {
+            scale,
+            tonic,
+            start: "I".parse().unwrap(),
+            transitions,
+            sevenths: false,
+        }
+    }
+
+    /// Creates a generator of progressions in a key, using the tonic and scale type of
+    /// the key.
+    ///
+    /// ### Failures
+    /// Returns an error if the key is not a known scale type.
+    pub fn in_key(key: &Key) -> Result<Self> {
+        match (key.root(), key.to_scale_type()) {
+            (Some(tonic), Some(scale_type)) => {
+                Ok(ProgressionGenerator::new(scale_type.to_scale(), tonic))
+            }
+            _ => nope!(KeyError::InvalidKey),
+        }
+    }
+
+    /// Sets the table of transitions, each from one function to another with a weight.
+    pub fn with_transitions(
+        mut self,
+        transitions: Vec<(ChordFunction, ChordFunction, f64)>,
+    ) -> Self {
+        self.transitions = transitions;
+        self
+    }
+
+    /// Sets the weight of the transition from one function to another, adding it to
+    /// the table if it is not there. A weight of zero rules the transition out.
+    pub fn with_transition(mut self, from: ChordFunction, to: ChordFunction, weight: f64) -> Self {
+        match self.transitions.iter_mut().find(|(f, t, _)| *f == from && *t == to) {
+            Some(transition) => transition.2 = weight,
+            None => self.transitions.push((from, to, weight)),
+        }
+        self
+    }
+
+    /// Sets the function of the first chord.
+    pub fn with_start(mut self, start: ChordFunction) -> Self {
+        self.start = start;
+        self
+    }
+
+    /// Sets whether the chords are seventh chords rather than triads.
+    pub fn with_sevenths(mut self, sevenths: bool) -> Self {
+        self.sevenths = sevenths;
+        self
+    }
+
+    /// Returns the scale of the key.
+    pub fn scale(&self) -> &Scale {
+        &self.scale
+    }
+
+    /// Returns the tonic of the key.
+    pub fn tonic(&self) -> Note {
+        self.tonic
+    }
+
+    /// Returns the function of the first chord.
+    pub fn start(&self) -> ChordFunction {
+        self.start
+    }
+
+    /// Returns the table of transitions.
+    pub fn transitions(&self) -> &Vec<(ChordFunction, ChordFunction, f64)> {
+        &self.transitions
+    }
+
+    /// Returns true if the chords are seventh chords.
+    pub fn sevenths(&self) -> bool {
+        self.sevenths
+    }
+
+    /// Generates a progression of the given number of chords from a seed.
+    ///
+    /// ### Failures
+    /// Returns an error if a chord is reached that has no transitions with a weight
+    /// above zero, if a weight is negative or not finite, or if a function makes no
+    /// known chord in the key.
+    pub fn generate(&self, length: usize, seed: u64) -> Result<Progression> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut functions = Vec::new();
+        let mut function = self.start;
+        for index in 0..length {
+            if index > 0 {
+                function = self.next(function, &mut rng)?;
+            }
+            functions.push(function);
+        }
+        let chords = functions
+            .iter()
+            .map(|function| function.to_chord(&self.scale, self.tonic, self.sevenths))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Progression::new(chords, self.scale.clone(), self.tonic))
+    }
+
+    /// Draws the function to follow the given one from the table.
+    fn next(&self, function: ChordFunction, rng: &mut impl Rng) -> Result<ChordFunction> {
+        let choices = self.transitions.iter().filter(|(from, _, _)| *from == function);
+        let choices = choices.collect::<Vec<_>>();
+        let index = match WeightedIndex::new(choices.iter().map(|(_, _, weight)| *weight)) {
+            Ok(index) => index,
+            Err(WeightedError::NoItem | WeightedError::AllWeightsZero) => {
+                nope!(GeneratorError::NoTransition(function.to_string()))
+            }
+            Err(_) => nope!(GeneratorError::InvalidWeights),
+        };
+        Ok(choices[index.sample(rng)].1)
+    }
+}
+
+/// Returns true if the weights are finite and not negative, and one is above zero.
+fn are_valid_weights(mut weights: impl Iterator<Item = f64> + Clone) -> bool {
+    weights.clone().all(|weight| weight.is_finite() && weight >= 0.0)
+        && weights.any(|weight| weight > 0.0)
+}
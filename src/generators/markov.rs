@@ -0,0 +1,366 @@
+use super::*;
+use crate::{error::NoteError, scales::ScaleDegree};
+
+/// The transitions of a [MarkovChain]: each context, with the states that followed it
+/// and how often.
+pub type Transitions<T> = Vec<(Vec<T>, Vec<(T, u32)>)>;
+
+/// A Markov chain of a given order, trained on sequences of states, that generates new
+/// sequences in their style.
+///
+/// Each state follows the states before it with the probability it did in training.
+/// The chain counts what follows every context of up to its order, and generates from
+/// the longest context it has seen, so that it can carry on from a context that only
+/// ended a training sequence. Sequences start with an opening drawn from those that
+/// began training sequences.
+///
+/// States should not depend on transposition, so that material in every key trains the
+/// same model. Chains of semitone steps, of scale degrees and of chord functions have
+/// methods to train on melodies and progressions and to generate them.
+///
+/// With the `serde` feature, trained chains can be serialized.
+///
+/// ### Examples
+/// ```
+/// use resonata::generators::*;
+///
+/// let mut chain = MarkovChain::new(1).unwrap();
+/// chain.train(&["a", "b", "a", "c"]);
+/// assert_eq!(chain.probability(&["a"], &"b"), 0.5);
+/// assert_eq!(chain.probability(&["b"], &"a"), 1.0);
+///
+/// let sequence = chain.generate(6, 3).unwrap();
+/// assert_eq!(sequence.len(), 6);
+/// assert_eq!(sequence[0], "a");
+/// assert_eq!(chain.generate(6, 3).unwrap(), sequence);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarkovChain<T> {
+    order: usize,
+    openings: Vec<(Vec<T>, u32)>,
+    transitions: Transitions<T>,
+}
+
+impl<T: Clone + PartialEq> MarkovChain<T> {
+    /// Creates an untrained chain of the given order, the number of states each state
+    /// depends on.
+    ///
+    /// ### Failures
+    /// Returns an error if the order is zero.
+    pub fn new(order: usize) -> Result<Self> {
+        if order == 0 {
+            nope!(GeneratorError::InvalidOrder(order));
+        }
+        Ok(MarkovChain { order, openings: Vec::new(), transitions: Vec::new() })
+    }
+
+    /// Creates a chain from its order, openings and transitions, as returned by its
+    /// getters.
+    ///
+    /// ### Failures
+    /// Returns an error if the order is zero, if an opening or a context is empty or
+    /// longer than the order, or if the openings or the states following a context have
+    /// no counts.
+    pub fn from_counts(
+        order: usize,
+        openings: Vec<(Vec<T>, u32)>,
+        transitions: Transitions<T>,
+    ) -> Result<Self> {
+        let mut chain = MarkovChain::new(order)?;
+        let contexts = openings.iter().map(|(o, _)| o).chain(transitions.iter().map(|(c, _)| c));
+        if contexts.clone().any(|context| context.is_empty() || context.len() > order) {
+            nope!(GeneratorError::InvalidOrder(order));
+        }
+        if !openings.is_empty() && uncounted(&openings)
+            || transitions.iter().any(|(_, followers)| uncounted(followers))
+        {
+            nope!(GeneratorError::InvalidWeights);
+        }
+        chain.openings = openings;
+        chain.transitions = transitions;
+        Ok(chain)
+    }
+
+    /// Counts the openings and transitions of a sequence.
+    pub fn train(&mut self, sequence: &[T]) {
+        if sequence.is_empty() {
+            return;
+        }
+        let opening = &sequence[..self.order.min(sequence.len())];
+        match self.openings.iter_mut().find(|(o, _)| o == opening) {
+            Some((_, count)) => *count += 1,
+            None => self.openings.push((opening.to_vec(), 1)),
+        }
+
+        for end in 1..sequence.len() {
+            for length in 1..=self.order.min(end) {
+                let context = &sequence[end - length..end];
+                let next = &sequence[end];
+                let index = match self.transitions.iter().position(|(c, _)| c == context) {
+                    Some(index) => index,
+                    None => {
+                        self.transitions.push((context.to_vec(), Vec::new()));
+                        self.transitions.len() - 1
+                    }
+                };
+                let followers = &mut self.transitions[index].1;
+                match followers.iter_mut().find(|(state, _)| state == next) {
+                    Some((_, count)) => *count += 1,
+                    None => followers.push((next.clone(), 1)),
+                }
+            }
+        }
+    }
+
+    /// Returns the order of the chain.
+    pub fn order(&self) -> usize {
+        self.order
+    }
+
+    /// Returns the openings of the training sequences, each as long as the order or
+    /// the sequence, with how often they occurred.
+    pub fn openings(&self) -> &Vec<(Vec<T>, u32)> {
+        &self.openings
+    }
+
+    /// Returns every context of up to the order seen in training, with how often each
+    /// state followed it.
+    pub fn transitions(&self) -> &Transitions<T> {
+        &self.transitions
+    }
+
+    /// Returns true if the chain has not been trained.
+    pub fn is_empty(&self) -> bool {
+        self.openings.is_empty()
+    }
+
+    /// Returns the probability of a state following the last states of a context, as
+    /// many as the order, or zero if that context was never seen.
+    pub fn probability(&self, context: &[T], next: &T) -> f64 {
+        let context = &context[context.len().saturating_sub(self.order)..];
+        match self.followers(context) {
+            Some(followers) => {
+                let total: u32 = followers.iter().map(|(_, count)| count).sum();
+                let count = followers.iter().find(|(state, _)| state == next).map(|(_, c)| *c);
+                count.unwrap_or(0) as f64 / total as f64
+            }
+            None => 0.0,
+        }
+    }
+
+    /// Generates a sequence of the given length from a seed, starting with an opening.
+    /// The sequence is cut short if it reaches a state that never led anywhere.
+    ///
+    /// ### Failures
+    /// Returns an error if the chain has not been trained.
+    pub fn generate(&self, length: usize, seed: u64) -> Result<Vec<T>> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let Ok(index) = WeightedIndex::new(self.openings.iter().map(|(_, count)| *count)) else {
+            nope!(GeneratorError::Untrained);
+        };
+        let mut sequence = self.openings[index.sample(&mut rng)].0.clone();
+        sequence.truncate(length);
+        let generated = self.extend(&sequence, length - sequence.len(), &mut rng);
+        sequence.extend(generated);
+        Ok(sequence)
+    }
+
+    /// Generates the given number of states to follow a context, from a seed. The
+    /// states are cut short if they reach a state that never led anywhere.
+    ///
+    /// ### Failures
+    /// Returns an error if the chain has not been trained.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::generators::*;
+    ///
+    /// let mut chain = MarkovChain::new(2).unwrap();
+    /// chain.train(&[1, 2, 3]);
+    /// chain.train(&[4, 2, 5]);
+    /// assert_eq!(chain.generate_after(&[1, 2], 1, 0).unwrap(), vec![3]);
+    /// assert_eq!(chain.generate_after(&[4, 2], 5, 0).unwrap(), vec![5]);
+    /// assert!([vec![3], vec![5]].contains(&chain.generate_after(&[2], 1, 0).unwrap()));
+    /// ```
+    pub fn generate_after(&self, context: &[T], length: usize, seed: u64) -> Result<Vec<T>> {
+        if self.is_empty() {
+            nope!(GeneratorError::Untrained);
+        }
+        let mut rng = StdRng::seed_from_u64(seed);
+        Ok(self.extend(context, length, &mut rng))
+    }
+
+    /// Draws the given number of states to follow a context, backing off to shorter
+    /// contexts when a longer one was never seen.
+    fn extend(&self, context: &[T], length: usize, rng: &mut impl Rng) -> Vec<T> {
+        let mut sequence = context.to_vec();
+        for _ in 0..length {
+            let start = sequence.len().saturating_sub(self.order);
+            let followers =
+                (start..sequence.len()).find_map(|start| self.followers(&sequence[start..]));
+            let Some(followers) = followers else {
+                break;
+            };
+            let Ok(index) = WeightedIndex::new(followers.iter().map(|(_, count)| *count)) else {
+                break;
+            };
+            sequence.push(followers[index.sample(rng)].0.clone());
+        }
+        sequence.split_off(context.len())
+    }
+
+    /// Returns the states that followed a context, with their counts.
+    fn followers(&self, context: &[T]) -> Option<&Vec<(T, u32)>> {
+        self.transitions.iter().find(|(c, _)| c == context).map(|(_, followers)| followers)
+    }
+}
+
+/// Returns true if none of the states were counted.
+fn uncounted<S>(counts: &[(S, u32)]) -> bool {
+    counts.iter().all(|(_, count)| *count == 0)
+}
+
+impl MarkovChain<i32> {
+    /// Trains the chain on the semitone steps between the notes of a melody.
+    pub fn train_intervals(&mut self, melody: &Melody) {
+        self.train(&melody.semitone_steps());
+    }
+
+    /// Generates a melody of the given number of notes, all of the given duration, by
+    /// semitone steps from the starting pitch. Notes are spelled with sharps.
+    ///
+    /// ### Failures
+    /// Returns an error if the chain has not been trained, or if the melody leaves the
+    /// range of MIDI pitches.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::{notes::*, durations::*, generators::*, melodies::*};
+    ///
+    /// let mut chain = MarkovChain::new(2).unwrap();
+    /// chain.train_intervals(&melody!("C4:1/4 D4:1/4 E4:1/4 C4:1/4").unwrap());
+    /// chain.train_intervals(&melody!("G4:1/4 A4:1/4 B4:1/4 G4:1/4").unwrap());
+    ///
+    /// let melody = chain.generate_melody(pnote!("F4").unwrap(), 4, Duration::quarter(), 0).unwrap();
+    /// assert_eq!(melody, melody!("F4:1/4 G4:1/4 A4:1/4 F4:1/4").unwrap());
+    /// ```
+    pub fn generate_melody(
+        &self,
+        start: PitchedNote,
+        length: usize,
+        duration: Duration,
+        seed: u64,
+    ) -> Result<Melody> {
+        let steps = self.generate(length.saturating_sub(1), seed)?;
+        if length == 0 {
+            return Ok(Melody::default());
+        }
+        let mut pitch = start;
+        let mut melody = Melody::from_notes(&[start], duration);
+        for step in steps {
+            let number = u8::try_from(pitch.to_midi_number() as i32 + step)
+                .map_err(|_| NoteError::InvalidNote)?;
+            pitch = PitchedNote::from_midi_number(number)?;
+            melody.push(Event::Note(pitch, duration));
+        }
+        Ok(melody)
+    }
+}
+
+impl MarkovChain<ScaleDegree> {
+    /// Trains the chain on the degrees of the notes of a melody, in the key of the scale
+    /// built on the tonic. Notes with no degree in the scale are skipped.
+    pub fn train_degrees(&mut self, melody: &Melody, scale: &Scale, tonic: Note) {
+        let notes = melody.notes().into_iter();
+        let degrees = notes.filter_map(|note| scale.degree_of(Note::from(note), tonic));
+        self.train(&degrees.collect::<Vec<_>>());
+    }
+
+    /// Generates a melody of the given number of notes, all of the given duration, in
+    /// the key of the scale built on the tonic. The first note is the nearest to the
+    /// given pitch, and each note after it is the nearest to the note before.
+    ///
+    /// ### Failures
+    /// Returns an error if the chain has not been trained.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::{notes::*, durations::*, generators::*, melodies::*, scales::*};
+    ///
+    /// let mut chain = MarkovChain::new(1).unwrap();
+    /// let c = note!("C").unwrap();
+    /// chain.train_degrees(&melody!("E4:1/4 D4:1/4 C4:1/4").unwrap(), &Scale::major(), c);
+    ///
+    /// let (minor, a) = (Scale::minor(), note!("A").unwrap());
+    /// let melody = chain.generate_melody(&minor, a, pnote!("A4").unwrap(), 3, Duration::half(), 0);
+    /// assert_eq!(melody.unwrap(), melody!("C5:1/2 B4:1/2 A4:1/2").unwrap());
+    /// ```
+    pub fn generate_melody(
+        &self,
+        scale: &Scale,
+        tonic: Note,
+        near: PitchedNote,
+        length: usize,
+        duration: Duration,
+        seed: u64,
+    ) -> Result<Melody> {
+        let mut previous = near;
+        let mut melody = Melody::default();
+        for degree in self.generate(length, seed)? {
+            let note = scale.note_at(degree, tonic);
+            let pitch = (previous.octave() - 1..=previous.octave() + 1)
+                .filter_map(|octave| note.with_octave(octave).ok())
+                .min_by_key(|pitch| previous.semitones_between(pitch))
+                .unwrap_or(previous);
+            melody.push(Event::Note(pitch, duration));
+            previous = pitch;
+        }
+        Ok(melody)
+    }
+}
+
+impl MarkovChain<ChordFunction> {
+    /// Trains the chain on the functions of the chords of a progression. Chords with
+    /// no function in the key are skipped.
+    pub fn train_functions(&mut self, progression: &Progression) {
+        let functions = progression.functions().into_iter().flatten().collect::<Vec<_>>();
+        self.train(&functions);
+    }
+
+    /// Generates a progression of the given number of chords in the key of the scale
+    /// built on the tonic, building chords on their functions with
+    /// [ChordFunction::to_chord].
+    ///
+    /// ### Failures
+    /// Returns an error if the chain has not been trained, or if a function makes no
+    /// known chord in the key.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::{notes::*, chords::*, generators::*, harmony::*, scales::*};
+    ///
+    /// let chords = ["C", "Am", "Dm", "G", "C"].map(|s| s.parse::<Chord>().unwrap());
+    /// let progression = Progression::new(chords.to_vec(), Scale::major(), note!("C").unwrap());
+    /// let mut chain = MarkovChain::new(2).unwrap();
+    /// chain.train_functions(&progression);
+    ///
+    /// let generated = chain.generate_progression(&Scale::major(), note!("Eb").unwrap(), 5, false, 0);
+    /// let expected = ["Eb", "Cm", "Fm", "Bb", "Eb"].map(|s| s.parse::<Chord>().unwrap());
+    /// assert_eq!(generated.unwrap().chords(), &expected.to_vec());
+    /// ```
+    pub fn generate_progression(
+        &self,
+        scale: &Scale,
+        tonic: Note,
+        length: usize,
+        sevenths: bool,
+        seed: u64,
+    ) -> Result<Progression> {
+        let chords = self
+            .generate(length, seed)?
+            .iter()
+            .map(|function| function.to_chord(scale, tonic, sevenths))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Progression::new(chords, scale.clone(), tonic))
+    }
+}
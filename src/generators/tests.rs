@@ -0,0 +1,149 @@
+#[cfg(test)]
+mod tests {
+    use super::super::*;
+    use crate::{chords::Chord, key, note, pnote};
+
+    fn range(low: &str, high: &str) -> (PitchedNote, PitchedNote) {
+        (low.parse().unwrap(), high.parse().unwrap())
+    }
+
+    #[test]
+    fn test_generate_melody() {
+        let (low, high) = range("D4", "D5");
+        let generator = MelodyGenerator::in_key(&key!("Bb Eb").unwrap(), low, high).unwrap();
+        let key_notes = Scale::major().to_notes(note!("Bb").unwrap());
+        for seed in 0..20 {
+            let melody = generator.generate(16, seed).unwrap();
+            assert_eq!(melody.notes()[0], pnote!("Bb4").unwrap());
+            assert!(melody.notes().iter().all(|note| key_notes.contains(&Note::from(*note))));
+            let (lowest, highest) = melody.ambitus().unwrap();
+            assert!(lowest.to_midi_number() >= low.to_midi_number());
+            assert!(highest.to_midi_number() <= high.to_midi_number());
+        }
+        assert_ne!(generator.generate(16, 1).unwrap(), generator.generate(16, 2).unwrap());
+
+        // Stepwise descent to the bottom of the range, then no further
+        let generator = generator
+            .with_steps(vec![0.0, 1.0])
+            .unwrap()
+            .with_contour(vec![Contour::Down])
+            .with_rhythm(vec![(Duration::half(), 1.0), (Duration::eighth(), 0.0)])
+            .unwrap();
+        let melody = generator.generate(5, 0).unwrap();
+        assert_eq!(melody, "Bb4:1/2 A4:1/2 G4:1/2 F4:1/2 Eb4:1/2".parse().unwrap());
+        assert_eq!(generator.generate(8, 0), Err(GeneratorError::ContourOutOfRange(6).into()));
+
+        assert!(generator.clone().with_steps(vec![0.0, 0.0]).is_err());
+        assert!(generator.clone().with_steps(vec![1.0, -1.0]).is_err());
+        assert!(generator.with_rhythm(vec![(Duration::zero(), 1.0)]).is_err());
+        let (low, high) = range("C5", "C4");
+        assert_eq!(
+            MelodyGenerator::new(Scale::major(), note!("C").unwrap(), low, high),
+            Err(GeneratorError::InvalidRange(low, high).into())
+        );
+    }
+
+    #[test]
+    fn test_generate_progression() {
+        let generator = ProgressionGenerator::in_key(&key!(note!("G#").unwrap())).unwrap();
+        for seed in 0..20 {
+            let progression = generator.generate(12, seed).unwrap();
+            assert_eq!(progression.chords()[0], "Am".parse::<Chord>().unwrap());
+            // Every transition is in the table.
+            let functions = progression.functions();
+            for pair in functions.windows(2) {
+                let (from, to) = (pair[0].unwrap(), pair[1].unwrap());
+                let numeral = |function: ChordFunction| function.to_string().to_uppercase();
+                assert!(
+                    generator.transitions().iter().any(|(f, t, _)| {
+                        numeral(*f) == numeral(from) && numeral(*t) == numeral(to)
+                    }),
+                    "{} {}",
+                    from,
+                    to
+                );
+            }
+        }
+
+        let function = |s: &str| s.parse::<ChordFunction>().unwrap();
+        let generator = ProgressionGenerator::new(Scale::major(), note!("D").unwrap())
+            .with_start(function("ii"))
+            .with_transition(function("ii"), function("V"), 0.0)
+            .with_transition(function("ii"), function("vii"), 0.0)
+            .with_transition(function("ii"), function("V/V"), 0.0);
+        assert_eq!(generator.generate(1, 0).unwrap().chords(), &vec![chord("Em")]);
+        assert_eq!(
+            generator.generate(2, 0),
+            Err(GeneratorError::NoTransition("II".to_string()).into())
+        );
+
+        let generator = generator.with_transition(function("ii"), function("V/V"), 1.0);
+        let chords = generator.with_sevenths(true).generate(4, 0).unwrap().chords().clone();
+        assert_eq!(chords[..3], [chord("Em7"), chord("E7"), chord("A7")]);
+    }
+
+    #[test]
+    fn test_markov_chain() {
+        let mut chain = MarkovChain::new(2).unwrap();
+        assert!(chain.is_empty());
+        assert_eq!(chain.generate(4, 0), Err(GeneratorError::Untrained.into()));
+        chain.train(&[1, 2, 3, 1, 2, 4]);
+        assert_eq!(chain.openings(), &vec![(vec![1, 2], 1)]);
+        assert_eq!(chain.probability(&[1, 2], &3), 0.5);
+        assert_eq!(chain.probability(&[2], &4), 0.5);
+        assert_eq!(chain.probability(&[2, 3], &1), 1.0);
+        assert_eq!(chain.probability(&[5], &1), 0.0);
+        for seed in 0..10 {
+            let sequence = chain.generate(8, seed).unwrap();
+            assert_eq!(sequence[..2], [1, 2]);
+            assert!(sequence.windows(2).all(|pair| chain.probability(&pair[..1], &pair[1]) > 0.0));
+        }
+        // The chain stops early after a state that only ended training.
+        assert!(chain.generate_after(&[2, 4], 3, 0).unwrap().is_empty());
+
+        // Melodies in different keys train the same model.
+        let mut major = MarkovChain::new(1).unwrap();
+        major.train_intervals(&"C4:1/4 E4:1/4 G4:1/4".parse().unwrap());
+        let mut other = MarkovChain::new(1).unwrap();
+        other.train_intervals(&"Ab3:1/8 C4:1/8 Eb4:1/8".parse().unwrap());
+        assert_eq!(major, other);
+        let c = note!("C").unwrap();
+        let mut degrees = MarkovChain::new(1).unwrap();
+        degrees.train_degrees(&"E4:1/4 D4:1/4 C4:1/4".parse().unwrap(), &Scale::major(), c);
+        let mut other = MarkovChain::new(1).unwrap();
+        let ab = note!("Ab").unwrap();
+        other.train_degrees(&"C5:1/4 Bb4:1/4 Ab4:1/4".parse().unwrap(), &Scale::major(), ab);
+        assert_eq!(degrees, other);
+
+        let progression = Progression::new(
+            ["C", "F", "G", "C"].map(chord).to_vec(),
+            Scale::major(),
+            note!("C").unwrap(),
+        );
+        let mut functions = MarkovChain::new(1).unwrap();
+        functions.train_functions(&progression);
+        let generated = functions.generate_progression(&Scale::major(), ab, 4, true, 0).unwrap();
+        assert_eq!(generated.chords(), &["Abmaj7", "Dbmaj7", "Eb7", "Abmaj7"].map(chord).to_vec());
+
+        assert_eq!(MarkovChain::<i32>::new(0), Err(GeneratorError::InvalidOrder(0).into()));
+        assert!(MarkovChain::from_counts(1, vec![(vec![1, 2], 1)], vec![]).is_err());
+        let invalid = Err(GeneratorError::InvalidWeights.into());
+        assert_eq!(
+            MarkovChain::from_counts(1, vec![(vec![1], 1)], vec![(vec![1], vec![])]),
+            invalid
+        );
+        assert_eq!(
+            MarkovChain::from_counts(1, vec![(vec![1], 1)], vec![(vec![1], vec![(2, 0)])]),
+            invalid
+        );
+        assert_eq!(MarkovChain::<i32>::from_counts(1, vec![(vec![1], 0)], vec![]), invalid);
+        assert_eq!(
+            MarkovChain::from_counts(2, chain.openings().clone(), chain.transitions().clone()),
+            Ok(chain)
+        );
+    }
+
+    fn chord(symbol: &str) -> Chord {
+        symbol.parse().unwrap()
+    }
+}
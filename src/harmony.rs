@@ -0,0 +1,306 @@
+use crate::{
+    chords::{Chord, ChordFunction, ChordType},
+    error::{HarmonyError, KeyError, ResonataError},
+    keys::Key,
+    nope,
+    notes::*,
+    scales::{Scale, ScaleDegree},
+};
+
+pub use segmentation::{HarmonicRegion, HarmonicSegmenter};
+
+mod segmentation;
+mod tests;
+mod utils;
+
+type Result<T> = std::result::Result<T, ResonataError>;
+
+/// The harmonic function of a chord in a key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HarmonicFunction {
+    Tonic,
+    Predominant,
+    Dominant,
+}
+
+/// A cadence, the harmonic close of a phrase.
+///
+/// - `PerfectAuthentic` is V or V7 to I, both in root position, with the tonic in
+///   the soprano.
+/// - `ImperfectAuthentic` is any other V to I, or VII to I.
+/// - `Half` ends on V.
+/// - `Plagal` is IV to I.
+/// - `Deceptive` is V to VI.
+/// - `PhrygianHalf` is a half cadence in a minor key from iv in first inversion, whose
+///   bass falls by a semitone to the dominant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Cadence {
+    PerfectAuthentic,
+    ImperfectAuthentic,
+    Half,
+    Plagal,
+    Deceptive,
+    PhrygianHalf,
+}
+
+/// A progression of chords in a key, given by a scale and a tonic, with the soprano
+/// note of each chord if it is known.
+///
+/// ### Examples
+/// ```
+/// use resonata::{notes::*, chords::*, harmony::*, keys::*};
+///
+/// let chords = ["G", "Em", "A7/C#", "D", "G"].map(|s| s.parse::<Chord>().unwrap());
+/// let progression = Progression::in_key(chords.to_vec(), &key!("F#").unwrap()).unwrap();
+///
+/// let functions = progression.functions().iter().map(|f| f.unwrap().to_string()).collect::<Vec<_>>();
+/// assert_eq!(functions, ["I", "VI", "V/V", "V", "I"]);
+///
+/// use HarmonicFunction::*;
+/// let functions = progression.harmonic_functions().into_iter().flatten().collect::<Vec<_>>();
+/// assert_eq!(functions, [Tonic, Tonic, Dominant, Dominant, Tonic]);
+///
+/// assert_eq!(progression.cadences(), vec![(4, Cadence::PerfectAuthentic)]);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Progression {
+    chords: Vec<Chord>,
+    scale: Scale,
+    tonic: Note,
+    soprano: Option<Vec<Note>>,
+}
+
+impl Progression {
+    /// Creates a progression of chords in the key of the scale built on the tonic.
+    pub fn new(chords: Vec<Chord>, scale: Scale, tonic: Note) -> Self {
+        Progression { chords, scale, tonic, soprano: None }
+    }
+
+    /// Creates a progression of chords in a key, using the tonic and scale type of the
+    /// key.
+    ///
+    /// ### Failures
+    /// Returns an error if the key is not a known scale type.
+    pub fn in_key(chords: Vec<Chord>, key: &Key) -> Result<Self> {
+        match (key.root(), key.to_scale_type()) {
+            (Some(tonic), Some(scale_type)) => {
+                Ok(Progression::new(chords, scale_type.to_scale(), tonic))
+            }
+            _ => nope!(KeyError::InvalidKey),
+        }
+    }
+
+    /// Sets the soprano note of each chord, which tells perfect authentic cadences from
+    /// imperfect ones.
+    ///
+    /// ### Failures
+    /// Returns an error if there is not one note for each chord, or if a note is not in
+    /// its chord.
+    pub fn with_soprano(mut self, soprano: Vec<Note>) -> Result<Self> {
+        if soprano.len() != self.chords.len() {
+            nope!(HarmonyError::SopranoLengthMismatch(self.chords.len(), soprano.len()));
+        }
+        if let Some(note) = soprano.iter().zip(&self.chords).find(|(n, c)| !c.contains(**n)) {
+            nope!(HarmonyError::SopranoNotInChord(*note.0));
+        }
+        self.soprano = Some(soprano);
+        Ok(self)
+    }
+
+    /// Returns the chords of the progression.
+    pub fn chords(&self) -> &Vec<Chord> {
+        &self.chords
+    }
+
+    /// Returns the scale of the key.
+    pub fn scale(&self) -> &Scale {
+        &self.scale
+    }
+
+    /// Returns the tonic of the key.
+    pub fn tonic(&self) -> Note {
+        self.tonic
+    }
+
+    /// Returns the soprano note of each chord, if they have been set.
+    pub fn soprano(&self) -> Option<&Vec<Note>> {
+        self.soprano.as_ref()
+    }
+
+    /// Returns the function of each chord as a Roman numeral, as found by
+    /// [Chord::function_in], or `None` for chords whose root is not on a degree of the
+    /// key.
+    pub fn functions(&self) -> Vec<Option<ChordFunction>> {
+        self.chords.iter().map(|chord| chord.function_in(&self.scale, self.tonic)).collect()
+    }
+
+    /// Returns the harmonic function of each chord, as given by
+    /// [ChordFunction::harmonic_function], or `None` for chords without a function.
+    ///
+    /// A tonic chord in second inversion followed by V is a cadential six-four, which
+    /// is labelled as a dominant.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::{notes::*, chords::*, harmony::*, scales::*};
+    ///
+    /// let chords = ["F", "C/G", "G7", "C"].map(|s| s.parse::<Chord>().unwrap());
+    /// let progression = Progression::new(chords.to_vec(), Scale::major(), note!("C").unwrap());
+    ///
+    /// use HarmonicFunction::*;
+    /// let functions = progression.harmonic_functions().into_iter().flatten().collect::<Vec<_>>();
+    /// assert_eq!(functions, [Predominant, Dominant, Dominant, Tonic]);
+    /// ```
+    pub fn harmonic_functions(&self) -> Vec<Option<HarmonicFunction>> {
+        let functions = self.functions();
+        let dominant = pitch_class(&self.scale.note_at(degree(5), self.tonic));
+        (0..self.chords.len())
+            .map(|i| {
+                let function = functions[i]?;
+                let cadential = self.is_on(&functions[i], 1)
+                    && pitch_class(&self.chords[i].bass()) == dominant
+                    && i + 1 < self.chords.len()
+                    && self.is_dominant(i + 1, &functions[i + 1]);
+                match cadential {
+                    true => Some(HarmonicFunction::Dominant),
+                    false => Some(function.harmonic_function()),
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the cadences in the progression, each with the index of the chord it
+    /// arrives on.
+    ///
+    /// Authentic, plagal and deceptive cadences are found wherever their chords follow
+    /// one another. Half cadences are only found on the last chord, since a dominant
+    /// elsewhere is taken to carry the phrase on. Without a soprano, the top voice of a
+    /// root position V to I is taken to be the tonic.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::{notes::*, chords::*, harmony::*, scales::*};
+    ///
+    /// let progression = |symbols: &[&str], scale: Scale, tonic: &str| {
+    ///     let chords = symbols.iter().map(|s| s.parse::<Chord>().unwrap()).collect();
+    ///     Progression::new(chords, scale, tonic.parse().unwrap())
+    /// };
+    ///
+    /// let cadences = progression(&["C", "G7", "Am", "F", "C"], Scale::major(), "C").cadences();
+    /// assert_eq!(cadences, vec![(2, Cadence::Deceptive), (4, Cadence::Plagal)]);
+    ///
+    /// let cadences = progression(&["Am", "Dm/F", "E"], Scale::minor(), "A").cadences();
+    /// assert_eq!(cadences, vec![(2, Cadence::PhrygianHalf)]);
+    ///
+    /// let soprano = ["E", "D", "E"].map(|n| n.parse::<Note>().unwrap()).to_vec();
+    /// let progression = progression(&["C", "G", "C"], Scale::major(), "C");
+    /// let progression = progression.with_soprano(soprano).unwrap();
+    /// assert_eq!(progression.cadences(), vec![(2, Cadence::ImperfectAuthentic)]);
+    /// ```
+    pub fn cadences(&self) -> Vec<(usize, Cadence)> {
+        let functions = self.functions();
+        let tonic = pitch_class(&self.tonic);
+        let mut cadences = Vec::new();
+        for i in 1..self.chords.len() {
+            let (from, to) = (&self.chords[i - 1], &self.chords[i]);
+            let dominant = self.is_dominant(i - 1, &functions[i - 1]);
+            let leading_tone = pitch_class(&from.root()) == (tonic + 11) % 12
+                && matches!(
+                    from.chord_type(),
+                    ChordType::Diminished | ChordType::Diminished7 | ChordType::HalfDiminished7
+                );
+            let cadence = if (dominant || leading_tone) && self.is_on(&functions[i], 1) {
+                let soprano = match &self.soprano {
+                    Some(soprano) => pitch_class(&soprano[i]) == tonic,
+                    None => true,
+                };
+                match dominant && from.bass() == from.root() && to.bass() == to.root() && soprano {
+                    true => Some(Cadence::PerfectAuthentic),
+                    false => Some(Cadence::ImperfectAuthentic),
+                }
+            } else if self.is_on(&functions[i - 1], 4) && self.is_on(&functions[i], 1) {
+                Some(Cadence::Plagal)
+            } else if dominant && functions[i].is_some_and(|f| is_primary(&f, 6)) {
+                Some(Cadence::Deceptive)
+            } else if i == self.chords.len() - 1 && self.is_dominant(i, &functions[i]) {
+                let phrygian = self.is_minor()
+                    && self.is_on(&functions[i - 1], 4)
+                    && from.chord_type() == ChordType::Minor
+                    && (pitch_class(&from.bass()) - pitch_class(&to.bass())).rem_euclid(12) == 1;
+                match phrygian {
+                    true => Some(Cadence::PhrygianHalf),
+                    false => Some(Cadence::Half),
+                }
+            } else {
+                None
+            };
+            if let Some(cadence) = cadence {
+                cadences.push((i, cadence));
+            }
+        }
+        cadences
+    }
+
+    /// Returns true if the function is on the unaltered degree of the key with the given
+    /// number.
+    fn is_on(&self, function: &Option<ChordFunction>, number: u8) -> bool {
+        function.is_some_and(|f| is_primary(&f, number) && f.degree().is_diatonic())
+    }
+
+    /// Returns true if the chord at the index is V with the leading tone, as a triad or
+    /// a seventh chord.
+    fn is_dominant(&self, index: usize, function: &Option<ChordFunction>) -> bool {
+        let leading_tone = (pitch_class(&self.tonic) + 11) % 12;
+        let chord = &self.chords[index];
+        self.is_on(function, 5)
+            && chord.notes().iter().any(|note| pitch_class(note) == leading_tone)
+            && matches!(chord.chord_type(), ChordType::Major | ChordType::Dominant7)
+    }
+
+    /// Returns true if the key has a minor third.
+    fn is_minor(&self) -> bool {
+        let third = self.scale.note_at(degree(3), self.tonic);
+        (pitch_class(&third) - pitch_class(&self.tonic)).rem_euclid(12) == 3
+    }
+}
+
+impl ChordFunction {
+    /// Returns the harmonic function of a chord with this function. Chords on the first,
+    /// third and sixth degrees are tonic, those on the second and fourth predominant,
+    /// and those on the fifth and seventh dominant, whatever their alteration. Secondary
+    /// chords are dominants of their target.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::{chords::*, harmony::*};
+    ///
+    /// let function = |s: &str| s.parse::<ChordFunction>().unwrap().harmonic_function();
+    /// assert_eq!(function("vi"), HarmonicFunction::Tonic);
+    /// assert_eq!(function("bII"), HarmonicFunction::Predominant);
+    /// assert_eq!(function("V/ii"), HarmonicFunction::Dominant);
+    /// ```
+    pub fn harmonic_function(&self) -> HarmonicFunction {
+        if self.is_secondary() {
+            return HarmonicFunction::Dominant;
+        }
+        match self.degree().number() % 7 {
+            1 | 3 | 6 => HarmonicFunction::Tonic,
+            2 | 4 => HarmonicFunction::Predominant,
+            _ => HarmonicFunction::Dominant,
+        }
+    }
+}
+
+/// Returns true if the function is not secondary, and is on the degree with the given
+/// number, altered or not.
+fn is_primary(function: &ChordFunction, number: u8) -> bool {
+    !function.is_secondary() && function.degree().number() == number
+}
+
+fn degree(number: u8) -> ScaleDegree {
+    ScaleDegree::new(number, Accidental::Natural).unwrap()
+}
+
+fn pitch_class(note: &Note) -> i32 {
+    note.to_chromatic_scale_degree().rem_euclid(12)
+}
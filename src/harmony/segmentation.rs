@@ -0,0 +1,352 @@
+use super::*;
+use crate::{
+    durations::{Duration, Event},
+    melodies::Melody,
+    time_signatures::{BeatStrength, TimeSignature},
+};
+
+/// The chord types matched against each region, simplest first, so that ties go to
+/// the simpler chord.
+const TEMPLATES: [ChordType; 9] = [
+    ChordType::Major,
+    ChordType::Minor,
+    ChordType::Dominant7,
+    ChordType::Minor7,
+    ChordType::Major7,
+    ChordType::Diminished,
+    ChordType::HalfDiminished7,
+    ChordType::Diminished7,
+    ChordType::Augmented,
+];
+
+/// A stretch of a stream of notes, with the chord that best explains it, or `None` if
+/// nothing sounds in it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HarmonicRegion {
+    start: Duration,
+    duration: Duration,
+    chord: Option<Chord>,
+    score: f64,
+}
+
+/// Segments a stream of voices into harmonic regions and labels each with a chord, by
+/// matching chord templates against the notes that sound in it.
+///
+/// The stream is cut wherever a note starts or ends and at every barline, and the cuts
+/// are then joined into regions to give the best total score. A chord scores the
+/// weight of its notes that sound in the region, less the weight of the notes that are
+/// not in it, and less one for each of its notes that is missing, plus a bonus for its
+/// root being the lowest note. A note weighs the share of the region it sounds for,
+/// increased by the metric weight of where it starts, so that notes on strong beats
+/// count for more than passing notes. Regions that start off the beat cost a little,
+/// by the same metric weight, and no region is longer than the window.
+///
+/// ### Examples
+/// ```
+/// use resonata::{chords::*, durations::*, harmony::*, melodies::*, time_signatures::*};
+///
+/// let soprano = melody!("E5:1/4 D5:1/8 C5:1/8 D5:1/2 C5:1/1").unwrap();
+/// let alto = melody!("G4:1/2 G4:1/2 G4:1/1").unwrap();
+/// let bass = melody!("C3:1/2 G2:1/2 C3:1/1").unwrap();
+///
+/// let segmenter = HarmonicSegmenter::new(time!(4, 4).unwrap());
+/// let regions = segmenter.segment(&[soprano, alto, bass]);
+/// let chords = regions.iter().map(|r| r.chord().unwrap().to_string()).collect::<Vec<_>>();
+/// assert_eq!(chords, ["C", "G", "C"]);
+/// assert_eq!(regions[1].start(), Duration::half());
+/// assert_eq!(regions[2].duration(), Duration::whole());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct HarmonicSegmenter {
+    time: TimeSignature,
+    window: Duration,
+    bass_weight: f64,
+    metric_weight: f64,
+}
+
+/// The shortest stretch of the stream, in which the same notes sound throughout.
+struct Slice {
+    start: Duration,
+    length: f64,
+    weights: [f64; 12],
+    bass: Option<PitchedNote>,
+    notes: Vec<Note>,
+}
+
+impl HarmonicRegion {
+    /// Returns the start of the region from the start of the stream.
+    pub fn start(&self) -> Duration {
+        self.start
+    }
+
+    /// Returns the duration of the region.
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// Returns the chord of the region, or `None` if it is silent.
+    pub fn chord(&self) -> Option<&Chord> {
+        self.chord.as_ref()
+    }
+
+    /// Returns the score of the chord in the region, as described in
+    /// [HarmonicSegmenter], averaged over the region. It grows with the number of
+    /// voices sounding chord tones, and falls with each missing or foreign note.
+    pub fn score(&self) -> f64 {
+        self.score
+    }
+}
+
+impl HarmonicSegmenter {
+    /// Creates a segmenter for streams in the given meter, with a window of one
+    /// measure and bass and metric weights of one half.
+    pub fn new(time: TimeSignature) -> Self {
+        let window = time.measure_duration();
+        HarmonicSegmenter { time, window, bass_weight: 0.5, metric_weight: 0.5 }
+    }
+
+    /// Sets the longest a region may be. Regions with the same chord either side of a
+    /// barline are joined after segmenting, so the window bounds the search rather than
+    /// the result.
+    pub fn with_window(mut self, window: Duration) -> Self {
+        self.window = window;
+        self
+    }
+
+    /// Sets the bonus for a chord whose root is the lowest note of its region.
+    pub fn with_bass_weight(mut self, weight: f64) -> Self {
+        self.bass_weight = weight;
+        self
+    }
+
+    /// Sets how much more a note starting on a downbeat weighs than one starting off
+    /// the beat, and so how much a region starting off the beat costs.
+    pub fn with_metric_weight(mut self, weight: f64) -> Self {
+        self.metric_weight = weight;
+        self
+    }
+
+    /// Returns the time signature of the stream.
+    pub fn time(&self) -> &TimeSignature {
+        &self.time
+    }
+
+    /// Returns the longest a region may be.
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    /// Returns the bonus for a chord with its root in the bass.
+    pub fn bass_weight(&self) -> f64 {
+        self.bass_weight
+    }
+
+    /// Returns the metric weight.
+    pub fn metric_weight(&self) -> f64 {
+        self.metric_weight
+    }
+
+    /// Segments a stream of voices, all starting together, into harmonic regions, in
+    /// order. Chords are spelled from the notes of their region, and written over the
+    /// lowest note of the region if it is a chord tone other than the root.
+    pub fn segment(&self, voices: &[Melody]) -> Vec<HarmonicRegion> {
+        let slices = self.slices(voices);
+        let window = self.window.to_whole_notes();
+
+        // The best score of the stream up to each cut, and where its last region starts.
+        let mut best = vec![(0.0, 0); slices.len() + 1];
+        for end in 1..=slices.len() {
+            best[end] = (f64::NEG_INFINITY, end - 1);
+            let mut length = 0.0;
+            for start in (0..end).rev() {
+                length += slices[start].length;
+                if start + 1 < end && length > window + 1e-9 {
+                    break;
+                }
+                // Silence is a region of its own.
+                let silent = slices[start..end].iter().filter(|s| s.notes.is_empty()).count();
+                if silent > 0 && silent < end - start {
+                    continue;
+                }
+                let score = match self.label(&slices[start..end]) {
+                    Some((_, score)) => score * length,
+                    None => 0.0,
+                };
+                let score = best[start].0 + score - self.boundary_cost(slices[start].start);
+                if score >= best[end].0 {
+                    best[end] = (score, start);
+                }
+            }
+        }
+
+        let mut bounds = Vec::new();
+        let mut end = slices.len();
+        while end > 0 {
+            bounds.push((best[end].1, end));
+            end = best[end].1;
+        }
+        bounds.reverse();
+
+        let mut regions: Vec<HarmonicRegion> = Vec::new();
+        for (start, end) in bounds {
+            let (chord, score) = match self.label(&slices[start..end]) {
+                Some((chord, score)) => (Some(chord), score),
+                None => (None, 0.0),
+            };
+            let duration = match slices.get(end) {
+                Some(next) => next.start - slices[start].start,
+                None => self.end(voices) - slices[start].start,
+            };
+            match regions.last_mut() {
+                Some(last) if last.chord == chord => {
+                    let (a, b) = (last.duration.to_whole_notes(), duration.to_whole_notes());
+                    last.score = (last.score * a + score * b) / (a + b);
+                    last.duration += duration;
+                }
+                _ => regions.push(HarmonicRegion {
+                    start: slices[start].start,
+                    duration,
+                    chord,
+                    score,
+                }),
+            }
+        }
+        regions
+    }
+
+    /// Returns the chord that best explains the slices and its score, or `None` if they
+    /// are silent.
+    fn label(&self, slices: &[Slice]) -> Option<(Chord, f64)> {
+        let length: f64 = slices.iter().map(|slice| slice.length).sum();
+        let mut weights = [0.0; 12];
+        for slice in slices {
+            for (total, weight) in weights.iter_mut().zip(slice.weights) {
+                *total += weight / length;
+            }
+        }
+        if weights.iter().all(|weight| *weight == 0.0) {
+            return None;
+        }
+        let bass = slices.iter().filter_map(|slice| slice.bass).min_by_key(|b| b.to_midi_number());
+        let bass = bass.map(|bass| pitch_class(&Note::from(bass)) as usize);
+
+        let mut best: Option<(usize, ChordType, f64)> = None;
+        for root in 0..12 {
+            for chord_type in TEMPLATES {
+                let mut set = 1u16 << root;
+                for interval in chord_type.intervals() {
+                    set |= 1 << ((root as i32 + interval.to_semitones()) % 12);
+                }
+                let mut score = match bass == Some(root) {
+                    true => self.bass_weight,
+                    false => 0.0,
+                };
+                for (class, weight) in weights.iter().enumerate() {
+                    score += match (set & 1 << class != 0, *weight > 0.0) {
+                        (true, true) => *weight,
+                        (true, false) => -1.0,
+                        (false, _) => -*weight,
+                    };
+                }
+                if best.is_none_or(|(_, _, best)| score > best + 1e-9) {
+                    best = Some((root, chord_type, score));
+                }
+            }
+        }
+
+        let (root, chord_type, score) = best?;
+        let notes = slices.iter().flat_map(|slice| slice.notes.iter());
+        let spelled = |class: usize| {
+            notes
+                .clone()
+                .find(|note| pitch_class(note) == class as i32)
+                .copied()
+                .unwrap_or(Note::from_chromatic_scale_degree(class as u8))
+        };
+        let chord = Chord::new(spelled(root), chord_type);
+        let inversion = chord
+            .notes()
+            .into_iter()
+            .skip(1)
+            .find(|note| bass.is_some_and(|bass| pitch_class(note) == bass as i32));
+        let chord = match inversion {
+            Some(bass) => chord.with_bass(bass),
+            None => chord,
+        };
+        Some((chord, score))
+    }
+
+    /// Cuts the voices wherever a note starts or ends and at every barline, and weighs
+    /// the notes sounding in each slice.
+    fn slices(&self, voices: &[Melody]) -> Vec<Slice> {
+        let mut notes = Vec::new();
+        for voice in voices {
+            let mut position = Duration::zero();
+            for event in voice.events() {
+                if let Event::Note(pitch, duration) = event {
+                    notes.push((position, position + *duration, *pitch));
+                }
+                position += event.duration();
+            }
+        }
+
+        let end = self.end(voices);
+        let measure = self.time.measure_duration();
+        let mut cuts = vec![Duration::zero(), end];
+        let mut barline = measure;
+        while barline < end {
+            cuts.push(barline);
+            barline += measure;
+        }
+        for (start, end, _) in &notes {
+            cuts.extend([*start, *end]);
+        }
+        cuts.sort();
+        cuts.dedup();
+
+        cuts.windows(2)
+            .filter(|cut| cut[0] < cut[1])
+            .map(|cut| {
+                let length = (cut[1] - cut[0]).to_whole_notes();
+                let sounding =
+                    notes.iter().filter(|(start, end, _)| *start <= cut[0] && *end >= cut[1]);
+                let mut weights = [0.0; 12];
+                for (start, _, pitch) in sounding.clone() {
+                    let accent = self.metric_weight * level(self.time.strength_at(*start));
+                    weights[pitch_class(&Note::from(*pitch)) as usize] += length * (1.0 + accent);
+                }
+                let bass = sounding.clone().map(|(_, _, pitch)| *pitch);
+                Slice {
+                    start: cut[0],
+                    length,
+                    weights,
+                    bass: bass.min_by_key(|pitch| pitch.to_midi_number()),
+                    notes: sounding.map(|(_, _, pitch)| Note::from(*pitch)).collect(),
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the cost of starting a region at the position, which is nothing on a
+    /// downbeat and a sixteenth note, times the metric weight, off the beat.
+    fn boundary_cost(&self, position: Duration) -> f64 {
+        self.metric_weight * (1.0 - level(self.time.strength_at(position))) / 16.0
+    }
+
+    /// Returns the end of the longest voice.
+    fn end(&self, voices: &[Melody]) -> Duration {
+        voices.iter().map(|voice| voice.duration()).max().unwrap_or(Duration::zero())
+    }
+}
+
+/// Returns the metric level of a beat strength, from zero off the beat to one on the
+/// downbeat.
+fn level(strength: BeatStrength) -> f64 {
+    match strength {
+        BeatStrength::Downbeat => 1.0,
+        BeatStrength::Strong => 0.75,
+        BeatStrength::Weak => 0.5,
+        BeatStrength::Division => 0.25,
+        BeatStrength::Offbeat => 0.0,
+    }
+}
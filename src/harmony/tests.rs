@@ -0,0 +1,160 @@
+#[cfg(test)]
+mod tests {
+    use super::super::*;
+    use crate::{
+        dur, durations::Duration, key, melodies::Melody, note, time, time_signatures::TimeSignature,
+    };
+
+    fn progression(symbols: &[&str], scale: Scale, tonic: &str) -> Progression {
+        let chords = symbols.iter().map(|symbol| symbol.parse().unwrap()).collect();
+        Progression::new(chords, scale, tonic.parse().unwrap())
+    }
+
+    #[test]
+    fn test_functions() {
+        let chords = ["C", "E7/G#", "Am", "Bb", "F#ø7", "Fm", "Db", "C/G", "G7", "Eb", "C"];
+        let major = progression(&chords, Scale::major(), "C");
+        let functions = major
+            .functions()
+            .iter()
+            .map(|function| function.map(|f| f.to_string()))
+            .collect::<Vec<_>>();
+        let expected = ["I", "V/VI", "VI", "♭VII", "VII/V", "IV", "♭II", "I", "V", "♭III", "I"];
+        assert_eq!(functions, expected.map(|f| Some(f.to_string())));
+
+        use HarmonicFunction::*;
+        assert_eq!(
+            major.harmonic_functions(),
+            vec![
+                Some(Tonic),
+                Some(Dominant),
+                Some(Tonic),
+                Some(Dominant),
+                Some(Dominant),
+                Some(Predominant),
+                Some(Predominant),
+                Some(Dominant),
+                Some(Dominant),
+                Some(Tonic),
+                Some(Tonic),
+            ]
+        );
+
+        // In minor, the dominant and leading tone chords borrow the raised seventh,
+        // and the subtonic chord is the dominant of the relative major.
+        let minor = progression(&["Am", "G", "C", "G#o7", "E7", "Am"], Scale::minor(), "A");
+        let functions =
+            minor.functions().iter().map(|f| f.unwrap().to_string()).collect::<Vec<_>>();
+        assert_eq!(functions, ["I", "VII", "III", "♯VII", "V", "I"]);
+
+        let key = key!(note!("G#").unwrap());
+        let dominant = Progression::in_key(vec!["E".parse().unwrap()], &key).unwrap();
+        assert_eq!(dominant.tonic(), note!("A").unwrap());
+        assert_eq!(dominant.harmonic_functions(), vec![Some(Dominant)]);
+        assert!(Progression::in_key(vec![], &key!(note!("Fb").unwrap())).is_err());
+
+        let pentatonic = progression(&["C", "F"], Scale::major_pentatonic(), "C");
+        assert_eq!(pentatonic.harmonic_functions(), vec![Some(Tonic), None]);
+    }
+
+    #[test]
+    fn test_cadences() {
+        use Cadence::*;
+        let cases = [
+            (vec!["C", "F", "G7", "C"], Scale::major(), "C", vec![(3, PerfectAuthentic)]),
+            (vec!["C", "G/B", "C"], Scale::major(), "C", vec![(2, ImperfectAuthentic)]),
+            (vec!["C", "Bo", "C/E"], Scale::major(), "C", vec![(2, ImperfectAuthentic)]),
+            (vec!["C", "Dm", "G"], Scale::major(), "C", vec![(2, Half)]),
+            (vec!["C", "G", "F", "C"], Scale::major(), "C", vec![(3, Plagal)]),
+            (vec!["C", "G7", "Ab"], Scale::major(), "C", vec![(2, Deceptive)]),
+            (vec!["Dm", "Gm/Bb", "A"], Scale::minor(), "D", vec![(2, PhrygianHalf)]),
+            (vec!["Dm", "Gm", "A"], Scale::minor(), "D", vec![(2, Half)]),
+            (vec!["Dm", "Am", "Dm"], Scale::minor(), "D", vec![]),
+            (vec!["Am", "G#o7", "Am"], Scale::minor(), "A", vec![(2, ImperfectAuthentic)]),
+            (vec!["Am", "G#ø7", "Am"], Scale::harmonic_minor(), "A", vec![(2, ImperfectAuthentic)]),
+            (vec!["C", "G", "Am", "D7", "G"], Scale::major(), "C", vec![(2, Deceptive), (4, Half)]),
+        ];
+        for (chords, scale, tonic, expected) in cases {
+            assert_eq!(progression(&chords, scale, tonic).cadences(), expected, "{:?}", chords);
+        }
+
+        let soprano = |notes: &[&str]| notes.iter().map(|n| n.parse().unwrap()).collect();
+        let chords = progression(&["F", "G7", "C"], Scale::major(), "C");
+        let with_tonic = chords.clone().with_soprano(soprano(&["C", "B", "C"])).unwrap();
+        assert_eq!(with_tonic.cadences(), vec![(2, PerfectAuthentic)]);
+        let with_third = chords.clone().with_soprano(soprano(&["A", "D", "E"])).unwrap();
+        assert_eq!(with_third.cadences(), vec![(2, ImperfectAuthentic)]);
+
+        assert_eq!(
+            chords.clone().with_soprano(soprano(&["C", "B"])),
+            Err(HarmonyError::SopranoLengthMismatch(3, 2).into())
+        );
+        assert_eq!(
+            chords.with_soprano(soprano(&["C", "C", "C"])),
+            Err(HarmonyError::SopranoNotInChord(note!("C").unwrap()).into())
+        );
+        assert_eq!(PhrygianHalf.to_string(), "Phrygian half cadence");
+        assert_eq!("Phrygian half cadence".parse(), Ok(PhrygianHalf));
+        assert_eq!("perfect authentic".parse(), Ok(PerfectAuthentic));
+        assert_eq!("HC".parse(), Ok(Half));
+        assert!("authentic".parse::<Cadence>().is_err());
+    }
+
+    fn regions(segmenter: &HarmonicSegmenter, voices: &[&str]) -> Vec<(String, Duration)> {
+        let voices =
+            voices.iter().map(|voice| voice.parse::<Melody>().unwrap()).collect::<Vec<_>>();
+        segmenter
+            .segment(&voices)
+            .iter()
+            .map(|region| {
+                let chord = region.chord().map_or("-".to_string(), |chord| chord.to_string());
+                (chord, region.duration())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_segment() {
+        let segmenter = HarmonicSegmenter::new(time!(4, 4).unwrap());
+        let (whole, half) = (Duration::whole(), Duration::half());
+
+        // An arpeggiated accompaniment in a single voice
+        let alberti = "C4:1/8 G4:1/8 E4:1/8 G4:1/8 C4:1/8 G4:1/8 E4:1/8 G4:1/8 \
+                       B3:1/8 G4:1/8 D4:1/8 G4:1/8 B3:1/8 G4:1/8 F4:1/8 G4:1/8";
+        assert_eq!(
+            regions(&segmenter, &[alberti]),
+            vec![("C".to_string(), whole), ("G7/B".to_string(), whole)]
+        );
+
+        // Passing and neighbour notes off the beat do not change the chord, and a chord
+        // held over the barline is one region.
+        let soprano = "E5:1/4 F5:1/8 E5:1/8 D5:1/4 C5:1/4 B4:1/1 C5:1/2 r:1/2";
+        let alto = "G4:1/1 D4:1/1 E4:1/2 r:1/2";
+        let bass = "C3:1/1 G2:1/2 G2:1/2 C3:1/2 r:1/2";
+        let chords = regions(&segmenter, &[soprano, alto, bass]);
+        assert_eq!(
+            chords,
+            vec![
+                ("C".to_string(), whole),
+                ("G".to_string(), whole),
+                ("C".to_string(), half),
+                ("-".to_string(), half),
+            ]
+        );
+
+        // A wider window lets one region cover a chord held for two measures.
+        let held = ["C4:2/1 D4:1/1", "E4:2/1 F4:1/1", "G4:2/1 A4:1/1"];
+        assert_eq!(regions(&segmenter, &held)[0], ("C".to_string(), dur!(2, 1).unwrap()));
+        let segmenter = segmenter.with_window(dur!(2, 1).unwrap());
+        assert_eq!(regions(&segmenter, &held)[1], ("Dm".to_string(), whole));
+
+        // The bass decides between chords that share their notes, and chords with
+        // another chord tone in the bass are inversions.
+        let augmented = ["E3:1/1", "C4:1/1", "G#4:1/1"];
+        assert_eq!(regions(&segmenter, &augmented)[0].0, "Eaug");
+        let segmenter = segmenter.with_bass_weight(0.0);
+        assert_eq!(regions(&segmenter, &augmented)[0].0, "Caug/E");
+        let seventh = ["C3:1/1", "A3:1/1", "E4:1/1", "G4:1/1"];
+        assert_eq!(regions(&segmenter, &seventh)[0].0, "Am7/C");
+    }
+}
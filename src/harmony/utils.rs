@@ -0,0 +1,51 @@
+use super::*;
+use crate::err;
+use std::{
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+impl Display for HarmonicFunction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            HarmonicFunction::Tonic => "tonic",
+            HarmonicFunction::Predominant => "predominant",
+            HarmonicFunction::Dominant => "dominant",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl Display for Cadence {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Cadence::PerfectAuthentic => "perfect authentic cadence",
+            Cadence::ImperfectAuthentic => "imperfect authentic cadence",
+            Cadence::Half => "half cadence",
+            Cadence::Plagal => "plagal cadence",
+            Cadence::Deceptive => "deceptive cadence",
+            Cadence::PhrygianHalf => "Phrygian half cadence",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for Cadence {
+    type Err = ResonataError;
+
+    /// Parses a cadence from its name, with or without the word "cadence" and ignoring
+    /// case, or from its abbreviation, such as "PAC" or "HC".
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let name = s.trim().to_lowercase().replace('-', " ");
+        let name = name.strip_suffix("cadence").unwrap_or(&name).trim();
+        match name {
+            "perfect authentic" | "pac" => Ok(Cadence::PerfectAuthentic),
+            "imperfect authentic" | "iac" => Ok(Cadence::ImperfectAuthentic),
+            "half" | "hc" => Ok(Cadence::Half),
+            "plagal" | "pc" => Ok(Cadence::Plagal),
+            "deceptive" | "dc" => Ok(Cadence::Deceptive),
+            "phrygian half" | "phrygian" => Ok(Cadence::PhrygianHalf),
+            _ => err!(HarmonyError::InvalidCadence(s.to_string())),
+        }
+    }
+}
@@ -0,0 +1,375 @@
+use crate::{
+    chords::Chord,
+    error::{KeyboardError, ResonataError},
+    intervals::{Interval, Size},
+    nope,
+    notes::*,
+    scales::Scale,
+};
+
+pub use fingering::Hand;
+
+mod fingering;
+mod tests;
+mod utils;
+
+type Result<T> = std::result::Result<T, ResonataError>;
+
+/// The ways of laying out a chord on the keyboard.
+///
+/// - `Close` stacks the chord tones as tightly as possible, in every inversion.
+/// - `Open` spreads a close voicing by taking every other note up an octave.
+/// - `Drop2` and `Drop3` take the second or third note from the top of a close
+///   voicing of four or more notes down an octave, so triads have none.
+/// - `RootlessA` and `RootlessB` leave out the root and play the third, fifth (or
+///   thirteenth), seventh and ninth, from the third or from the seventh.
+/// - `Shell` plays the root with the third and seventh, in either order.
+/// - `Quartal` stacks four notes in perfect fourths that hold the chord's third,
+///   using chord tones and natural ninths, elevenths on minor chords and thirteenths.
+///
+/// Slash chords keep their bass below every voicing but rootless and quartal ones,
+/// which are meant to be played over a bass line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VoicingStyle {
+    Close,
+    Open,
+    Drop2,
+    Drop3,
+    RootlessA,
+    RootlessB,
+    Shell,
+    Quartal,
+}
+
+/// A piano keyboard, given by its lowest and highest keys
+///
+/// ### Examples
+/// ```
+/// use resonata::{notes::*, chords::*, keyboards::*};
+///
+/// let keyboard = Keyboard::new(pnote!("C3").unwrap(), pnote!("C5").unwrap()).unwrap();
+/// let voicings = keyboard.voicings(&chord!("Dm7").unwrap(), VoicingStyle::RootlessA);
+/// assert_eq!(voicings, vec![["F3", "A3", "C4", "E4"].map(|n| n.parse().unwrap())]);
+///
+/// let notes = ["C4", "D4", "E4", "F4", "G4"].map(|n| n.parse::<PitchedNote>().unwrap());
+/// assert_eq!(Keyboard::piano().fingering(&notes, Hand::Right).unwrap(), vec![1, 2, 3, 4, 5]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Keyboard {
+    lowest: PitchedNote,
+    highest: PitchedNote,
+}
+
+impl Keyboard {
+    /// Creates a keyboard from its lowest and highest keys.
+    ///
+    /// ### Failures
+    /// Returns an error if the lowest key is above the highest.
+    pub fn new(lowest: PitchedNote, highest: PitchedNote) -> Result<Self> {
+        if lowest.to_midi_number() > highest.to_midi_number() {
+            nope!(KeyboardError::InvalidRange(lowest, highest));
+        }
+        Ok(Keyboard { lowest, highest })
+    }
+
+    /// A full 88 key piano, from A0 to C8.
+    pub fn piano() -> Self {
+        Keyboard::new(PitchedNote::from_midi_number(21).unwrap(), pnote!("C8").unwrap()).unwrap()
+    }
+
+    /// Returns the lowest key.
+    pub fn lowest(&self) -> PitchedNote {
+        self.lowest
+    }
+
+    /// Returns the highest key.
+    pub fn highest(&self) -> PitchedNote {
+        self.highest
+    }
+
+    /// Returns true if the pitch is on the keyboard.
+    pub fn contains(&self, pitch: PitchedNote) -> bool {
+        let midi = pitch.to_midi_number();
+        self.lowest.to_midi_number() <= midi && midi <= self.highest.to_midi_number()
+    }
+
+    /// Returns true if the pitch is played on a black key.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::{notes::*, keyboards::*};
+    ///
+    /// assert!(Keyboard::is_black_key(pnote!("Bb3").unwrap()));
+    /// assert!(!Keyboard::is_black_key(pnote!("Cb4").unwrap()));
+    /// ```
+    pub fn is_black_key(pitch: PitchedNote) -> bool {
+        [1, 3, 6, 8, 10].contains(&(pitch.to_midi_number() % 12))
+    }
+
+    /// Returns every voicing of a chord in the given style that fits on the keyboard,
+    /// from the lowest to the highest. Each voicing lists its pitches from the bottom
+    /// up, spelled as in the chord. Styles that need a seventh or a sixth, such as
+    /// rootless and shell voicings, give no voicings of triads.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::{notes::*, chords::*, keyboards::*};
+    ///
+    /// let keyboard = Keyboard::new(pnote!("C3").unwrap(), pnote!("C5").unwrap()).unwrap();
+    /// let pitches = |s: &str| s.split(' ').map(|n| n.parse().unwrap()).collect::<Vec<PitchedNote>>();
+    ///
+    /// let voicings = keyboard.voicings(&chord!("Cmaj7").unwrap(), VoicingStyle::Drop2);
+    /// assert_eq!(voicings, vec![
+    ///     pitches("C3 G3 B3 E4"),
+    ///     pitches("E3 B3 C4 G4"),
+    ///     pitches("G3 C4 E4 B4"),
+    ///     pitches("B3 E4 G4 C5"),
+    /// ]);
+    ///
+    /// let voicings = keyboard.voicings(&chord!("G7").unwrap(), VoicingStyle::Shell);
+    /// assert_eq!(voicings[0], pitches("G3 B3 F4"));
+    /// assert_eq!(voicings[1], pitches("G3 F4 B4"));
+    ///
+    /// let voicings = keyboard.voicings(&chord!("C/E").unwrap(), VoicingStyle::Close);
+    /// assert!(voicings.iter().all(|voicing| Note::from(voicing[0]) == note!("E").unwrap()));
+    /// ```
+    pub fn voicings(&self, chord: &Chord, style: VoicingStyle) -> Vec<Vec<PitchedNote>> {
+        let mut voicings = Vec::new();
+        for shape in shapes(chord, style) {
+            for octave in -1..=9 {
+                let Some(voicing) = stack(&shape, octave) else {
+                    continue;
+                };
+                if voicing.iter().all(|pitch| self.contains(*pitch)) && !voicings.contains(&voicing)
+                {
+                    voicings.push(voicing);
+                }
+            }
+        }
+        voicings.sort_by_key(|voicing| {
+            voicing.iter().map(|pitch| pitch.to_midi_number()).collect::<Vec<_>>()
+        });
+        voicings
+    }
+
+    /// Suggests a fingering for a line of single notes, one finger for each note,
+    /// numbered from 1 for the thumb to 5 for the little finger. The fingering is the
+    /// one with the lowest cost under [Keyboard::fingering_cost].
+    ///
+    /// ### Failures
+    /// Returns an error if a note is not on the keyboard.
+    pub fn fingering(&self, notes: &[PitchedNote], hand: Hand) -> Result<Vec<u8>> {
+        self.check(notes)?;
+        Ok(fingering::best_fingering(notes, hand))
+    }
+
+    /// Returns the cost of playing a line of notes with the given fingers, where lower is
+    /// easier. Each pair of neighbouring notes costs more the further its span is from
+    /// the relaxed span of its fingers, and more again if the fingers cross other than by
+    /// passing the thumb under. The fourth and fifth fingers cost extra, as does the
+    /// thumb on a black key, and playing different notes in a row with one finger costs
+    /// the most of all.
+    ///
+    /// ### Failures
+    /// Returns an error if a note is not on the keyboard, if there is not one finger
+    /// for each note, or if a finger is not numbered from 1 to 5.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::{notes::*, keyboards::*};
+    ///
+    /// let notes = ["E4", "F4", "G4"].map(|n| n.parse::<PitchedNote>().unwrap());
+    /// let keyboard = Keyboard::piano();
+    /// let natural = keyboard.fingering_cost(&notes, &[3, 4, 5], Hand::Right).unwrap();
+    /// let crossed = keyboard.fingering_cost(&notes, &[3, 2, 1], Hand::Right).unwrap();
+    /// assert!(natural < crossed);
+    /// ```
+    pub fn fingering_cost(&self, notes: &[PitchedNote], fingers: &[u8], hand: Hand) -> Result<u32> {
+        self.check(notes)?;
+        if fingers.len() != notes.len() || fingers.iter().any(|finger| !(1..=5).contains(finger)) {
+            nope!(KeyboardError::InvalidFingering(fingers.to_vec()));
+        }
+        Ok(fingering::cost(notes, fingers, hand))
+    }
+
+    /// Suggests a fingering for a scale played up and back down from the given tonic,
+    /// over a number of octaves. Returns each note of the scale with its finger.
+    ///
+    /// ### Failures
+    /// Returns an error if a note of the scale is not on the keyboard.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::{notes::*, scales::*, keyboards::*};
+    ///
+    /// let keyboard = Keyboard::piano();
+    /// let tonic = pnote!("C4").unwrap();
+    /// let right = keyboard.scale_fingering(&Scale::major(), tonic, 1, Hand::Right).unwrap();
+    /// let fingers = right.iter().map(|(_, finger)| *finger).collect::<Vec<_>>();
+    /// assert_eq!(fingers, vec![1, 2, 3, 1, 2, 3, 4, 5, 4, 3, 2, 1, 3, 2, 1]);
+    ///
+    /// let left = keyboard.scale_fingering(&Scale::major(), tonic, 1, Hand::Left).unwrap();
+    /// let fingers = left.iter().map(|(_, finger)| *finger).collect::<Vec<_>>();
+    /// assert_eq!(fingers, vec![5, 4, 3, 2, 1, 3, 2, 1, 2, 3, 1, 2, 3, 4, 5]);
+    /// ```
+    pub fn scale_fingering(
+        &self,
+        scale: &Scale,
+        tonic: PitchedNote,
+        octaves: u8,
+        hand: Hand,
+    ) -> Result<Vec<(PitchedNote, u8)>> {
+        let intervals = scale.intervals();
+        let mut notes = Vec::new();
+        for octave in 0..octaves as i8 {
+            let base = Note::from(tonic).with_octave(tonic.octave() + octave)?;
+            notes.push(base);
+            for interval in intervals.iter().take(intervals.len() - 1) {
+                notes.push((base + *interval)?);
+            }
+        }
+        notes.push(Note::from(tonic).with_octave(tonic.octave() + octaves as i8)?);
+        let descent = notes.iter().rev().skip(1).copied().collect::<Vec<_>>();
+        notes.extend(descent);
+
+        let fingers = self.fingering(&notes, hand)?;
+        Ok(notes.into_iter().zip(fingers).collect())
+    }
+
+    /// Checks that every note is on the keyboard.
+    fn check(&self, notes: &[PitchedNote]) -> Result<()> {
+        match notes.iter().find(|pitch| !self.contains(**pitch)) {
+            Some(pitch) => nope!(KeyboardError::PitchOutOfRange(*pitch)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Returns the notes of each voicing of the chord in the style, from the bottom up.
+fn shapes(chord: &Chord, style: VoicingStyle) -> Vec<Vec<Note>> {
+    let root = chord.root();
+    let intervals = chord.intervals();
+    let find = |size: Size, octaves: u8| {
+        intervals.iter().find(|i| i.size() == size && i.octaves() == octaves).map(|i| root + *i)
+    };
+    let interval = |s: &str| root + s.parse::<Interval>().unwrap();
+    let third = find(Size::Third, 0);
+    let seventh = find(Size::Seventh, 0).or_else(|| find(Size::Sixth, 0));
+
+    // The bass of a slash chord goes below a voicing of the other notes.
+    let slash = chord.bass() != root;
+    let pitch_class = |note: &Note| note.to_chromatic_scale_degree().rem_euclid(12);
+    let upper = chord
+        .notes()
+        .into_iter()
+        .filter(|note| !slash || pitch_class(note) != pitch_class(&chord.bass()))
+        .collect::<Vec<_>>();
+    let inversions = (0..upper.len())
+        .map(|n| {
+            let mut inversion = upper.clone();
+            inversion.rotate_left(n);
+            inversion
+        })
+        .collect::<Vec<_>>();
+
+    let shapes = match style {
+        VoicingStyle::Close => inversions,
+        VoicingStyle::Open => inversions
+            .into_iter()
+            .map(|close| {
+                close.iter().step_by(2).chain(close.iter().skip(1).step_by(2)).copied().collect()
+            })
+            .collect(),
+        VoicingStyle::Drop2 | VoicingStyle::Drop3 => {
+            let drop = if style == VoicingStyle::Drop2 { 2 } else { 3 };
+            inversions
+                .into_iter()
+                .filter(|close| close.len() >= 4)
+                .map(|mut close| {
+                    let dropped = close.remove(close.len() - drop);
+                    close.insert(0, dropped);
+                    close
+                })
+                .collect()
+        }
+        VoicingStyle::RootlessA | VoicingStyle::RootlessB => {
+            let (Some(third), Some(seventh)) = (third, seventh) else {
+                return Vec::new();
+            };
+            let fifth = find(Size::Sixth, 1).or_else(|| find(Size::Fifth, 0));
+            let ninth = find(Size::Second, 1).unwrap_or(interval("M9"));
+            let Some(fifth) = fifth else {
+                return Vec::new();
+            };
+            match style {
+                VoicingStyle::RootlessA => vec![vec![third, fifth, seventh, ninth]],
+                _ => vec![vec![seventh, ninth, third, fifth]],
+            }
+        }
+        VoicingStyle::Shell => {
+            let (Some(third), Some(seventh)) = (third, seventh) else {
+                return Vec::new();
+            };
+            vec![vec![root, third, seventh], vec![root, seventh, third]]
+        }
+        VoicingStyle::Quartal => {
+            let Some(third) = third else {
+                return Vec::new();
+            };
+            let tensions = [interval("M9"), interval("M13")];
+            let mut allowed = [chord.notes(), tensions.to_vec()].concat();
+            if pitch_class(&third) == pitch_class(&interval("m3")) {
+                allowed.push(interval("P11"));
+            }
+            let allowed = allowed.iter().map(pitch_class).collect::<Vec<_>>();
+            let fourth = "P4".parse::<Interval>().unwrap();
+            chord
+                .notes()
+                .into_iter()
+                .chain(tensions)
+                .map(|bottom| {
+                    (0..3).fold(vec![bottom], |mut stack, _| {
+                        stack.push(*stack.last().unwrap() + fourth);
+                        stack
+                    })
+                })
+                .filter(|stack| stack.iter().all(|note| allowed.contains(&pitch_class(note))))
+                .filter(|stack| stack.iter().any(|note| pitch_class(note) == pitch_class(&third)))
+                .collect()
+        }
+    };
+
+    let mut shapes: Vec<Vec<Note>> = match style {
+        VoicingStyle::RootlessA | VoicingStyle::RootlessB | VoicingStyle::Quartal => shapes,
+        _ if slash => {
+            shapes.into_iter().map(|shape| [vec![chord.bass()], shape].concat()).collect()
+        }
+        _ => shapes,
+    };
+    shapes.dedup();
+    shapes
+}
+
+/// Places the notes from the bottom up, each in the lowest octave above the one before,
+/// starting with the first note in the given octave.
+fn stack(notes: &[Note], octave: i8) -> Option<Vec<PitchedNote>> {
+    let mut pitches: Vec<PitchedNote> = Vec::new();
+    for note in notes {
+        let pitch = match pitches.last() {
+            Some(below) => {
+                let below = below.to_midi_number() as i32;
+                let mut octave = below.div_euclid(12) - 2;
+                loop {
+                    let pitch = note.with_octave(octave as i8).ok()?;
+                    if pitch.to_midi_number() as i32 > below {
+                        break pitch;
+                    }
+                    octave += 1;
+                }
+            }
+            None => note.with_octave(octave).ok()?,
+        };
+        pitches.push(pitch);
+    }
+    Some(pitches)
+}
@@ -0,0 +1,144 @@
+use super::*;
+
+/// The hand playing a line of notes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Hand {
+    Left,
+    Right,
+}
+
+/// The spans, in semitones, that a pair of fingers of the right hand can reach from the
+/// lower finger to the higher: the practical limits, the comfortable limits, and the
+/// relaxed span. Negative spans pass the thumb under or cross a finger over it.
+struct Span {
+    min_practical: i32,
+    min_comfortable: i32,
+    min_relaxed: i32,
+    max_relaxed: i32,
+    max_comfortable: i32,
+    max_practical: i32,
+}
+
+/// Returns the spans of two fingers, lowest finger first.
+fn span(low: u8, high: u8) -> Span {
+    let [min_practical, min_comfortable, min_relaxed, max_relaxed, max_comfortable, max_practical] =
+        match (low, high) {
+            (1, 2) => [-5, -3, 1, 2, 8, 10],
+            (1, 3) => [-4, -2, 3, 4, 10, 12],
+            (1, 4) => [-3, -1, 5, 6, 12, 14],
+            (1, 5) => [-1, 1, 7, 8, 13, 15],
+            (2, 3) => [1, 1, 1, 2, 3, 5],
+            (2, 4) => [1, 1, 3, 4, 5, 7],
+            (2, 5) => [2, 2, 5, 6, 8, 10],
+            (3, 4) => [1, 1, 1, 2, 2, 4],
+            (3, 5) => [1, 1, 3, 4, 5, 7],
+            _ => [1, 1, 1, 2, 3, 5],
+        };
+    Span {
+        min_practical,
+        min_comfortable,
+        min_relaxed,
+        max_relaxed,
+        max_comfortable,
+        max_practical,
+    }
+}
+
+/// Returns the cost of moving from one note and finger to the next.
+fn step_cost(from: (PitchedNote, u8), to: (PitchedNote, u8), hand: Hand) -> u32 {
+    let ((from, from_finger), (to, to_finger)) = (from, to);
+    let mut semitones = from.semitones_to(&to);
+    if hand == Hand::Left {
+        semitones = -semitones;
+    }
+    if from_finger == to_finger {
+        return if semitones == 0 { 0 } else { 10 };
+    }
+
+    // Measure the span from the lower finger to the higher.
+    let (low, high) = (from_finger.min(to_finger), from_finger.max(to_finger));
+    let (distance, low_pitch, high_pitch) = match from_finger < to_finger {
+        true => (semitones, from, to),
+        false => (-semitones, to, from),
+    };
+    let span = span(low, high);
+    let thumb = low == 1;
+    let mut cost = 0;
+
+    let below = |limit: i32| (limit - distance).max(0) as u32;
+    let above = |limit: i32| (distance - limit).max(0) as u32;
+    cost += 2 * (below(span.min_comfortable) + above(span.max_comfortable));
+    cost += 10 * (below(span.min_practical) + above(span.max_practical));
+
+    if thumb && distance < 0 {
+        // Passing the thumb under moves the hand, and is easiest between keys of the
+        // same colour and hardest with the thumb on a black key.
+        let thumb_black = Keyboard::is_black_key(low_pitch);
+        let finger_black = Keyboard::is_black_key(high_pitch);
+        cost += 5 + match (thumb_black, finger_black) {
+            (true, false) => 3,
+            (false, true) => 2,
+            _ => 1,
+        };
+    } else {
+        cost += if thumb { 2 } else { 1 } * below(span.min_relaxed);
+        cost += if thumb { 1 } else { 2 } * above(span.max_relaxed);
+    }
+    cost
+}
+
+/// Returns the cost of playing a note with a finger, regardless of its neighbours.
+fn note_cost(pitch: PitchedNote, finger: u8) -> u32 {
+    let black = Keyboard::is_black_key(pitch);
+    match finger {
+        1 if black => 2,
+        4 => 1,
+        5 if black => 2,
+        5 => 1,
+        _ => 0,
+    }
+}
+
+/// Returns the cost of playing the notes with the fingers.
+pub(super) fn cost(notes: &[PitchedNote], fingers: &[u8], hand: Hand) -> u32 {
+    let notes = notes.iter().copied().zip(fingers.iter().copied()).collect::<Vec<_>>();
+    let played = notes.iter().map(|(pitch, finger)| note_cost(*pitch, *finger)).sum::<u32>();
+    let moves = notes.windows(2).map(|pair| step_cost(pair[0], pair[1], hand)).sum::<u32>();
+    played + moves
+}
+
+/// Finds the fingering with the lowest cost, breaking ties towards lower fingers.
+pub(super) fn best_fingering(notes: &[PitchedNote], hand: Hand) -> Vec<u8> {
+    let Some(first) = notes.first() else {
+        return Vec::new();
+    };
+    // The lowest cost of reaching each note with each finger, and the finger before.
+    let mut table: Vec<[(u32, u8); 5]> = vec![[(0, 0); 5]];
+    for finger in 1..=5 {
+        table[0][finger as usize - 1] = (note_cost(*first, finger), 0);
+    }
+    for (index, pair) in notes.windows(2).enumerate() {
+        let mut row = [(u32::MAX, 0); 5];
+        for to in 1..=5u8 {
+            for from in 1..=5u8 {
+                let cost = table[index][from as usize - 1].0
+                    + step_cost((pair[0], from), (pair[1], to), hand)
+                    + note_cost(pair[1], to);
+                if cost < row[to as usize - 1].0 {
+                    row[to as usize - 1] = (cost, from);
+                }
+            }
+        }
+        table.push(row);
+    }
+
+    let last = table.last().unwrap();
+    let mut finger = (1..=5u8).min_by_key(|finger| last[*finger as usize - 1].0).unwrap();
+    let mut fingers = vec![finger];
+    for row in table.iter().skip(1).rev() {
+        finger = row[finger as usize - 1].1;
+        fingers.push(finger);
+    }
+    fingers.reverse();
+    fingers
+}
@@ -0,0 +1,80 @@
+#[cfg(test)]
+mod tests {
+    use super::super::*;
+    use crate::{chords::*, error::KeyboardError};
+
+    fn pitches(s: &str) -> Vec<PitchedNote> {
+        s.split(' ').map(|pitch| pitch.parse().unwrap()).collect()
+    }
+
+    fn keyboard(lowest: &str, highest: &str) -> Keyboard {
+        Keyboard::new(lowest.parse().unwrap(), highest.parse().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_voicings() {
+        let keyboard = keyboard("C3", "C5");
+        let chord = chord!("Cmaj7").unwrap();
+        let close = keyboard.voicings(&chord, VoicingStyle::Close);
+        assert_eq!(close.len(), 6);
+        assert_eq!(close[1], pitches("E3 G3 B3 C4"));
+        let open = keyboard.voicings(&chord, VoicingStyle::Open);
+        assert_eq!(open, vec![pitches("C3 G3 E4 B4"), pitches("E3 B3 G4 C5")]);
+        let drop3 = keyboard.voicings(&chord, VoicingStyle::Drop3);
+        assert_eq!(drop3[0], pitches("C3 B3 E4 G4"));
+
+        let g13 = chord!("G13").unwrap();
+        assert_eq!(keyboard.voicings(&g13, VoicingStyle::RootlessA), vec![pitches("B3 E4 F4 A4")]);
+        assert_eq!(keyboard.voicings(&g13, VoicingStyle::RootlessB), vec![pitches("F3 A3 B3 E4")]);
+        assert_eq!(keyboard.voicings(&chord, VoicingStyle::Quartal), vec![pitches("E3 A3 D4 G4")]);
+        let dm7 = chord!("Dm7").unwrap();
+        assert_eq!(keyboard.voicings(&dm7, VoicingStyle::Quartal), vec![pitches("D3 G3 C4 F4")]);
+
+        // Triads have no shells, drop or rootless voicings, and slash chords keep their bass.
+        for style in [VoicingStyle::Shell, VoicingStyle::Drop2, VoicingStyle::Drop3] {
+            assert!(keyboard.voicings(&chord!("C").unwrap(), style).is_empty());
+        }
+        assert!(keyboard.voicings(&chord!("C").unwrap(), VoicingStyle::RootlessA).is_empty());
+        let voicings = keyboard.voicings(&chord!("C/E").unwrap(), VoicingStyle::Open);
+        assert_eq!(voicings[0], pitches("E3 G3 C4"));
+        assert!(voicings.iter().all(|voicing| Note::from(voicing[0]) == note!("E").unwrap()));
+
+        // Voicings keep the chord's spelling.
+        let keyboard = Keyboard::piano();
+        let voicings = keyboard.voicings(&chord!("Dbmaj7").unwrap(), VoicingStyle::Close);
+        assert!(voicings.contains(&pitches("Db4 F4 Ab4 C5")));
+    }
+
+    #[test]
+    fn test_fingering() {
+        let keyboard = Keyboard::piano();
+        let scale = |tonic: &str, octaves: u8, hand: Hand| {
+            let fingering = keyboard
+                .scale_fingering(&Scale::major(), tonic.parse().unwrap(), octaves, hand)
+                .unwrap();
+            fingering.into_iter().map(|(_, finger)| finger).collect::<Vec<_>>()
+        };
+        let up = [1, 2, 3, 1, 2, 3, 4, 1, 2, 3, 1, 2, 3, 4, 5];
+        for tonic in ["C4", "G4", "D4"] {
+            assert_eq!(scale(tonic, 2, Hand::Right)[..15], up);
+        }
+        assert_eq!(scale("C3", 2, Hand::Left)[..5], [5, 4, 3, 2, 1]);
+
+        let notes = pitches("C4 D4 E4 F4 G4");
+        assert_eq!(keyboard.fingering(&notes, Hand::Right).unwrap(), vec![1, 2, 3, 4, 5]);
+        assert_eq!(keyboard.fingering(&notes, Hand::Left).unwrap(), vec![5, 4, 3, 2, 1]);
+        let cost = |fingers: &[u8]| keyboard.fingering_cost(&notes, fingers, Hand::Right).unwrap();
+        assert!(cost(&[1, 2, 3, 4, 5]) < cost(&[1, 2, 1, 2, 3]));
+        assert!(cost(&[1, 2, 3, 1, 2]) < cost(&[1, 1, 2, 3, 4]));
+
+        assert_eq!(
+            keyboard.fingering_cost(&notes, &[1, 2, 3], Hand::Right),
+            Err(ResonataError::from(KeyboardError::InvalidFingering(vec![1, 2, 3])))
+        );
+        let small = Keyboard::new(pnote!("C4").unwrap(), pnote!("F4").unwrap()).unwrap();
+        assert_eq!(
+            small.fingering(&notes, Hand::Right),
+            Err(ResonataError::from(KeyboardError::PitchOutOfRange(pnote!("G4").unwrap())))
+        );
+    }
+}
@@ -0,0 +1,18 @@
+use super::*;
+use std::fmt::{self, Display, Formatter};
+
+impl Display for VoicingStyle {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            VoicingStyle::Close => "close",
+            VoicingStyle::Open => "open",
+            VoicingStyle::Drop2 => "drop 2",
+            VoicingStyle::Drop3 => "drop 3",
+            VoicingStyle::RootlessA => "rootless A",
+            VoicingStyle::RootlessB => "rootless B",
+            VoicingStyle::Shell => "shell",
+            VoicingStyle::Quartal => "quartal",
+        };
+        write!(f, "{}", name)
+    }
+}
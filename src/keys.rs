@@ -1,4 +1,8 @@
-use crate::{error::ResonataError, notes::*, scales::Scale};
+use crate::{
+    error::ResonataError,
+    notes::*,
+    scales::{MajorMode, Scale},
+};
 use std::ops::Index;
 mod utils;
 
@@ -75,6 +79,31 @@ impl Key {
         Key { pitches }
     }
 
+    /// Creates a key from a tonic and a mode of the major scale. The pitches are
+    /// spelled from the parent major scale of the mode, so D Dorian has no
+    /// accidentals and F# Aeolian has three sharps.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::{notes::*, keys::*, scales::*};
+    ///
+    /// let key = Key::from_mode(note!("D").unwrap(), MajorMode::Dorian);
+    /// assert_eq!(key, key!("C D E F G A B").unwrap());
+    ///
+    /// let key = Key::from_mode(note!("F#").unwrap(), MajorMode::Aeolian);
+    /// assert_eq!(key, key!("F# C# G#").unwrap());
+    ///
+    /// let key = Key::from_mode(note!("Bb").unwrap(), MajorMode::Mixolydian);
+    /// assert_eq!(key, key!("Bb Eb Ab").unwrap());
+    /// ```
+    pub fn from_mode(tonic: Note, mode: MajorMode) -> Key {
+        let parent = match mode as usize {
+            0 => tonic,
+            n => tonic - Scale::major().interval(n - 1),
+        };
+        Key::new(Scale::major().to_notes(parent))
+    }
+
     pub fn set_pitch(&mut self, note: Note) {
         self.pitches.insert(note.name(), note.accidental());
     }
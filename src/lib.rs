@@ -1,10 +1,24 @@
 use intervals::Interval;
 
+pub mod audio;
+pub mod chords;
+pub mod counterpoint;
+pub mod durations;
 pub mod error;
+pub mod exercises;
+pub mod fretboards;
+pub mod generators;
+pub mod harmony;
 pub mod intervals;
+pub mod keyboards;
 pub mod keys;
+pub mod melodies;
+pub mod notation;
 pub mod notes;
 pub mod scales;
+#[cfg(feature = "serde")]
+pub mod serialization;
+pub mod time_signatures;
 
 pub trait TransposeUp {
     type Output;
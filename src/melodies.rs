@@ -0,0 +1,351 @@
+use crate::{
+    durations::{Duration, Event},
+    error::ResonataError,
+    intervals::Interval,
+    keys::Key,
+    notes::*,
+    scales::{ChromaticPolicy, Scale},
+    TransposeDown, TransposeUp,
+};
+
+pub use crate::melody;
+
+mod tests;
+mod utils;
+
+type Result<T> = std::result::Result<T, ResonataError>;
+
+/// The direction of a melodic step, as used in contour analysis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Contour {
+    Up,
+    Down,
+    Repeat,
+}
+
+/// A melody, or a single voice of a larger texture
+///
+/// A melody is an ordered list of timed events, each either a pitched note or a rest.
+/// Transformations apply to every note in the line and leave rests where they are.
+///
+/// A macro is provided to make creating melodies easier:
+/// melody!(string)
+///
+/// ### Examples
+/// ```
+/// use resonata::{notes::*, intervals::*, durations::*, melodies::*, TransposeUp};
+///
+/// let melody = melody!("C4:1/4 D4:1/8 E4:1/8 r:1/4 G4:1/4").unwrap();
+/// assert_eq!(melody.len(), 5);
+/// assert_eq!(melody.duration(), Duration::whole());
+///
+/// let up = melody.transposed_up(inv!("M2").unwrap()).unwrap();
+/// assert_eq!(up, melody!("D4:1/4 E4:1/8 F#4:1/8 r:1/4 A4:1/4").unwrap());
+///
+/// let backwards = melody.retrograde();
+/// assert_eq!(backwards, melody!("G4:1/4 r:1/4 E4:1/8 D4:1/8 C4:1/4").unwrap());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Melody {
+    events: Vec<Event>,
+}
+
+/// A macro to create a melody.
+#[macro_export]
+macro_rules! melody {
+    ($str:literal) => {
+        $str.parse::<Melody>()
+    };
+    ($($event:expr),*) => {
+        Melody::new(vec![$($event),*])
+    };
+}
+
+impl Melody {
+    /// Creates a melody from a list of events.
+    pub fn new(events: Vec<Event>) -> Self {
+        Melody { events }
+    }
+
+    /// Creates a melody of notes that all have the same duration.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::{notes::*, durations::*, melodies::*};
+    ///
+    /// let notes = vec![pnote!("E4").unwrap(), pnote!("D4").unwrap(), pnote!("C4").unwrap()];
+    /// let melody = Melody::from_notes(&notes, Duration::half());
+    /// assert_eq!(melody, melody!("E4:1/2 D4:1/2 C4:1/2").unwrap());
+    /// ```
+    pub fn from_notes(notes: &[PitchedNote], duration: Duration) -> Self {
+        Melody { events: notes.iter().map(|note| Event::Note(*note, duration)).collect() }
+    }
+
+    /// Adds an event to the end of the melody.
+    pub fn push(&mut self, event: Event) {
+        self.events.push(event);
+    }
+
+    /// Returns the events in the melody.
+    pub fn events(&self) -> &Vec<Event> {
+        &self.events
+    }
+
+    /// Returns the pitches of the notes in the melody, skipping rests.
+    pub fn notes(&self) -> Vec<PitchedNote> {
+        self.events.iter().filter_map(|event| event.pitch()).collect()
+    }
+
+    /// Returns the number of events in the melody.
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Returns true if the melody has no events.
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Returns the total duration of the melody.
+    pub fn duration(&self) -> Duration {
+        self.events.iter().map(|event| event.duration()).sum()
+    }
+
+    /// Returns the lowest and highest notes of the melody, or `None` if it has no notes.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::{notes::*, melodies::*};
+    ///
+    /// let melody = melody!("E4:1/4 B3:1/4 r:1/4 F#4:1/4").unwrap();
+    /// assert_eq!(melody.ambitus(), Some((pnote!("B3").unwrap(), pnote!("F#4").unwrap())));
+    /// ```
+    pub fn ambitus(&self) -> Option<(PitchedNote, PitchedNote)> {
+        let notes = self.notes();
+        let lowest = notes.iter().min_by_key(|note| note.to_midi_number())?;
+        let highest = notes.iter().max_by_key(|note| note.to_midi_number())?;
+        Some((*lowest, *highest))
+    }
+
+    /// Returns the number of semitones between each pair of consecutive notes,
+    /// skipping rests.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::melodies::*;
+    ///
+    /// let melody = melody!("C4:1/4 E4:1/4 r:1/4 D4:1/4 D4:1/4").unwrap();
+    /// assert_eq!(melody.semitone_steps(), vec![4, -2, 0]);
+    /// ```
+    pub fn semitone_steps(&self) -> Vec<i32> {
+        self.notes().windows(2).map(|pair| pair[0].semitones_to(&pair[1])).collect()
+    }
+
+    /// Returns the direction of each step between consecutive notes, skipping rests.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::melodies::*;
+    ///
+    /// let melody = melody!("C4:1/4 E4:1/4 r:1/4 D4:1/4 D4:1/4").unwrap();
+    /// assert_eq!(melody.contour(), vec![Contour::Up, Contour::Down, Contour::Repeat]);
+    /// ```
+    pub fn contour(&self) -> Vec<Contour> {
+        self.semitone_steps()
+            .into_iter()
+            .map(|step| match step {
+                n if n > 0 => Contour::Up,
+                n if n < 0 => Contour::Down,
+                _ => Contour::Repeat,
+            })
+            .collect()
+    }
+
+    /// Returns the contour segment of the melody: each note is replaced by the rank
+    /// of its pitch among the distinct pitches of the melody, counting from zero
+    /// for the lowest.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::melodies::*;
+    ///
+    /// let melody = melody!("G4:1/4 C5:1/4 E4:1/4 G4:1/4").unwrap();
+    /// assert_eq!(melody.contour_segment(), vec![1, 2, 0, 1]);
+    /// ```
+    pub fn contour_segment(&self) -> Vec<usize> {
+        let notes = self.notes();
+        let mut pitches = notes.iter().map(|note| note.to_midi_number()).collect::<Vec<_>>();
+        pitches.sort();
+        pitches.dedup();
+        notes.iter().map(|note| pitches.binary_search(&note.to_midi_number()).unwrap()).collect()
+    }
+
+    /// Returns the melody with every note moved by the given number of note names,
+    /// keeping each note's accidental, as `PitchedNote::moved_by` does.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::melodies::*;
+    ///
+    /// let melody = melody!("C4:1/4 F#4:1/4 r:1/4 B4:1/4").unwrap();
+    /// assert_eq!(melody.moved_by(2).unwrap(), melody!("E4:1/4 A#4:1/4 r:1/4 D5:1/4").unwrap());
+    /// ```
+    pub fn moved_by(&self, steps: i32) -> Result<Self> {
+        self.map_notes(|note| note.moved_by(steps))
+    }
+
+    /// Returns the melody moved by the given number of degrees of a scale built
+    /// on the given root, so that a sequence keeps the spelling of the scale.
+    /// Notes outside the scale are handled according to the policy.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::{notes::*, scales::*, melodies::*};
+    ///
+    /// let melody = melody!("D4:1/4 E4:1/4 F#4:1/4 D4:1/4").unwrap();
+    /// let (scale, root) = (Scale::major(), note!("D").unwrap());
+    /// let policy = ChromaticPolicy::Reject;
+    ///
+    /// let moved = melody.transposed_in_scale(2, &scale, root, policy).unwrap();
+    /// assert_eq!(moved, melody!("F#4:1/4 G4:1/4 A4:1/4 F#4:1/4").unwrap());
+    ///
+    /// let moved = melody.transposed_in_scale(-3, &scale, root, policy).unwrap();
+    /// assert_eq!(moved, melody!("A3:1/4 B3:1/4 C#4:1/4 A3:1/4").unwrap());
+    ///
+    /// let chromatic = melody!("D4:1/4 F4:1/4").unwrap();
+    /// assert!(chromatic.transposed_in_scale(1, &scale, root, policy).is_err());
+    /// ```
+    pub fn transposed_in_scale(
+        &self,
+        steps: i32,
+        scale: &Scale,
+        root: Note,
+        policy: ChromaticPolicy,
+    ) -> Result<Self> {
+        self.map_notes(|note| note.moved_in_scale(steps, scale, root, policy))
+    }
+
+    /// Returns the melody moved by the given number of degrees of the key.
+    /// Notes outside the key are handled according to the policy.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::{notes::*, keys::*, scales::*, melodies::*};
+    ///
+    /// let melody = melody!("G4:1/8 F#4:1/8 G4:1/8 A4:1/8").unwrap();
+    /// let key = key!("Bb Eb").unwrap();
+    /// let moved = melody.transposed_in_key(-1, &key, ChromaticPolicy::KeepAlteration).unwrap();
+    /// assert_eq!(moved, melody!("F4:1/8 E4:1/8 F4:1/8 G4:1/8").unwrap());
+    /// ```
+    pub fn transposed_in_key(
+        &self,
+        steps: i32,
+        key: &Key,
+        policy: ChromaticPolicy,
+    ) -> Result<Self> {
+        self.map_notes(|note| note.moved_in_key(steps, key, policy))
+    }
+
+    /// Returns the melody inverted around the given axis pitch: each note is replaced
+    /// by the note the same interval away from the axis in the opposite direction.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::{notes::*, melodies::*};
+    ///
+    /// let melody = melody!("C4:1/4 E4:1/4 G4:1/4 B3:1/4").unwrap();
+    /// let inverted = melody.inverted(pnote!("C4").unwrap()).unwrap();
+    /// assert_eq!(inverted, melody!("C4:1/4 Ab3:1/4 F3:1/4 Db4:1/4").unwrap());
+    /// ```
+    pub fn inverted(&self, axis: PitchedNote) -> Result<Self> {
+        self.map_notes(|note| match axis.diatonic_distance_to(&note) {
+            // Notes of the axis's letter and octave mirror its accidental.
+            0 => {
+                let semitones =
+                    2 * axis.accidental().to_semitones() - note.accidental().to_semitones();
+                PitchedNote::new(axis.name(), Accidental::from_semitones(semitones), axis.octave())
+            }
+            n if n > 0 => axis - axis.interval_to(&note),
+            _ => axis + note.interval_to(&axis),
+        })
+    }
+
+    /// Returns the melody in reverse order.
+    pub fn retrograde(&self) -> Self {
+        Melody { events: self.events.iter().rev().copied().collect() }
+    }
+
+    /// Returns the melody with every duration multiplied by the given fraction.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::melodies::*;
+    ///
+    /// let melody = melody!("C4:1/4 D4:1/8 r:1/8").unwrap();
+    /// assert_eq!(melody.time_scaled(3, 2).unwrap(), melody!("C4:3/8 D4:3/16 r:3/16").unwrap());
+    /// ```
+    pub fn time_scaled(&self, numerator: u32, denominator: u32) -> Result<Self> {
+        let events = self
+            .events
+            .iter()
+            .map(|event| Ok(event.with_duration(event.duration().scaled(numerator, denominator)?)))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Melody { events })
+    }
+
+    /// Returns the melody with every duration multiplied by the given factor.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::melodies::*;
+    ///
+    /// let melody = melody!("C4:1/8 D4:1/8 E4:1/4").unwrap();
+    /// assert_eq!(melody.augmented(2).unwrap(), melody!("C4:1/4 D4:1/4 E4:1/2").unwrap());
+    /// ```
+    pub fn augmented(&self, factor: u32) -> Result<Self> {
+        self.time_scaled(factor, 1)
+    }
+
+    /// Returns the melody with every duration divided by the given factor.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::melodies::*;
+    ///
+    /// let melody = melody!("C4:1/8 D4:1/8 E4:1/4").unwrap();
+    /// assert_eq!(melody.diminished(2).unwrap(), melody!("C4:1/16 D4:1/16 E4:1/8").unwrap());
+    /// ```
+    pub fn diminished(&self, factor: u32) -> Result<Self> {
+        self.time_scaled(1, factor)
+    }
+
+    /// Applies the given function to every note in the melody, keeping rests and durations.
+    fn map_notes<F>(&self, mut f: F) -> Result<Self>
+    where
+        F: FnMut(PitchedNote) -> Result<PitchedNote>,
+    {
+        let events = self
+            .events
+            .iter()
+            .map(|event| match event {
+                Event::Note(note, duration) => Ok(Event::Note(f(*note)?, *duration)),
+                Event::Rest(duration) => Ok(Event::Rest(*duration)),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Melody { events })
+    }
+}
+
+impl TransposeUp for Melody {
+    type Output = Result<Melody>;
+    fn transposed_up(&self, interval: Interval) -> Self::Output {
+        self.map_notes(|note| note + interval)
+    }
+}
+
+impl TransposeDown for Melody {
+    type Output = Result<Melody>;
+    fn transposed_down(&self, interval: Interval) -> Self::Output {
+        self.map_notes(|note| note - interval)
+    }
+}
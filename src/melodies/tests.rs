@@ -0,0 +1,77 @@
+#[cfg(test)]
+mod tests {
+    use super::super::*;
+    use crate::{dur, durations::*, intervals::*, scales::*};
+
+    #[test]
+    fn test_parse_and_display() {
+        let melody = "C#4:1/8 r:q Bb3:3/8".parse::<Melody>().unwrap();
+        assert_eq!(
+            melody.events(),
+            &vec![
+                Event::Note(pnote!("C#4").unwrap(), Duration::eighth()),
+                Event::Rest(Duration::quarter()),
+                Event::Note(pnote!("Bb3").unwrap(), dur!(3, 8).unwrap()),
+            ]
+        );
+        assert_eq!(melody.to_string().parse::<Melody>().unwrap(), melody);
+        assert!("".parse::<Melody>().unwrap().is_empty());
+        assert!("C4:1/4 C4".parse::<Melody>().is_err());
+    }
+
+    #[test]
+    fn test_chromatic_transposition() {
+        let melody = melody!("E4:1/4 G#4:1/4 r:1/4 B4:1/2").unwrap();
+        let down = melody.transposed_down(inv!("m3").unwrap()).unwrap();
+        assert_eq!(down, melody!("C#4:1/4 E#4:1/4 r:1/4 G#4:1/2").unwrap());
+        assert_eq!(down.transposed_up(inv!("m3").unwrap()).unwrap(), melody);
+        assert!(melody!("G9:1/4").unwrap().transposed_up(inv!("M2").unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_scale_transposition() {
+        // A sequence in G harmonic minor keeps its raised seventh.
+        let melody = melody!("G4:1/8 A4:1/8 Bb4:1/8 F#4:1/8").unwrap();
+        let root = note!("G").unwrap();
+        let scale = Scale::harmonic_minor();
+        let policy = ChromaticPolicy::Reject;
+        let moved = melody.transposed_in_scale(1, &scale, root, policy).unwrap();
+        assert_eq!(moved, melody!("A4:1/8 Bb4:1/8 C5:1/8 G4:1/8").unwrap());
+        let moved = melody.transposed_in_scale(7, &scale, root, policy).unwrap();
+        assert_eq!(moved, melody!("G5:1/8 A5:1/8 Bb5:1/8 F#5:1/8").unwrap());
+        let moved = melody.transposed_in_scale(-1, &scale, root, policy).unwrap();
+        assert_eq!(moved, melody!("F#4:1/8 G4:1/8 A4:1/8 Eb4:1/8").unwrap());
+
+        // Enharmonic spellings of scale notes are respelled.
+        let melody = melody!("A#3:1/4").unwrap();
+        let moved = melody.transposed_in_scale(0, &scale, root, policy).unwrap();
+        assert_eq!(moved, melody!("Bb3:1/4").unwrap());
+    }
+
+    #[test]
+    fn test_inversion() {
+        let melody = melody!("D4:1/4 F#4:1/4 A4:1/4 C5:1/4 r:1/4").unwrap();
+        let inverted = melody.inverted(pnote!("D4").unwrap()).unwrap();
+        assert_eq!(inverted, melody!("D4:1/4 Bb3:1/4 G3:1/4 E3:1/4 r:1/4").unwrap());
+        assert_eq!(inverted.inverted(pnote!("D4").unwrap()).unwrap(), melody);
+
+        // Notes of the axis's letter mirror its accidental.
+        let melody = melody!("Cb4:1/4 C#4:1/4 C4:1/4 C##4:1/4").unwrap();
+        let inverted = melody.inverted(pnote!("C4").unwrap()).unwrap();
+        assert_eq!(inverted, melody!("C#4:1/4 Cb4:1/4 C4:1/4 Cbb4:1/4").unwrap());
+        let inverted = melody.inverted(pnote!("C#4").unwrap()).unwrap();
+        assert_eq!(inverted, melody!("C###4:1/4 C#4:1/4 C##4:1/4 C4:1/4").unwrap());
+    }
+
+    #[test]
+    fn test_contour() {
+        let melody = melody!("C4:1/4 C4:1/4 G4:1/4 E4:1/4 r:1/4 C5:1/4").unwrap();
+        assert_eq!(melody.semitone_steps(), vec![0, 7, -3, 8]);
+        assert_eq!(
+            melody.contour(),
+            vec![Contour::Repeat, Contour::Up, Contour::Down, Contour::Up]
+        );
+        assert_eq!(melody.contour_segment(), vec![0, 0, 2, 1, 3]);
+        assert_eq!(melody.retrograde().contour_segment(), vec![3, 1, 2, 0, 0]);
+    }
+}
@@ -0,0 +1,33 @@
+use super::*;
+use std::{
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+impl From<Vec<Event>> for Melody {
+    fn from(events: Vec<Event>) -> Self {
+        Melody::new(events)
+    }
+}
+
+impl FromIterator<Event> for Melody {
+    fn from_iter<I: IntoIterator<Item = Event>>(iter: I) -> Self {
+        Melody::new(iter.into_iter().collect())
+    }
+}
+
+impl FromStr for Melody {
+    type Err = ResonataError;
+
+    /// Parses a melody from whitespace separated events, such as "C4:1/4 r:1/8 D4:1/8".
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        s.split_whitespace().map(|event| event.parse::<Event>()).collect()
+    }
+}
+
+impl Display for Melody {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let events = self.events.iter().map(|event| event.to_string()).collect::<Vec<_>>();
+        write!(f, "{}", events.join(" "))
+    }
+}
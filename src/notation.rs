@@ -0,0 +1,3 @@
+pub mod abc;
+pub mod kern;
+pub mod tab;
@@ -0,0 +1,210 @@
+use crate::{
+    durations::{Duration, Tempo},
+    error::ResonataError,
+    keys::Key,
+    notes::*,
+    scales::MajorMode,
+    time_signatures::TimeSignature,
+};
+
+mod tests;
+mod utils;
+
+type Result<T> = std::result::Result<T, ResonataError>;
+
+/// A single element of the body of an ABC tune.
+///
+/// Notes, chords and rests carry their resolved pitches and durations: accidentals
+/// have already been applied from the key signature and from earlier accidentals in
+/// the same bar, and lengths are absolute rather than multiples of the unit note length.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AbcElement {
+    Note(PitchedNote, Duration),
+    Chord(Vec<PitchedNote>, Duration),
+    Rest(Duration),
+    ChordSymbol(String),
+    Tie,
+    Bar,
+}
+
+impl AbcElement {
+    /// Returns the duration of the element, if it takes up time.
+    pub fn duration(&self) -> Option<Duration> {
+        match self {
+            AbcElement::Note(_, duration)
+            | AbcElement::Chord(_, duration)
+            | AbcElement::Rest(duration) => Some(*duration),
+            _ => None,
+        }
+    }
+}
+
+/// A tune in ABC notation.
+///
+/// A tune is made up of a header, of which the `X:`, `T:`, `M:`, `L:`, `Q:` and `K:` fields
+/// are understood, and a body of notes, chords, rests, chord symbols and bar lines.
+/// Tunes can be parsed from a string, and written back out using `Display`.
+///
+/// The `K:` field is read as a tonic, a mode and any explicit accidentals, such as
+/// `K:D =c`, and note accidentals are resolved against `Key::pitch`. As in standard
+/// ABC, an accidental lasts until the end of the bar, for notes of the same pitch and
+/// octave.
+///
+/// ### Examples
+/// ```
+/// use resonata::{notes::*, notation::abc::*, scales::*, durations::*};
+///
+/// let tune: AbcTune = "X:1\nT:Example\nM:3/4\nL:1/8\nK:G\nf2 ^c =f d2|f4 z2|]".parse().unwrap();
+/// assert_eq!(tune.title(), Some("Example"));
+/// assert_eq!(tune.tonic(), note!("G").unwrap());
+/// assert_eq!(tune.mode(), MajorMode::Ionian);
+/// assert_eq!(tune.notes(), vec![
+///     pnote!("F#5").unwrap(),
+///     pnote!("C#5").unwrap(),
+///     pnote!("F5").unwrap(),
+///     pnote!("D5").unwrap(),
+///     pnote!("F#5").unwrap(),
+/// ]);
+/// assert_eq!(tune.elements()[0], AbcElement::Note(pnote!("F#5").unwrap(), Duration::quarter()));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct AbcTune {
+    reference: u32,
+    title: Option<String>,
+    meter: Option<TimeSignature>,
+    unit_length: Duration,
+    tempo: Option<Tempo>,
+    tonic: Note,
+    mode: MajorMode,
+    key: Key,
+    elements: Vec<AbcElement>,
+}
+
+impl AbcTune {
+    /// Creates an empty tune in the given key, with a unit note length of an eighth note.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::{notes::*, notation::abc::*, scales::*, durations::*, time_signatures::*};
+    ///
+    /// let mut tune = AbcTune::new(note!("D").unwrap(), MajorMode::Dorian);
+    /// tune.set_title("Generated");
+    /// tune.set_meter(time!(4, 4).unwrap());
+    /// tune.push(AbcElement::Note(pnote!("D4").unwrap(), Duration::quarter()));
+    /// tune.push(AbcElement::Note(pnote!("F#4").unwrap(), Duration::quarter()));
+    /// tune.push(AbcElement::Note(pnote!("F4").unwrap(), Duration::half()));
+    /// tune.push(AbcElement::Bar);
+    ///
+    /// assert_eq!(tune.to_string(), "X:1\nT:Generated\nM:4/4\nL:1/8\nK:Ddor\nD2 ^F2 =F4 |\n");
+    /// ```
+    pub fn new(tonic: Note, mode: MajorMode) -> Self {
+        AbcTune {
+            reference: 1,
+            title: None,
+            meter: None,
+            unit_length: Duration::eighth(),
+            tempo: None,
+            tonic,
+            mode,
+            key: Key::from_mode(tonic, mode),
+            elements: Vec::new(),
+        }
+    }
+
+    /// Parses every tune in a collection, such as a file of folk tunes.
+    /// Each tune starts with an `X:` field; any text before the first tune is ignored.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::notation::abc::*;
+    ///
+    /// let tunes = AbcTune::parse_collection("%abc-2.1\n\nX:1\nK:D\nDEF|\n\nX:2\nK:Am\nABc|\n").unwrap();
+    /// assert_eq!(tunes.len(), 2);
+    /// assert_eq!(tunes[1].reference(), 2);
+    /// ```
+    pub fn parse_collection(s: &str) -> Result<Vec<AbcTune>> {
+        utils::split_collection(s).iter().map(|tune| tune.parse()).collect()
+    }
+
+    /// Appends an element to the body of the tune.
+    pub fn push(&mut self, element: AbcElement) {
+        self.elements.push(element);
+    }
+
+    /// Returns the pitches of every note and chord in the tune, in order.
+    pub fn notes(&self) -> Vec<PitchedNote> {
+        let mut notes = Vec::new();
+        for element in &self.elements {
+            match element {
+                AbcElement::Note(note, _) => notes.push(*note),
+                AbcElement::Chord(chord, _) => notes.extend(chord.iter().copied()),
+                _ => (),
+            }
+        }
+        notes
+    }
+
+    pub fn set_reference(&mut self, reference: u32) {
+        self.reference = reference;
+    }
+
+    pub fn set_title(&mut self, title: &str) {
+        self.title = Some(title.to_string());
+    }
+
+    pub fn set_meter(&mut self, meter: TimeSignature) {
+        self.meter = Some(meter);
+    }
+
+    pub fn set_unit_length(&mut self, unit_length: Duration) {
+        self.unit_length = unit_length;
+    }
+
+    pub fn set_tempo(&mut self, tempo: Tempo) {
+        self.tempo = Some(tempo);
+    }
+
+    /// Returns the reference number from the `X:` field.
+    pub fn reference(&self) -> u32 {
+        self.reference
+    }
+
+    /// Returns the title from the first `T:` field.
+    pub fn title(&self) -> Option<&str> {
+        self.title.as_deref()
+    }
+
+    /// Returns the meter from the `M:` field.
+    pub fn meter(&self) -> Option<&TimeSignature> {
+        self.meter.as_ref()
+    }
+
+    /// Returns the unit note length from the `L:` field.
+    pub fn unit_length(&self) -> Duration {
+        self.unit_length
+    }
+
+    /// Returns the tempo from the `Q:` field.
+    pub fn tempo(&self) -> Option<Tempo> {
+        self.tempo
+    }
+
+    /// Returns the tonic from the `K:` field.
+    pub fn tonic(&self) -> Note {
+        self.tonic
+    }
+
+    /// Returns the mode from the `K:` field.
+    pub fn mode(&self) -> MajorMode {
+        self.mode
+    }
+
+    /// Returns the key signature from the `K:` field, including any explicit accidentals.
+    pub fn key(&self) -> &Key {
+        &self.key
+    }
+
+    pub fn elements(&self) -> &Vec<AbcElement> {
+        &self.elements
+    }
+}
@@ -0,0 +1,160 @@
+#[cfg(test)]
+mod tests {
+    use super::super::*;
+    use crate::{durations::*, keys::*, time_signatures::*};
+
+    fn parse(s: &str) -> AbcTune {
+        s.parse::<AbcTune>().unwrap()
+    }
+
+    #[test]
+    fn test_key_field() {
+        let tune = parse("X:1\nK:F#m\n");
+        assert_eq!(tune.tonic(), note!("F#").unwrap());
+        assert_eq!(tune.mode(), MajorMode::Aeolian);
+        assert_eq!(tune.key(), &key!("F# C# G#").unwrap());
+
+        let tune = parse("X:1\nK:A Dorian\n");
+        assert_eq!(tune.mode(), MajorMode::Dorian);
+        assert_eq!(tune.key(), &key!("F#").unwrap());
+
+        let tune = parse("X:1\nK:Bbmix\n");
+        assert_eq!(tune.tonic(), note!("Bb").unwrap());
+        assert_eq!(tune.key(), &key!("Bb Eb Ab").unwrap());
+
+        let tune = parse("X:1\nK:D exp ^f\n");
+        assert_eq!(tune.key(), &key!("F#").unwrap());
+
+        assert!("X:1\nK:H\n".parse::<AbcTune>().is_err());
+        assert!("X:1\nK:C blues\n".parse::<AbcTune>().is_err());
+        assert!("X:1\nT:No key\n".parse::<AbcTune>().is_err());
+    }
+
+    #[test]
+    fn test_bar_scoped_accidentals() {
+        let tune = parse("X:1\nK:F\nB ^c c c' | c B =B B|\n");
+        assert_eq!(
+            tune.notes(),
+            vec![
+                pnote!("Bb4").unwrap(),
+                pnote!("C#5").unwrap(),
+                pnote!("C#5").unwrap(),
+                pnote!("C6").unwrap(),
+                pnote!("C5").unwrap(),
+                pnote!("Bb4").unwrap(),
+                pnote!("B4").unwrap(),
+                pnote!("B4").unwrap(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_octaves_and_lengths() {
+        let tune = parse("X:1\nL:1/4\nK:C\nC, c'' D2 E/ F3/2 G// z4|\n");
+        let elements = tune.elements();
+        assert_eq!(elements[0], AbcElement::Note(pnote!("C3").unwrap(), Duration::quarter()));
+        assert_eq!(elements[1], AbcElement::Note(pnote!("C7").unwrap(), Duration::quarter()));
+        assert_eq!(elements[2], AbcElement::Note(pnote!("D4").unwrap(), Duration::half()));
+        assert_eq!(elements[3], AbcElement::Note(pnote!("E4").unwrap(), Duration::eighth()));
+        assert_eq!(elements[4], AbcElement::Note(pnote!("F4").unwrap(), dur!(3, 8).unwrap()));
+        assert_eq!(elements[5], AbcElement::Note(pnote!("G4").unwrap(), Duration::sixteenth()));
+        assert_eq!(elements[6], AbcElement::Rest(Duration::whole()));
+        assert_eq!(elements[7], AbcElement::Bar);
+    }
+
+    #[test]
+    fn test_default_unit_length() {
+        assert_eq!(parse("X:1\nM:2/4\nK:C\n").unit_length(), Duration::sixteenth());
+        assert_eq!(parse("X:1\nM:6/8\nK:C\n").unit_length(), Duration::eighth());
+        assert_eq!(parse("X:1\nK:C\n").unit_length(), Duration::eighth());
+    }
+
+    #[test]
+    fn test_rhythms() {
+        let tune = parse("X:1\nL:1/8\nK:C\nA>B c<d (3efg|\n");
+        let durations: Vec<Duration> =
+            tune.elements().iter().filter_map(|element| element.duration()).collect();
+        assert_eq!(
+            durations,
+            vec![
+                dur!(3, 16).unwrap(),
+                Duration::sixteenth(),
+                Duration::sixteenth(),
+                dur!(3, 16).unwrap(),
+                dur!(1, 12).unwrap(),
+                dur!(1, 12).unwrap(),
+                dur!(1, 12).unwrap(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tuplet_defaults() {
+        let durations = |text: &str| -> Vec<Duration> {
+            parse(text).elements().iter().filter_map(|element| element.duration()).collect()
+        };
+        let quintuplet = durations("X:1\nM:4/4\nL:1/8\nK:C\n(5cdefg|\n");
+        assert_eq!(quintuplet, vec![dur!(1, 20).unwrap(); 5]);
+        let quintuplet = durations("X:1\nM:6/8\nL:1/8\nK:C\n(5cdefg|\n");
+        assert_eq!(quintuplet, vec![dur!(3, 40).unwrap(); 5]);
+        let duplet = durations("X:1\nM:6/8\nL:1/8\nK:C\n(2cd|\n");
+        assert_eq!(duplet, vec![dur!(3, 16).unwrap(); 2]);
+        let duplet = durations("X:1\nM:2/4\nL:1/8\nK:C\n(2cd|\n");
+        assert_eq!(duplet, vec![dur!(3, 16).unwrap(); 2]);
+
+        // A meter change in the body applies to later tuplets.
+        let septuplet = durations("X:1\nM:4/4\nL:1/8\nK:C\n[M:12/8](7cdefgab|\n");
+        assert_eq!(septuplet, vec![dur!(3, 56).unwrap(); 7]);
+    }
+
+    #[test]
+    fn test_chords_and_symbols() {
+        let tune = parse("X:1\nK:G\n\"G\"[GBd]2 \"^text\"\"D7\"A-A|\n");
+        assert_eq!(
+            tune.elements(),
+            &vec![
+                AbcElement::ChordSymbol("G".to_string()),
+                AbcElement::Chord(
+                    vec![pnote!("G4").unwrap(), pnote!("B4").unwrap(), pnote!("D5").unwrap()],
+                    Duration::quarter()
+                ),
+                AbcElement::ChordSymbol("D7".to_string()),
+                AbcElement::Note(pnote!("A4").unwrap(), Duration::eighth()),
+                AbcElement::Tie,
+                AbcElement::Note(pnote!("A4").unwrap(), Duration::eighth()),
+                AbcElement::Bar,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_header_fields() {
+        let tune = parse("X:7\nT:The Kesh\nT:Alternate title\nM:6/8\nL:1/8\nQ:3/8=120\nK:G\n");
+        assert_eq!(tune.reference(), 7);
+        assert_eq!(tune.title(), Some("The Kesh"));
+        assert_eq!(tune.meter(), Some(&time!(6, 8).unwrap()));
+        assert_eq!(tune.tempo(), Tempo::new(dur!(3, 8).unwrap(), 120.0).ok());
+        assert_eq!(parse("X:1\nL:1/4\nQ:96\nK:C\n").tempo(), Tempo::quarter(96.0).ok());
+        assert!("X:1\nQ:1/4=0\nK:C\n".parse::<AbcTune>().is_err());
+        assert_eq!(parse("X:1\nM:C|\nK:C\n").meter(), Some(&time!(2, 2).unwrap()));
+        assert_eq!(parse("X:1\nM:(2+2+3)/8\nK:C\n").meter().unwrap().groups(), &vec![2, 2, 3]);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let text = "X:3\nT:Round Trip\nM:3/4\nL:1/8\nK:Eb\nE2 =E2 F2 | [EGB]3 z3 | e'/ ^f/ g5 |\n";
+        let tune = parse(text);
+        assert_eq!(tune.to_string(), text);
+        assert_eq!(parse(&tune.to_string()), tune);
+
+        let text = "X:1\nL:1/8\nK:D =c ^g\nd c G g |\n";
+        let tune = parse(text);
+        assert_eq!(tune.to_string(), text);
+        assert_eq!(tune.notes()[1], pnote!("C5").unwrap());
+        assert_eq!(parse(&tune.to_string()), tune);
+
+        let tune = parse("X:1\nL:1/8\nK:G exp _b\nB F |\n");
+        assert_eq!(parse(&tune.to_string()), tune);
+        assert_eq!(tune.notes(), vec![pnote!("Bb4").unwrap(), pnote!("F4").unwrap()]);
+    }
+}
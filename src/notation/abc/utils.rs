@@ -0,0 +1,656 @@
+use super::*;
+use crate::{dur, error::NotationError, nope};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::{
+    collections::HashMap,
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+lazy_static! {
+    static ref FIELD_RE: Regex = Regex::new(r"^([A-Za-z]):\s*(.*)$").unwrap();
+    static ref KEY_RE: Regex = Regex::new(r"^([A-Ga-g])([#b]?)(.*)$").unwrap();
+    static ref FRACTION_RE: Regex = Regex::new(r"^(\d+)/(\d+)$").unwrap();
+}
+
+/// Splits a collection of tunes on its `X:` fields.
+pub(super) fn split_collection(s: &str) -> Vec<String> {
+    let mut tunes: Vec<String> = Vec::new();
+    for line in s.lines() {
+        if line.starts_with("X:") {
+            tunes.push(String::new());
+        }
+        if let Some(tune) = tunes.last_mut() {
+            tune.push_str(line);
+            tune.push('\n');
+        }
+    }
+    tunes
+}
+
+/// Parses the value of a `K:` field into a tonic, mode and key signature.
+fn parse_key_field(value: &str) -> Result<(Note, MajorMode, Key)> {
+    let invalid = || NotationError::InvalidKeySignature(value.to_string());
+    let value = value.trim();
+    if value.is_empty() || value.eq_ignore_ascii_case("none") {
+        return Ok((note!(NoteName::C), MajorMode::Ionian, Key::new(Vec::new())));
+    }
+
+    let cap = KEY_RE.captures(value).ok_or_else(invalid)?;
+    let tonic = Note::new(
+        cap[1].parse()?,
+        if &cap[2] == "#" {
+            Accidental::Sharp(1)
+        } else if &cap[2] == "b" {
+            Accidental::Flat(1)
+        } else {
+            Accidental::Natural
+        },
+    );
+
+    let mut tokens = cap[3].split_whitespace().peekable();
+    let mut explicit_only = false;
+    let mode = match tokens.peek().map(|t| t.to_ascii_lowercase()) {
+        Some(word) if !word.contains('=') && !word.starts_with(['^', '_']) => {
+            tokens.next();
+            match word.as_str() {
+                "m" => MajorMode::Aeolian,
+                _ => match word.get(..3) {
+                    Some("maj") | Some("ion") => MajorMode::Ionian,
+                    Some("min") | Some("aeo") => MajorMode::Aeolian,
+                    Some("dor") => MajorMode::Dorian,
+                    Some("phr") => MajorMode::Phrygian,
+                    Some("lyd") => MajorMode::Lydian,
+                    Some("mix") => MajorMode::Mixolydian,
+                    Some("loc") => MajorMode::Locrian,
+                    Some("exp") => {
+                        explicit_only = true;
+                        MajorMode::Ionian
+                    }
+                    _ => nope!(invalid()),
+                },
+            }
+        }
+        _ => MajorMode::Ionian,
+    };
+
+    let mut key = if explicit_only { Key::new(Vec::new()) } else { Key::from_mode(tonic, mode) };
+    for token in tokens {
+        if token.eq_ignore_ascii_case("exp") {
+            key = Key::new(Vec::new());
+        } else if token.starts_with(['^', '_', '=']) {
+            let (accidental, rest) = parse_accidental(token);
+            let name = rest.parse::<NoteName>().map_err(|_| invalid())?;
+            key.set_pitch(Note::new(name, accidental.ok_or_else(invalid)?));
+        } else if !token.contains('=') {
+            nope!(invalid());
+        }
+    }
+
+    Ok((tonic, mode, key))
+}
+
+/// Splits a leading ABC accidental (`^`, `^^`, `_`, `__` or `=`) from a string.
+fn parse_accidental(s: &str) -> (Option<Accidental>, &str) {
+    let rest = s.trim_start_matches('^');
+    let sharps = s.len() - rest.len();
+    if sharps > 0 {
+        return (Some(Accidental::Sharp(sharps as u8)), rest);
+    }
+    let rest = s.trim_start_matches('_');
+    let flats = s.len() - rest.len();
+    if flats > 0 {
+        return (Some(Accidental::Flat(flats as u8)), rest);
+    }
+    match s.strip_prefix('=') {
+        Some(rest) => (Some(Accidental::Natural), rest),
+        None => (None, s),
+    }
+}
+
+fn parse_meter(value: &str) -> Option<Option<TimeSignature>> {
+    match value.trim() {
+        "none" | "" => Some(None),
+        // Additive meters may be written with parentheses, as in (2+2+3)/8.
+        value => value.replace(['(', ')'], "").parse().ok().map(Some),
+    }
+}
+
+fn parse_fraction(value: &str) -> Option<Duration> {
+    let cap = FRACTION_RE.captures(value.trim())?;
+    Duration::new(cap[1].parse().ok()?, cap[2].parse().ok()?).ok()
+}
+
+/// Parses a `Q:` field, such as `1/4=120`. A bare number of beats per minute
+/// is returned without a beat length, which is then taken to be the unit note length.
+fn parse_tempo(value: &str) -> Option<(Option<Duration>, f64)> {
+    // Text strings such as "Allegro" may surround the tempo itself.
+    let value: String = value.split('"').step_by(2).collect::<Vec<_>>().join(" ");
+    let (beat, bpm) = match value.split_once('=') {
+        Some((beats, bpm)) => {
+            let beat = beats.split_whitespace().map(parse_fraction).sum::<Option<Duration>>()?;
+            (Some(beat), bpm)
+        }
+        None => (None, value.as_str()),
+    };
+    let bpm = bpm.trim().parse::<f64>().ok().filter(|bpm| *bpm > 0.0 && bpm.is_finite())?;
+    Some((beat, bpm))
+}
+
+/// Returns the default unit note length for a meter, as given by the ABC standard.
+fn default_unit_length(meter: &Option<TimeSignature>) -> Duration {
+    match meter {
+        Some(time) if time.measure_duration() < dur!(3, 4).unwrap() => Duration::sixteenth(),
+        _ => Duration::eighth(),
+    }
+}
+
+/// Returns the default `q` of a tuplet `(p`, as given by the ABC standard: `(5`, `(7`
+/// and `(9` are in the time of three notes in compound meters, and of two otherwise.
+fn default_tuplet_time(p: u32, meter: &Option<TimeSignature>) -> u32 {
+    match p {
+        3 | 6 => 2,
+        2 | 4 | 8 => 3,
+        _ if meter.as_ref().is_some_and(|time| time.is_compound()) => 3,
+        _ => 2,
+    }
+}
+
+/// Reads the body of a tune one character at a time, resolving accidentals
+/// and note lengths as it goes.
+struct BodyParser {
+    key: Key,
+    unit_length: Duration,
+    meter: Option<TimeSignature>,
+    line: usize,
+    accidentals: HashMap<(NoteName, i8), Accidental>,
+    elements: Vec<AbcElement>,
+    last_timed: Option<usize>,
+    broken: Option<(u32, u32)>,
+    tuplet: Option<(u32, u32, u32)>,
+}
+
+impl BodyParser {
+    fn field(&mut self, name: &str, value: &str) -> Result<()> {
+        match name {
+            "K" => self.key = parse_key_field(value)?.2,
+            "L" => {
+                self.unit_length =
+                    parse_fraction(value).ok_or(NotationError::InvalidHeader(self.line))?
+            }
+            "M" => {
+                self.meter = parse_meter(value).ok_or(NotationError::InvalidHeader(self.line))?
+            }
+            _ => (),
+        }
+        Ok(())
+    }
+
+    fn invalid(&self, token: impl ToString) -> ResonataError {
+        NotationError::InvalidToken(self.line, token.to_string()).into()
+    }
+
+    fn line(&mut self, line: &str) -> Result<()> {
+        let chars: Vec<char> = line.chars().collect();
+        let mut i = 0;
+        while i < chars.len() {
+            let c = chars[i];
+            i += 1;
+            match c {
+                ' ' | '\t' | '`' | '\\' | 'y' | ')' | ']' => (),
+                '|' | ':' => {
+                    while i < chars.len() && matches!(chars[i], '|' | ':' | ']' | '[') {
+                        i += 1;
+                    }
+                    while i < chars.len() && chars[i].is_ascii_digit() {
+                        i += 1;
+                    }
+                    self.bar();
+                }
+                '[' => match chars.get(i) {
+                    Some('|') => {
+                        i += 1;
+                        self.bar();
+                    }
+                    Some(d) if d.is_ascii_digit() => {
+                        while i < chars.len() && chars[i].is_ascii_digit() {
+                            i += 1;
+                        }
+                    }
+                    Some(f) if f.is_ascii_alphabetic() && chars.get(i + 1) == Some(&':') => {
+                        let end = find(&chars, i, ']').ok_or_else(|| self.invalid(c))?;
+                        let field: String = chars[i + 2..end].iter().collect();
+                        self.field(&f.to_string(), &field)?;
+                        i = end + 1;
+                    }
+                    _ => {
+                        let end = find(&chars, i, ']').ok_or_else(|| self.invalid(c))?;
+                        let mut notes = Vec::new();
+                        let mut length = None;
+                        let mut j = i;
+                        while j < end {
+                            match chars[j] {
+                                '^' | '_' | '=' | 'A'..='G' | 'a'..='g' => {
+                                    let (note, duration, next) = self.note(&chars, j)?;
+                                    notes.push(note);
+                                    length.get_or_insert(duration);
+                                    j = next;
+                                }
+                                '-' | ' ' | '.' | '~' => j += 1,
+                                other => return Err(self.invalid(other)),
+                            }
+                        }
+                        let (n, d, next) = parse_length(&chars, end + 1);
+                        let duration = length.unwrap_or(self.unit_length).scaled(n, d)?;
+                        self.timed(AbcElement::Chord(notes, duration))?;
+                        i = next;
+                    }
+                },
+                '"' => {
+                    let end = find(&chars, i, '"').ok_or_else(|| self.invalid(c))?;
+                    let text: String = chars[i..end].iter().collect();
+                    // Annotations start with a placement character rather than a chord root.
+                    if !text.starts_with(['^', '_', '<', '>', '@']) {
+                        self.elements.push(AbcElement::ChordSymbol(text));
+                    }
+                    i = end + 1;
+                }
+                '!' | '+' | '{' => {
+                    let close = if c == '{' { '}' } else { c };
+                    i = find(&chars, i, close).ok_or_else(|| self.invalid(c))? + 1;
+                }
+                '~' | '.' | 'H' | 'L' | 'M' | 'O' | 'P' | 'S' | 'T' | 'u' | 'v' => (),
+                '-' => self.elements.push(AbcElement::Tie),
+                '(' => {
+                    if chars.get(i).is_some_and(|c| c.is_ascii_digit()) {
+                        let (p, next) = parse_number(&chars, i);
+                        i = next;
+                        let mut q = default_tuplet_time(p, &self.meter);
+                        let mut r = p;
+                        if chars.get(i) == Some(&':') {
+                            let (n, next) = parse_number(&chars, i + 1);
+                            q = if next > i + 1 { n } else { q };
+                            i = next;
+                            if chars.get(i) == Some(&':') {
+                                let (n, next) = parse_number(&chars, i + 1);
+                                r = if next > i + 1 { n } else { r };
+                                i = next;
+                            }
+                        }
+                        self.tuplet = Some((p, q, r));
+                    }
+                }
+                '>' | '<' => {
+                    let mut count = 1;
+                    while chars.get(i) == Some(&c) {
+                        count += 1;
+                        i += 1;
+                    }
+                    let dot = 1 << count;
+                    let (longer, shorter) = ((2 * dot - 1, dot), (1, dot));
+                    let (previous, next) =
+                        if c == '>' { (longer, shorter) } else { (shorter, longer) };
+                    let index = self.last_timed.ok_or_else(|| self.invalid(c))?;
+                    rescale(&mut self.elements[index], previous)?;
+                    self.broken = Some(next);
+                }
+                '^' | '_' | '=' | 'A'..='G' | 'a'..='g' => {
+                    let (note, duration, next) = self.note(&chars, i - 1)?;
+                    self.timed(AbcElement::Note(note, duration))?;
+                    i = next;
+                }
+                'z' | 'x' => {
+                    let (n, d, next) = parse_length(&chars, i);
+                    self.timed(AbcElement::Rest(self.unit_length.scaled(n, d)?))?;
+                    i = next;
+                }
+                'Z' | 'X' => {
+                    let (bars, next) = parse_number(&chars, i);
+                    let bars = if next > i { bars } else { 1 };
+                    let bar =
+                        self.meter.as_ref().ok_or_else(|| self.invalid(c))?.measure_duration();
+                    self.timed(AbcElement::Rest(bar.scaled(bars, 1)?))?;
+                    i = next;
+                }
+                other => return Err(self.invalid(other)),
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads a note starting at the given index, returning its resolved pitch,
+    /// its duration and the index after it.
+    fn note(&mut self, chars: &[char], start: usize) -> Result<(PitchedNote, Duration, usize)> {
+        let mut i = start;
+        while matches!(chars.get(i), Some('^' | '_' | '=')) {
+            i += 1;
+        }
+        let prefix: String = chars[start..i].iter().collect();
+        let (explicit, rest) = parse_accidental(&prefix);
+        if !rest.is_empty() {
+            return Err(self.invalid(prefix));
+        }
+
+        let letter = *chars.get(i).ok_or_else(|| self.invalid(&prefix))?;
+        let name: NoteName = letter.to_string().parse().map_err(|_| self.invalid(letter))?;
+        let mut octave: i8 = if letter.is_ascii_uppercase() { 4 } else { 5 };
+        i += 1;
+        while let Some(mark @ ('\'' | ',')) = chars.get(i) {
+            octave += if *mark == '\'' { 1 } else { -1 };
+            i += 1;
+        }
+
+        let accidental = match explicit {
+            Some(accidental) => {
+                self.accidentals.insert((name, octave), accidental);
+                accidental
+            }
+            None => match self.accidentals.get(&(name, octave)) {
+                Some(accidental) => *accidental,
+                None => self.key.pitch(name).accidental(),
+            },
+        };
+
+        let (n, d, next) = parse_length(chars, i);
+        let pitch = PitchedNote::new(name, accidental, octave)?;
+        Ok((pitch, self.unit_length.scaled(n, d)?, next))
+    }
+
+    /// Pushes a note, chord or rest, applying any pending tuplet or broken rhythm.
+    fn timed(&mut self, mut element: AbcElement) -> Result<()> {
+        if let Some(factor) = self.broken.take() {
+            rescale(&mut element, factor)?;
+        }
+        if let Some((p, q, r)) = self.tuplet {
+            rescale(&mut element, (q, p))?;
+            self.tuplet = if r > 1 { Some((p, q, r - 1)) } else { None };
+        }
+        self.last_timed = Some(self.elements.len());
+        self.elements.push(element);
+        Ok(())
+    }
+
+    fn bar(&mut self) {
+        self.accidentals.clear();
+        if self.elements.last() != Some(&AbcElement::Bar) {
+            self.elements.push(AbcElement::Bar);
+        }
+    }
+}
+
+fn find(chars: &[char], start: usize, target: char) -> Option<usize> {
+    chars[start.min(chars.len())..].iter().position(|c| *c == target).map(|p| p + start)
+}
+
+fn parse_number(chars: &[char], start: usize) -> (u32, usize) {
+    let mut i = start;
+    let mut n = 0u32;
+    while let Some(d) = chars.get(i).and_then(|c| c.to_digit(10)) {
+        n = n.saturating_mul(10).saturating_add(d);
+        i += 1;
+    }
+    (n, i)
+}
+
+/// Parses a length multiplier such as `3`, `/`, `//`, `/4` or `3/2`.
+fn parse_length(chars: &[char], start: usize) -> (u32, u32, usize) {
+    let (n, mut i) = parse_number(chars, start);
+    let n = if i > start { n } else { 1 };
+    let mut d = 1;
+    while chars.get(i) == Some(&'/') {
+        let (divisor, next) = parse_number(chars, i + 1);
+        d *= if next > i + 1 { divisor.max(1) } else { 2 };
+        i = next;
+    }
+    (n, d, i)
+}
+
+fn rescale(element: &mut AbcElement, (n, d): (u32, u32)) -> Result<()> {
+    match element {
+        AbcElement::Note(_, duration)
+        | AbcElement::Chord(_, duration)
+        | AbcElement::Rest(duration) => *duration = duration.scaled(n, d)?,
+        _ => (),
+    }
+    Ok(())
+}
+
+impl FromStr for AbcTune {
+    type Err = ResonataError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let mut reference = 1;
+        let mut title = None;
+        let mut meter = None;
+        let mut unit_length = None;
+        let mut tempo = None;
+        let mut body: Option<(AbcTune, BodyParser)> = None;
+
+        for (index, line) in s.lines().enumerate() {
+            let line = match line.find('%') {
+                Some(comment) => &line[..comment],
+                None => line,
+            };
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let field = FIELD_RE.captures(line);
+            match &mut body {
+                Some((_, parser)) => {
+                    parser.line = index + 1;
+                    match field {
+                        Some(cap) => parser.field(&cap[1], &cap[2])?,
+                        None => parser.line(line)?,
+                    }
+                }
+                None => {
+                    let cap = field.ok_or(NotationError::InvalidHeader(index + 1))?;
+                    let value = cap[2].trim();
+                    match &cap[1] {
+                        "X" => {
+                            reference = value
+                                .parse()
+                                .map_err(|_| NotationError::InvalidHeader(index + 1))?
+                        }
+                        "T" => {
+                            title.get_or_insert(value.to_string());
+                        }
+                        "M" => {
+                            meter =
+                                parse_meter(value).ok_or(NotationError::InvalidHeader(index + 1))?
+                        }
+                        "L" => {
+                            unit_length = Some(
+                                parse_fraction(value)
+                                    .ok_or(NotationError::InvalidHeader(index + 1))?,
+                            )
+                        }
+                        "Q" => {
+                            tempo = Some(
+                                parse_tempo(value)
+                                    .ok_or(NotationError::InvalidHeader(index + 1))?,
+                            )
+                        }
+                        "K" => {
+                            let (tonic, mode, key) = parse_key_field(value)?;
+                            let unit_length = unit_length.unwrap_or(default_unit_length(&meter));
+                            let tune = AbcTune {
+                                reference,
+                                title: title.take(),
+                                meter: meter.clone(),
+                                unit_length,
+                                tempo: tempo
+                                    .map(|(beat, bpm)| Tempo::new(beat.unwrap_or(unit_length), bpm))
+                                    .transpose()?,
+                                tonic,
+                                mode,
+                                key: key.clone(),
+                                elements: Vec::new(),
+                            };
+                            let parser = BodyParser {
+                                key,
+                                unit_length,
+                                meter: meter.clone(),
+                                line: index + 1,
+                                accidentals: HashMap::new(),
+                                elements: Vec::new(),
+                                last_timed: None,
+                                broken: None,
+                                tuplet: None,
+                            };
+                            body = Some((tune, parser));
+                        }
+                        _ => (),
+                    }
+                }
+            }
+        }
+
+        match body {
+            Some((mut tune, parser)) => {
+                tune.elements = parser.elements;
+                Ok(tune)
+            }
+            None => nope!(NotationError::MissingKeySignature),
+        }
+    }
+}
+
+/// Writes an accidental in the form used by the `K:` field, e.g. `#` or `b`.
+fn key_accidental(accidental: Accidental) -> String {
+    match accidental {
+        Accidental::Flat(n) => "b".repeat(n as usize),
+        Accidental::Natural => String::new(),
+        Accidental::Sharp(n) => "#".repeat(n as usize),
+    }
+}
+
+/// Writes an accidental in the form used before a note, e.g. `^` or `=`.
+fn note_accidental(accidental: Accidental) -> String {
+    match accidental {
+        Accidental::Flat(n) => "_".repeat(n as usize),
+        Accidental::Natural => "=".to_string(),
+        Accidental::Sharp(n) => "^".repeat(n as usize),
+    }
+}
+
+fn write_length(duration: Duration, unit_length: Duration) -> String {
+    match duration.ratio_to(&unit_length).unwrap() {
+        (1, 1) => String::new(),
+        (n, 1) => n.to_string(),
+        (1, 2) => "/".to_string(),
+        (1, d) => format!("/{}", d),
+        (n, d) => format!("{}/{}", n, d),
+    }
+}
+
+impl AbcTune {
+    /// Writes a pitch, adding an accidental only where the key signature and the
+    /// accidentals earlier in the bar would not already give the right one.
+    fn write_pitch(
+        &self,
+        note: &PitchedNote,
+        accidentals: &mut HashMap<(NoteName, i8), Accidental>,
+    ) -> String {
+        let mut token = String::new();
+        let current = match accidentals.get(&(note.name(), note.octave())) {
+            Some(accidental) => *accidental,
+            None => self.key.pitch(note.name()).accidental(),
+        };
+        if current != note.accidental() {
+            token.push_str(&note_accidental(note.accidental()));
+            accidentals.insert((note.name(), note.octave()), note.accidental());
+        }
+
+        let name = note.name().to_string();
+        if note.octave() >= 5 {
+            token.push_str(&name.to_lowercase());
+            token.push_str(&"'".repeat((note.octave() - 5) as usize));
+        } else {
+            token.push_str(&name);
+            token.push_str(&",".repeat((4 - note.octave()) as usize));
+        }
+        token
+    }
+}
+
+impl Display for AbcTune {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "X:{}", self.reference)?;
+        if let Some(title) = &self.title {
+            writeln!(f, "T:{}", title)?;
+        }
+        if let Some(meter) = &self.meter {
+            writeln!(f, "M:{}", meter)?;
+        }
+        writeln!(f, "L:{}", self.unit_length)?;
+        if let Some(tempo) = self.tempo {
+            writeln!(f, "Q:{}", tempo)?;
+        }
+        let mode = match self.mode {
+            MajorMode::Ionian => "",
+            MajorMode::Dorian => "dor",
+            MajorMode::Phrygian => "phr",
+            MajorMode::Lydian => "lyd",
+            MajorMode::Mixolydian => "mix",
+            MajorMode::Aeolian => "m",
+            MajorMode::Locrian => "loc",
+        };
+        write!(f, "K:{}{}{}", self.tonic.name(), key_accidental(self.tonic.accidental()), mode)?;
+        // Pitches the key signature changes from the mode are written after it.
+        let modal = Key::from_mode(self.tonic, self.mode);
+        for (note, default) in self.key.pitches().iter().zip(modal.pitches()) {
+            if *note != default {
+                let name = note.name().to_string().to_lowercase();
+                write!(f, " {}{}", note_accidental(note.accidental()), name)?;
+            }
+        }
+        writeln!(f)?;
+
+        let mut accidentals = HashMap::new();
+        let mut bars = 0;
+        let mut line_start = true;
+        for element in &self.elements {
+            let token = match element {
+                AbcElement::Note(note, duration) => {
+                    let pitch = self.write_pitch(note, &mut accidentals);
+                    format!("{}{}", pitch, write_length(*duration, self.unit_length))
+                }
+                AbcElement::Chord(notes, duration) => {
+                    let pitches: String =
+                        notes.iter().map(|note| self.write_pitch(note, &mut accidentals)).collect();
+                    format!("[{}]{}", pitches, write_length(*duration, self.unit_length))
+                }
+                AbcElement::Rest(duration) => {
+                    format!("z{}", write_length(*duration, self.unit_length))
+                }
+                AbcElement::ChordSymbol(symbol) => format!("\"{}\"", symbol),
+                AbcElement::Tie => {
+                    write!(f, "-")?;
+                    continue;
+                }
+                AbcElement::Bar => {
+                    accidentals.clear();
+                    bars += 1;
+                    write!(f, " |")?;
+                    if bars % 4 == 0 {
+                        writeln!(f)?;
+                        line_start = true;
+                    }
+                    continue;
+                }
+            };
+            if !line_start {
+                write!(f, " ")?;
+            }
+            write!(f, "{}", token)?;
+            line_start = false;
+        }
+        if !line_start {
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
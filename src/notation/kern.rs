@@ -0,0 +1,151 @@
+use crate::{
+    durations::{Duration, Tempo},
+    error::ResonataError,
+    keys::Key,
+    notes::*,
+    scales::MajorMode,
+    time_signatures::TimeSignature,
+};
+
+mod tests;
+mod utils;
+
+type Result<T> = std::result::Result<T, ResonataError>;
+
+/// The role of a note in a chain of tied notes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tie {
+    Start,
+    Continue,
+    End,
+}
+
+/// A single element of a `**kern` spine.
+///
+/// Interpretations are kept in the stream at the point where they occur,
+/// so a key or meter change can be matched to the notes that follow it.
+#[derive(Debug, Clone, PartialEq)]
+pub enum KernElement {
+    Note(PitchedNote, Duration, Option<Tie>),
+    Chord(Vec<PitchedNote>, Duration, Option<Tie>),
+    Rest(Duration),
+    Bar(Option<u32>),
+    KeySignature(Key),
+    KeyDesignation(Note, MajorMode),
+    Meter(TimeSignature),
+    Tempo(Tempo),
+}
+
+impl KernElement {
+    /// Returns the duration of the element, if it takes up time.
+    pub fn duration(&self) -> Option<Duration> {
+        match self {
+            KernElement::Note(_, duration, _)
+            | KernElement::Chord(_, duration, _)
+            | KernElement::Rest(duration) => Some(*duration),
+            _ => None,
+        }
+    }
+}
+
+/// A voice read from a single `**kern` spine.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct KernVoice {
+    elements: Vec<KernElement>,
+}
+
+impl KernVoice {
+    /// Returns the elements of the voice, in order.
+    pub fn elements(&self) -> &Vec<KernElement> {
+        &self.elements
+    }
+
+    /// Returns the notes of the voice with their durations. Tied notes are joined into
+    /// a single note, and the notes of a chord are each given the chord's duration.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::{notes::*, durations::*, notation::kern::*};
+    ///
+    /// let score: KernScore = "**kern\n*M3/4\n2.c[\n=2\n4c]\n4d\n*-\n".parse().unwrap();
+    /// assert_eq!(score.voices()[0].timed_notes(), vec![
+    ///     (pnote!("C4").unwrap(), Duration::whole()),
+    ///     (pnote!("D4").unwrap(), Duration::quarter()),
+    /// ]);
+    /// ```
+    pub fn timed_notes(&self) -> Vec<(PitchedNote, Duration)> {
+        let mut notes: Vec<(PitchedNote, Duration)> = Vec::new();
+        let mut tied = false;
+        for element in &self.elements {
+            match element {
+                KernElement::Note(note, duration, tie) => {
+                    match (tie, notes.last_mut()) {
+                        (Some(Tie::Continue | Tie::End), Some(last)) if tied && last.0 == *note => {
+                            last.1 += *duration
+                        }
+                        _ => notes.push((*note, *duration)),
+                    }
+                    tied = matches!(tie, Some(Tie::Start | Tie::Continue));
+                }
+                KernElement::Chord(chord, duration, _) => {
+                    notes.extend(chord.iter().map(|note| (*note, *duration)));
+                    tied = false;
+                }
+                _ => (),
+            }
+        }
+        notes
+    }
+
+    /// Returns the pitches of the voice, with tied notes counted once.
+    pub fn notes(&self) -> Vec<PitchedNote> {
+        self.timed_notes().into_iter().map(|(note, _)| note).collect()
+    }
+
+    /// Returns the first key signature in the voice.
+    pub fn key(&self) -> Option<&Key> {
+        self.elements.iter().find_map(|element| match element {
+            KernElement::KeySignature(key) => Some(key),
+            _ => None,
+        })
+    }
+
+    /// Returns the first meter in the voice.
+    pub fn meter(&self) -> Option<&TimeSignature> {
+        self.elements.iter().find_map(|element| match element {
+            KernElement::Meter(meter) => Some(meter),
+            _ => None,
+        })
+    }
+}
+
+/// A score in Humdrum `**kern` format.
+///
+/// Each `**kern` spine is read into a voice, in order from left to right, which by
+/// Humdrum convention is from the lowest voice to the highest. Spines of other
+/// representations, such as `**dynam` or `**text`, are skipped. When a spine splits
+/// with `*^`, the new sub-spine is appended as a voice after the existing ones, starting
+/// with the key signature, key, meter and tempo of the spine it split from.
+///
+/// ### Examples
+/// ```
+/// use resonata::{notes::*, keys::*, durations::*, notation::kern::*, time_signatures::*};
+///
+/// let text = "**kern\t**kern\n*k[f#]\t*k[f#]\n*M3/4\t*M3/4\n4G\t4d\n4A\t4f#\n=1\t=1\n*-\t*-\n";
+/// let score: KernScore = text.parse().unwrap();
+/// assert_eq!(score.voices().len(), 2);
+/// assert_eq!(score.voices()[0].key(), Some(&key!("F#").unwrap()));
+/// assert_eq!(score.voices()[1].meter(), Some(&time!(3, 4).unwrap()));
+/// assert_eq!(score.voices()[1].notes(), vec![pnote!("D4").unwrap(), pnote!("F#4").unwrap()]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct KernScore {
+    voices: Vec<KernVoice>,
+}
+
+impl KernScore {
+    /// Returns the voices of the score.
+    pub fn voices(&self) -> &Vec<KernVoice> {
+        &self.voices
+    }
+}
@@ -0,0 +1,142 @@
+#[cfg(test)]
+mod tests {
+    use super::super::*;
+    use crate::{durations::*, keys::*, time_signatures::*};
+
+    // The opening of Bach's chorale "Aus meines Herzens Grunde" (BWV 269).
+    const CHORALE: &str = "!!!COM: Bach, Johann Sebastian
+**kern\t**kern\t**kern\t**kern\t**dynam
+*ICvox\t*ICvox\t*ICvox\t*ICvox\t*
+*clefF4\t*clefGv2\t*clefG2\t*clefG2\t*
+*k[f#]\t*k[f#]\t*k[f#]\t*k[f#]\t*
+*G:\t*G:\t*G:\t*G:\t*
+*M3/4\t*M3/4\t*M3/4\t*M3/4\t*
+*MM72\t*MM72\t*MM72\t*MM72\t*
+4GG\t4B\t4d\t4g\tp
+=1\t=1\t=1\t=1\t=1
+4G\t4B\t4d\t4g\t.
+4E\t8cL\t4e\t4g\t.
+.\t8BJ\t.\t.\t.
+8F#L\t4A\t4d\t4a\t.
+8DJ\t.\t.\t.\t.
+=2\t=2\t=2\t=2\t=2
+*-\t*-\t*-\t*-\t*-
+";
+
+    #[test]
+    fn test_chorale() {
+        let score: KernScore = CHORALE.parse().unwrap();
+        assert_eq!(score.voices().len(), 4);
+
+        let bass = &score.voices()[0];
+        assert_eq!(bass.key(), Some(&key!("F#").unwrap()));
+        assert_eq!(bass.meter(), Some(&time!(3, 4).unwrap()));
+        assert!(bass.elements().contains(&KernElement::Tempo(Tempo::quarter(72.0).unwrap())));
+        assert!(bass
+            .elements()
+            .contains(&KernElement::KeyDesignation(note!("G").unwrap(), MajorMode::Ionian)));
+        assert_eq!(
+            bass.timed_notes(),
+            vec![
+                (pnote!("G2").unwrap(), Duration::quarter()),
+                (pnote!("G3").unwrap(), Duration::quarter()),
+                (pnote!("E3").unwrap(), Duration::quarter()),
+                (pnote!("F#3").unwrap(), Duration::eighth()),
+                (pnote!("D3").unwrap(), Duration::eighth()),
+            ]
+        );
+
+        let tenor = &score.voices()[1];
+        assert_eq!(
+            tenor.notes(),
+            vec![
+                pnote!("B3").unwrap(),
+                pnote!("B3").unwrap(),
+                pnote!("C4").unwrap(),
+                pnote!("B3").unwrap(),
+                pnote!("A3").unwrap(),
+            ]
+        );
+        assert_eq!(tenor.elements()[5], KernElement::Bar(Some(1)));
+
+        let soprano = &score.voices()[3];
+        assert_eq!(soprano.notes()[3], pnote!("A4").unwrap());
+    }
+
+    #[test]
+    fn test_durations() {
+        let score: KernScore = "**kern\n4.c\n16..d\n0e\n3f\n3%2g\nqa\n8r\n*-\n".parse().unwrap();
+        let durations: Vec<Duration> =
+            score.voices()[0].elements().iter().filter_map(|element| element.duration()).collect();
+        assert_eq!(
+            durations,
+            vec![
+                dur!(3, 8).unwrap(),
+                dur!(7, 64).unwrap(),
+                dur!(2, 1).unwrap(),
+                dur!(1, 3).unwrap(),
+                dur!(2, 3).unwrap(),
+                Duration::zero(),
+                Duration::eighth(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_pitches_and_ties() {
+        let score: KernScore =
+            "**kern\n4cc#[\n4cc#_\n4cc#]\n4BB-\n4en\n4C E G\n*-\n".parse().unwrap();
+        let voice = &score.voices()[0];
+        assert_eq!(
+            voice.elements()[0],
+            KernElement::Note(pnote!("C#5").unwrap(), Duration::quarter(), Some(Tie::Start))
+        );
+        assert_eq!(
+            voice.timed_notes(),
+            vec![
+                (pnote!("C#5").unwrap(), dur!(3, 4).unwrap()),
+                (pnote!("Bb2").unwrap(), Duration::quarter()),
+                (pnote!("E4").unwrap(), Duration::quarter()),
+                (pnote!("C3").unwrap(), Duration::quarter()),
+                (pnote!("E3").unwrap(), Duration::quarter()),
+                (pnote!("G3").unwrap(), Duration::quarter()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_spine_split_and_join() {
+        let text = "**kern\t**kern\n4c\t4g\n*^\t*\n4c\t4e\t4g\n*v\t*v\t*\n4c\t4g\n*-\t*-\n";
+        let score: KernScore = text.parse().unwrap();
+        assert_eq!(score.voices().len(), 3);
+        assert_eq!(score.voices()[0].notes().len(), 3);
+        assert_eq!(score.voices()[1].notes().len(), 3);
+        assert_eq!(score.voices()[2].notes(), vec![pnote!("E4").unwrap()]);
+
+        // The new spine starts with the key signature and meter in effect where it split.
+        let text = "**kern\n*k[f#]\n*M4/4\n1g\n*M3/4\n*MM80\n*^\n2.g\t4f#\n*-\t*-\n";
+        let score: KernScore = text.parse().unwrap();
+        let split = &score.voices()[1];
+        assert_eq!(split.key(), Some(&key!("F#").unwrap()));
+        assert_eq!(split.meter(), Some(&time!(3, 4).unwrap()));
+        assert_eq!(
+            split.elements(),
+            &vec![
+                KernElement::KeySignature(key!("F#").unwrap()),
+                KernElement::Meter(time!(3, 4).unwrap()),
+                KernElement::Tempo(Tempo::quarter(80.0).unwrap()),
+                KernElement::Note(pnote!("F#4").unwrap(), Duration::quarter(), None),
+            ]
+        );
+        assert_eq!(score.voices()[0].meter(), Some(&time!(4, 4).unwrap()));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!("4c\n".parse::<KernScore>().is_err());
+        assert!("**kern\t**kern\n4c\n*-\t*-\n".parse::<KernScore>().is_err());
+        assert!("**kern\n4h\n*-\n".parse::<KernScore>().is_err());
+        assert!("**kern\ncd\n*-\n".parse::<KernScore>().is_err());
+        assert!("**kern\n*MM0\n4c\n*-\n".parse::<KernScore>().is_err());
+    }
+}
@@ -0,0 +1,275 @@
+use super::*;
+use crate::{
+    error::{NotationError, NoteError},
+    nope,
+};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::str::FromStr;
+
+lazy_static! {
+    static ref KEY_SIGNATURE_RE: Regex = Regex::new(r"^\*k\[((?:[a-g][#-]*)*)\]$").unwrap();
+    static ref KEY_DESIGNATION_RE: Regex =
+        Regex::new(r"^\*([A-Ga-g])([#-]?):(ion|dor|phr|lyd|mix|aeo|loc)?$").unwrap();
+    static ref METER_RE: Regex = Regex::new(r"^\*M(\d+(?:\+\d+)*/\d+)$").unwrap();
+    static ref TEMPO_RE: Regex = Regex::new(r"^\*MM(\d+(?:\.\d+)?)$").unwrap();
+    static ref BAR_RE: Regex = Regex::new(r"^=+(\d*)").unwrap();
+}
+
+fn parse_accidental(s: &str) -> Accidental {
+    let sharps = s.matches('#').count() as i32;
+    let flats = s.matches('-').count() as i32;
+    Accidental::from_semitones(sharps - flats)
+}
+
+/// Parses an interpretation such as `*k[f#c#]`, `*G:`, `*M3/4` or `*MM96`, returning
+/// `None` for interpretations that have no meaning here, such as clefs.
+fn parse_interpretation(token: &str, line: usize) -> Result<Option<KernElement>> {
+    if let Some(cap) = KEY_SIGNATURE_RE.captures(token) {
+        let mut notes = Vec::new();
+        let mut chars = cap[1].chars().peekable();
+        while let Some(letter) = chars.next() {
+            let mut accidental = String::new();
+            while let Some(c) = chars.next_if(|c| matches!(c, '#' | '-')) {
+                accidental.push(c);
+            }
+            notes.push(Note::new(letter.to_string().parse()?, parse_accidental(&accidental)));
+        }
+        return Ok(Some(KernElement::KeySignature(Key::new(notes))));
+    }
+
+    if let Some(cap) = KEY_DESIGNATION_RE.captures(token) {
+        let tonic = Note::new(cap[1].parse()?, parse_accidental(&cap[2]));
+        let minor = cap[1].chars().all(|c| c.is_ascii_lowercase());
+        let mode = match cap.get(3).map(|m| m.as_str()) {
+            Some("ion") => MajorMode::Ionian,
+            Some("dor") => MajorMode::Dorian,
+            Some("phr") => MajorMode::Phrygian,
+            Some("lyd") => MajorMode::Lydian,
+            Some("mix") => MajorMode::Mixolydian,
+            Some("aeo") => MajorMode::Aeolian,
+            Some("loc") => MajorMode::Locrian,
+            _ if minor => MajorMode::Aeolian,
+            _ => MajorMode::Ionian,
+        };
+        return Ok(Some(KernElement::KeyDesignation(tonic, mode)));
+    }
+
+    if let Some(cap) = METER_RE.captures(token) {
+        let meter =
+            cap[1].parse().map_err(|_| NotationError::InvalidToken(line, token.to_string()))?;
+        return Ok(Some(KernElement::Meter(meter)));
+    }
+
+    if let Some(cap) = TEMPO_RE.captures(token) {
+        let invalid = || NotationError::InvalidToken(line, token.to_string());
+        let bpm = cap[1].parse().map_err(|_| invalid())?;
+        return Ok(Some(KernElement::Tempo(Tempo::quarter(bpm).map_err(|_| invalid())?)));
+    }
+
+    Ok(None)
+}
+
+/// Parses a `**kern` duration, such as `4`, `8.`, `0` for a breve or `3%2` for a
+/// rational duration.
+fn parse_duration(recip: &str) -> Option<Duration> {
+    let dots = recip.matches('.').count() as u32;
+    let recip = recip.trim_end_matches('.');
+    let base = match recip.split_once('%') {
+        Some((n, d)) => Duration::new(d.parse().ok()?, n.parse().ok()?).ok()?,
+        None => match recip {
+            "000" => Duration::new(8, 1).ok()?,
+            "00" => Duration::new(4, 1).ok()?,
+            "0" => Duration::new(2, 1).ok()?,
+            n => Duration::new(1, n.parse().ok()?).ok()?,
+        },
+    };
+    // Each dot adds half the value of the one before it.
+    base.scaled((1 << (dots + 1)) - 1, 1 << dots).ok()
+}
+
+/// Parses a `**kern` pitch such as `c` (C4), `cc` (C5), `C` (C3) or `BB-` (Bb2).
+fn parse_pitch(letters: &str, accidental: &str) -> Result<PitchedNote> {
+    let first = letters.chars().next().ok_or(NoteError::InvalidNote)?;
+    let count = letters.len() as i8;
+    let octave = if first.is_ascii_lowercase() { 3 + count } else { 4 - count };
+    let accidental =
+        if accidental == "n" { Accidental::Natural } else { parse_accidental(accidental) };
+    PitchedNote::new(first.to_string().parse()?, accidental, octave)
+}
+
+/// Parses a data token, which is a rest, a note or a chord of space-separated notes.
+fn parse_data(token: &str) -> Option<KernElement> {
+    let mut pitches = Vec::new();
+    let mut durations = Vec::new();
+    let mut rest = false;
+    let mut grace = false;
+    let mut tie = None;
+
+    for subtoken in token.split(' ') {
+        let mut recip = String::new();
+        let mut letters = String::new();
+        let mut accidental = String::new();
+        for c in subtoken.chars() {
+            match c {
+                '0'..='9' | '.' | '%' => recip.push(c),
+                'a'..='g' | 'A'..='G' => {
+                    if letters.chars().next().is_some_and(|first| first != c) {
+                        return None;
+                    }
+                    letters.push(c)
+                }
+                '#' | '-' | 'n' => accidental.push(c),
+                'r' => rest = true,
+                'q' | 'Q' => grace = true,
+                '[' => tie = Some(Tie::Start),
+                '_' => tie = Some(Tie::Continue),
+                ']' => tie = Some(Tie::End),
+                _ => (),
+            }
+        }
+        if !recip.is_empty() {
+            durations.push(parse_duration(&recip)?);
+        }
+        if !letters.is_empty() {
+            pitches.push(parse_pitch(&letters, &accidental).ok()?);
+        }
+    }
+
+    let duration = match durations.first() {
+        Some(duration) => *duration,
+        None if grace => Duration::zero(),
+        None => return None,
+    };
+    match (rest, pitches.len()) {
+        (true, _) => Some(KernElement::Rest(duration)),
+        (false, 0) => None,
+        (false, 1) => Some(KernElement::Note(pitches[0], duration, tie)),
+        (false, _) => Some(KernElement::Chord(pitches, duration, tie)),
+    }
+}
+
+/// Returns the latest key signature, key, meter and tempo of a voice, in the order they
+/// were set, so that a spine split from it starts with them.
+fn current_interpretations(voice: &KernVoice) -> Vec<KernElement> {
+    let mut current: Vec<KernElement> = Vec::new();
+    for element in &voice.elements {
+        if let KernElement::KeySignature(_)
+        | KernElement::KeyDesignation(..)
+        | KernElement::Meter(_)
+        | KernElement::Tempo(_) = element
+        {
+            let kind = std::mem::discriminant(element);
+            current.retain(|other| std::mem::discriminant(other) != kind);
+            current.push(element.clone());
+        }
+    }
+    current
+}
+
+impl FromStr for KernScore {
+    type Err = ResonataError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let mut voices: Vec<KernVoice> = Vec::new();
+        // The voice read from each spine, or `None` for spines that are not `**kern`.
+        let mut spines: Option<Vec<Option<usize>>> = None;
+
+        for (index, line) in s.lines().enumerate() {
+            let line_number = index + 1;
+            let invalid = |token: &str| NotationError::InvalidToken(line_number, token.to_string());
+            if line.is_empty() || line.starts_with("!!") {
+                continue;
+            }
+            let tokens: Vec<&str> = line.split('\t').collect();
+
+            let columns = match &mut spines {
+                Some(columns) => columns,
+                None => {
+                    if !line.starts_with("**") {
+                        continue;
+                    }
+                    let mut columns = Vec::new();
+                    for token in &tokens {
+                        if *token == "**kern" {
+                            columns.push(Some(voices.len()));
+                            voices.push(KernVoice::default());
+                        } else {
+                            columns.push(None);
+                        }
+                    }
+                    spines = Some(columns);
+                    continue;
+                }
+            };
+
+            if tokens.len() != columns.len() {
+                nope!(NotationError::SpineMismatch(line_number));
+            }
+
+            if line.starts_with('!') {
+                continue;
+            }
+
+            if line.starts_with('*') {
+                let mut next = Vec::new();
+                let mut i = 0;
+                while i < tokens.len() {
+                    let column = columns[i];
+                    match tokens[i] {
+                        "*^" => {
+                            next.push(column);
+                            next.push(column.map(|parent| {
+                                let elements = current_interpretations(&voices[parent]);
+                                voices.push(KernVoice { elements });
+                                voices.len() - 1
+                            }));
+                        }
+                        "*v" => {
+                            next.push(column);
+                            while tokens.get(i + 1) == Some(&"*v") {
+                                i += 1;
+                            }
+                        }
+                        "*x" if tokens.get(i + 1) == Some(&"*x") => {
+                            next.push(columns[i + 1]);
+                            next.push(column);
+                            i += 1;
+                        }
+                        "*-" => (),
+                        "*+" | "*x" => nope!(invalid(tokens[i])),
+                        token => {
+                            next.push(column);
+                            if let Some(voice) = column {
+                                if let Some(element) = parse_interpretation(token, line_number)? {
+                                    voices[voice].elements.push(element);
+                                }
+                            }
+                        }
+                    }
+                    i += 1;
+                }
+                *columns = next;
+                continue;
+            }
+
+            for (token, column) in tokens.iter().zip(columns.iter()) {
+                let Some(voice) = column else { continue };
+                let voice = &mut voices[*voice];
+                if *token == "." {
+                    continue;
+                }
+                if let Some(cap) = BAR_RE.captures(token) {
+                    voice.elements.push(KernElement::Bar(cap[1].parse().ok()));
+                    continue;
+                }
+                voice.elements.push(parse_data(token).ok_or_else(|| invalid(token))?);
+            }
+        }
+
+        if spines.is_none() {
+            nope!(NotationError::MissingExclusiveInterpretation);
+        }
+        Ok(KernScore { voices })
+    }
+}
@@ -0,0 +1,290 @@
+use crate::{
+    durations::{Duration, Event},
+    error::{FretboardError, ResonataError},
+    fretboards::{Fretboard, Position, Tuning, Voicing},
+    melodies::Melody,
+    nope,
+    notes::*,
+};
+
+mod tests;
+mod utils;
+
+type Result<T> = std::result::Result<T, ResonataError>;
+
+/// A single column of guitar tablature.
+///
+/// A column of notes holds every string struck at that moment, each with its position
+/// and sounding pitch. A space is a column of dashes, where earlier notes keep ringing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TabColumn {
+    Notes(Vec<(Position, PitchedNote)>),
+    Space,
+    Bar,
+}
+
+/// A note read from tablature, with the time it starts and how long it lasts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TabNote {
+    position: Position,
+    pitch: PitchedNote,
+    onset: Duration,
+    duration: Duration,
+}
+
+/// Plain-text guitar tablature
+///
+/// Tablature is written one line per string, with the highest string at the top, as in
+/// `e|---0---3---|`. Each line may start with the name of its string, which must match
+/// the tuning. Frets are written as numbers, and bar lines as `|`. Consecutive tab
+/// lines make up a system, and the systems of a tab are read one after another. Other
+/// lines, such as chord names or lyrics, are skipped, as are playing techniques such as
+/// `h`, `p`, `/` and `~`, and dead notes written as `x`.
+///
+/// Tab has no rhythm of its own, so every column counts as one step, and a note lasts
+/// until the next column of notes. A fret of two digits is still one column.
+/// Tabs are parsed with `Tab::parse` and written back out using `Display`, which names
+/// each string, writing the highest in lower case when it shares its name with another.
+///
+/// ### Examples
+/// ```
+/// use resonata::{notes::*, durations::*, fretboards::*, notation::tab::*};
+///
+/// let fretboard = Fretboard::new(Tuning::standard(), 22);
+/// let text = "e|-------0-|\nB|-----1---|\nG|---0-----|\nD|-2-------|\nA|---------|\nE|---------|";
+/// let tab = Tab::parse(text, &fretboard).unwrap();
+/// assert_eq!(tab.notes(), vec![
+///     pnote!("E3").unwrap(),
+///     pnote!("G3").unwrap(),
+///     pnote!("C4").unwrap(),
+///     pnote!("E4").unwrap(),
+/// ]);
+///
+/// let notes = tab.timed_notes(Duration::sixteenth()).unwrap();
+/// assert_eq!(notes[1].position(), Position::new(3, 0));
+/// assert_eq!(notes[1].onset(), dur!(3, 16).unwrap());
+/// assert_eq!(notes[1].duration(), Duration::eighth());
+/// assert_eq!(tab.to_string(), text);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tab {
+    tuning: Tuning,
+    columns: Vec<TabColumn>,
+}
+
+impl TabNote {
+    /// Returns the string and fret the note is played at.
+    pub fn position(&self) -> Position {
+        self.position
+    }
+
+    /// Returns the sounding pitch of the note.
+    pub fn pitch(&self) -> PitchedNote {
+        self.pitch
+    }
+
+    /// Returns the time from the start of the tab to the start of the note.
+    pub fn onset(&self) -> Duration {
+        self.onset
+    }
+
+    /// Returns how long the note lasts.
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+}
+
+impl Tab {
+    /// Creates an empty tab for the given tuning.
+    pub fn new(tuning: Tuning) -> Self {
+        Tab { tuning, columns: Vec::new() }
+    }
+
+    /// Parses tablature for an instrument with the given fretboard. Pitches are spelled
+    /// with sharps.
+    ///
+    /// ### Failures
+    /// Returns an error if a system does not have one line for every string, if a
+    /// string's name does not match the tuning, or if a fret is not on the fretboard.
+    pub fn parse(s: &str, fretboard: &Fretboard) -> Result<Self> {
+        utils::parse_tab(s, fretboard)
+    }
+
+    /// Writes a melody as tablature, giving each note as many columns as there are
+    /// steps in its duration, rounded down but at least one. Each note is played at the
+    /// position closest to the fret of the note before it, starting as low on the neck
+    /// as possible.
+    ///
+    /// ### Failures
+    /// Returns an error if a note of the melody is not on the fretboard.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::{notes::*, durations::*, fretboards::*, melodies::*, notation::tab::*};
+    ///
+    /// let fretboard = Fretboard::new(Tuning::standard(), 22);
+    /// let melody = melody!("A4:1/8 B4:1/8 C#5:1/8 r:1/8 E5:1/4").unwrap();
+    /// let tab = Tab::from_melody(&melody, &fretboard, Duration::sixteenth()).unwrap();
+    /// assert_eq!(tab.to_string(), [
+    ///     "e|5-7-9---12---|",
+    ///     "B|-------------|",
+    ///     "G|-------------|",
+    ///     "D|-------------|",
+    ///     "A|-------------|",
+    ///     "E|-------------|",
+    /// ].join("\n"));
+    /// assert_eq!(tab.to_melody(Duration::sixteenth()).unwrap().notes(), melody.notes());
+    /// ```
+    pub fn from_melody(melody: &Melody, fretboard: &Fretboard, step: Duration) -> Result<Self> {
+        let mut tab = Tab::new(fretboard.tuning().clone());
+        let mut fret = None;
+        for event in melody.events() {
+            let (n, d) = event.duration().ratio_to(&step)?;
+            let mut steps = (n / d).max(1);
+            if let Event::Note(pitch, _) = event {
+                let positions = fretboard.positions_of_pitch(*pitch);
+                let position = match fret {
+                    Some(fret) => {
+                        positions.iter().min_by_key(|p| (p.fret().abs_diff(fret), p.fret()))
+                    }
+                    None => positions.iter().min_by_key(|p| p.fret()),
+                };
+                let Some(position) = position else {
+                    nope!(FretboardError::PitchNotOnFretboard(*pitch));
+                };
+                fret = Some(position.fret());
+                tab.push(TabColumn::Notes(vec![(*position, *pitch)]));
+                steps -= 1;
+            }
+            for _ in 0..steps {
+                tab.push(TabColumn::Space);
+            }
+        }
+        Ok(tab)
+    }
+
+    /// Appends a column to the tab.
+    pub fn push(&mut self, column: TabColumn) {
+        self.columns.push(column);
+    }
+
+    /// Appends a voicing to the tab as a single column of notes.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::{chords::*, fretboards::*, notation::tab::*};
+    ///
+    /// let fretboard = Fretboard::new(Tuning::standard(), 15);
+    /// let mut tab = Tab::new(Tuning::standard());
+    /// for symbol in ["C", "Am", "G"] {
+    ///     let voicings = fretboard.voicings(&symbol.parse().unwrap(), &VoicingOptions::new());
+    ///     tab.push(TabColumn::Space);
+    ///     tab.push_voicing(&voicings[0]);
+    ///     tab.push(TabColumn::Space);
+    ///     tab.push(TabColumn::Bar);
+    /// }
+    /// assert_eq!(tab.to_string(), [
+    ///     "e|-0-|-0-|-3-|",
+    ///     "B|-1-|-1-|-0-|",
+    ///     "G|-0-|-2-|-0-|",
+    ///     "D|-2-|-2-|-0-|",
+    ///     "A|-3-|-0-|-2-|",
+    ///     "E|---|---|-3-|",
+    /// ].join("\n"));
+    /// ```
+    pub fn push_voicing(&mut self, voicing: &Voicing) {
+        let positions = voicing
+            .frets()
+            .iter()
+            .enumerate()
+            .filter_map(|(string, fret)| Some(Position::new(string, (*fret)?)));
+        let notes = positions.zip(voicing.pitches().iter().copied()).collect();
+        self.columns.push(TabColumn::Notes(notes));
+    }
+
+    /// Returns the tuning of the tab.
+    pub fn tuning(&self) -> &Tuning {
+        &self.tuning
+    }
+
+    /// Returns the columns of the tab, in order.
+    pub fn columns(&self) -> &Vec<TabColumn> {
+        &self.columns
+    }
+
+    /// Returns the pitches of every note in the tab, in order, with the notes of each
+    /// column from the lowest string to the highest.
+    pub fn notes(&self) -> Vec<PitchedNote> {
+        let mut notes = Vec::new();
+        for column in &self.columns {
+            if let TabColumn::Notes(column) = column {
+                notes.extend(column.iter().map(|(_, pitch)| *pitch));
+            }
+        }
+        notes
+    }
+
+    /// Returns every note in the tab with its onset and duration, taking each column as
+    /// the given step. A note lasts until the next column of notes, or the end of the tab.
+    ///
+    /// ### Failures
+    /// Returns an error if a time overflows.
+    pub fn timed_notes(&self, step: Duration) -> Result<Vec<TabNote>> {
+        // Each column of notes with its onset and length in steps.
+        let mut columns = Vec::new();
+        let mut steps = 0;
+        for column in &self.columns {
+            match column {
+                TabColumn::Notes(notes) => columns.push((steps, 1, notes)),
+                TabColumn::Space => {
+                    if let Some(last) = columns.last_mut() {
+                        last.1 += 1;
+                    }
+                }
+                TabColumn::Bar => continue,
+            }
+            steps += 1;
+        }
+
+        let mut notes = Vec::new();
+        for (onset, steps, column) in columns {
+            let onset = step.scaled(onset, 1)?;
+            let duration = step.scaled(steps, 1)?;
+            notes.extend(column.iter().map(|(position, pitch)| TabNote {
+                position: *position,
+                pitch: *pitch,
+                onset,
+                duration,
+            }));
+        }
+        Ok(notes)
+    }
+
+    /// Reads the tab as a melody, taking each column as the given step. Each column of
+    /// notes becomes a note on its highest pitch, and any space before the first note
+    /// becomes a rest.
+    ///
+    /// ### Failures
+    /// Returns an error if a duration overflows.
+    pub fn to_melody(&self, step: Duration) -> Result<Melody> {
+        let mut notes: Vec<TabNote> = Vec::new();
+        for note in self.timed_notes(step)? {
+            match notes.last_mut() {
+                Some(last) if last.onset == note.onset => {
+                    if note.pitch.to_midi_number() > last.pitch.to_midi_number() {
+                        *last = note;
+                    }
+                }
+                _ => notes.push(note),
+            }
+        }
+        let mut melody = Melody::default();
+        if let Some(first) = notes.first().filter(|note| !note.onset.is_zero()) {
+            melody.push(Event::Rest(first.onset));
+        }
+        for note in notes {
+            melody.push(Event::Note(note.pitch, note.duration));
+        }
+        Ok(melody)
+    }
+}
@@ -0,0 +1,119 @@
+#[cfg(test)]
+mod tests {
+    use super::super::*;
+    use crate::{error::NotationError, melodies::*};
+
+    // The opening riff of "Smoke on the Water", in two systems with a chord line between.
+    const RIFF: &str = "G5    Bb5    C5
+e|-----------------|
+B|-----------------|
+G|--0---3---5------|
+D|--0---3---5------|
+A|-----------------|
+E|-----------------|
+
+e|----------------|
+B|----------------|
+G|--0---3---6-5---|
+D|--0---3---6-5---|
+A|----------------|
+E|----------------|
+";
+
+    fn fretboard() -> Fretboard {
+        Fretboard::new(Tuning::standard(), 22)
+    }
+
+    #[test]
+    fn test_parse_systems() {
+        let tab = Tab::parse(RIFF, &fretboard()).unwrap();
+        assert_eq!(tab.columns().iter().filter(|c| **c == TabColumn::Bar).count(), 2);
+        let notes = tab.timed_notes(Duration::sixteenth()).unwrap();
+        assert_eq!(notes.len(), 14);
+        assert_eq!(notes[0].position(), Position::new(2, 0));
+        assert_eq!(notes[0].pitch(), pnote!("D3").unwrap());
+        assert_eq!(notes[1].pitch(), pnote!("G3").unwrap());
+        assert_eq!(notes[2].onset(), Duration::quarter().scaled(3, 2).unwrap());
+
+        // The second system carries on from where the first left off.
+        assert_eq!(notes[6].onset(), Duration::new(19, 16).unwrap());
+        assert_eq!(notes[4].duration(), Duration::new(9, 16).unwrap());
+        assert_eq!(notes[13].pitch(), pnote!("C4").unwrap());
+
+        let melody = tab.to_melody(Duration::sixteenth()).unwrap();
+        assert_eq!(melody.events()[0], Event::Rest(Duration::eighth()));
+        assert_eq!(melody.semitone_steps(), vec![3, 2, -5, 3, 3, -1]);
+    }
+
+    #[test]
+    fn test_parse_frets() {
+        let fretboard = Fretboard::new(Tuning::bass(), 20);
+        let text = "G|--12h14--x--|\nD|--------/7--|\nA|-0----------|\nE|------------|";
+        let tab = Tab::parse(text, &fretboard).unwrap();
+        let notes = tab.notes();
+        assert_eq!(
+            notes,
+            vec![
+                pnote!("A1").unwrap(),
+                pnote!("G3").unwrap(),
+                pnote!("A3").unwrap(),
+                pnote!("A2").unwrap(),
+            ]
+        );
+
+        // Labels are optional, but must match the tuning when given.
+        let text = "|--1--|\n|-----|\n|-----|\n|--3--|";
+        assert_eq!(Tab::parse(text, &fretboard).unwrap().notes().len(), 2);
+        let text = "G|--1--|\nD|-----|\nA|-----|\nB|-----|";
+        assert_eq!(
+            Tab::parse(text, &fretboard),
+            Err(ResonataError::from(NotationError::InvalidToken(4, "B".to_string())))
+        );
+        let text = "G|--1--|\nD|-----|\nA|-----|\n\nE|-----|";
+        assert_eq!(
+            Tab::parse(text, &fretboard),
+            Err(ResonataError::from(NotationError::StringMismatch(1)))
+        );
+        let text = "G|--21--|\nD|------|\nA|------|\nE|------|";
+        assert_eq!(
+            Tab::parse(text, &fretboard),
+            Err(ResonataError::from(FretboardError::InvalidPosition(3, 21)))
+        );
+    }
+
+    #[test]
+    fn test_render() {
+        let fretboard = fretboard();
+        let tab = Tab::parse(RIFF, &fretboard).unwrap();
+        let text = tab.to_string();
+        assert!(text.starts_with("e|-----------------|"));
+        assert_eq!(Tab::parse(&text, &fretboard).unwrap(), tab);
+
+        let melody = melody!("E2:1/4 A4:1/4 C4:1/4").unwrap();
+        let tab = Tab::from_melody(&melody, &fretboard, Duration::eighth()).unwrap();
+        assert_eq!(
+            tab.to_string(),
+            ["e|--5---|", "B|------|", "G|----5-|", "D|------|", "A|------|", "E|0-----|"]
+                .join("\n")
+        );
+
+        // Notes that are not a whole number of steps long are rounded down.
+        let melody = melody!("A4:3/16 B4:1/16 C#5:1/4").unwrap();
+        let tab = Tab::from_melody(&melody, &fretboard, Duration::eighth()).unwrap();
+        assert!(tab.to_string().starts_with("e|579-|"));
+        let melody = melody!("A4:1/12 B4:1/12 C#5:1/12 E5:1/4").unwrap();
+        let tab = Tab::from_melody(&melody, &fretboard, Duration::sixteenth()).unwrap();
+        assert!(tab.to_string().starts_with("e|57912---|"));
+        let melody = melody!("C2:1/4").unwrap();
+        assert_eq!(
+            Tab::from_melody(&melody, &fretboard, Duration::eighth()),
+            Err(ResonataError::from(FretboardError::PitchNotOnFretboard(pnote!("C2").unwrap())))
+        );
+
+        let tab = Tab::parse("d|-0-|\nA|-0-|\nG|-0-|\nD|-0-|\nA|-0-|\nD|-0-|", &fretboard);
+        assert!(tab.is_err());
+        let dadgad = Fretboard::new(Tuning::dadgad(), 22);
+        let tab = Tab::parse("d|-0-|\nA|-0-|\nG|-0-|\nD|-0-|\nA|-0-|\nD|-0-|", &dadgad).unwrap();
+        assert_eq!(tab.to_string(), "d|-0-|\nA|-0-|\nG|-0-|\nD|-0-|\nA|-0-|\nD|-0-|");
+    }
+}
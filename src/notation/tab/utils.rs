@@ -0,0 +1,149 @@
+use super::*;
+use crate::error::NotationError;
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::fmt::{self, Display, Formatter};
+
+lazy_static! {
+    static ref TAB_LINE_RE: Regex = Regex::new(r"^\s*([A-Ga-g][#b♯♭]?\d?)?\s*\|(.*-.*)$").unwrap();
+}
+
+/// Parses every system of a tab, skipping the lines between them.
+pub(super) fn parse_tab(s: &str, fretboard: &Fretboard) -> Result<Tab> {
+    let mut tab = Tab::new(fretboard.tuning().clone());
+    let mut system: Vec<(usize, Option<&str>, &str)> = Vec::new();
+    for (index, line) in s.lines().enumerate() {
+        match TAB_LINE_RE.captures(line.trim_end()) {
+            Some(cap) => {
+                let label = cap.get(1).map(|label| label.as_str());
+                system.push((index + 1, label, cap.get(2).unwrap().as_str()));
+            }
+            None if !system.is_empty() => {
+                parse_system(&system, fretboard, &mut tab)?;
+                system.clear();
+            }
+            None => (),
+        }
+    }
+    if !system.is_empty() {
+        parse_system(&system, fretboard, &mut tab)?;
+    }
+    Ok(tab)
+}
+
+/// Parses a system of tab lines, highest string first, into columns.
+fn parse_system(
+    system: &[(usize, Option<&str>, &str)],
+    fretboard: &Fretboard,
+    tab: &mut Tab,
+) -> Result<()> {
+    let strings = fretboard.tuning().strings();
+    if system.len() != strings.len() {
+        nope!(NotationError::StringMismatch(system[0].0));
+    }
+
+    let mut lines = Vec::new();
+    for (offset, (line, label, body)) in system.iter().enumerate() {
+        let string = strings.len() - 1 - offset;
+        if let Some(label) = label {
+            let mut chars = label.trim_end_matches(|c: char| c.is_ascii_digit()).chars();
+            let name = chars.next().map(|c| c.to_ascii_uppercase()).into_iter().chain(chars);
+            let note: Note = name
+                .collect::<String>()
+                .parse()
+                .map_err(|_| NotationError::InvalidToken(*line, label.to_string()))?;
+            let open = Note::from(strings[string]);
+            if (note.to_chromatic_scale_degree() - open.to_chromatic_scale_degree()).rem_euclid(12)
+                != 0
+            {
+                nope!(NotationError::InvalidToken(*line, label.to_string()));
+            }
+        }
+        lines.push((string, body.chars().collect::<Vec<_>>()));
+    }
+
+    let length = lines.iter().map(|(_, chars)| chars.len()).max().unwrap_or(0);
+    let starts_number = |chars: &Vec<char>, i: usize| {
+        chars.get(i).is_some_and(|c| c.is_ascii_digit())
+            && (i == 0 || !chars[i - 1].is_ascii_digit())
+    };
+    let mut i = 0;
+    while i < length {
+        if lines.iter().any(|(_, chars)| chars.get(i) == Some(&'|')) {
+            tab.push(TabColumn::Bar);
+            i += 1;
+            continue;
+        }
+
+        // A column takes in every number that starts within it, so that a fret of two
+        // digits on one string lines up with a single digit on another.
+        let mut width = 1;
+        let mut notes = Vec::new();
+        let mut j = i;
+        while j < i + width {
+            for (string, chars) in &lines {
+                if starts_number(chars, j) {
+                    let digits = chars[j..].iter().take_while(|c| c.is_ascii_digit());
+                    let number = digits.collect::<String>();
+                    width = width.max(j - i + number.len());
+                    let fret = number.parse().unwrap_or(u8::MAX);
+                    let position = Position::new(*string, fret);
+                    notes.push((position, fretboard.pitch_at(position)?));
+                }
+            }
+            j += 1;
+        }
+        if notes.is_empty() {
+            tab.push(TabColumn::Space);
+        } else {
+            notes.sort_by_key(|(position, _)| position.string());
+            tab.push(TabColumn::Notes(notes));
+        }
+        i += width;
+    }
+    Ok(())
+}
+
+impl Display for Tab {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let strings = self.tuning.strings();
+        let mut labels =
+            strings.iter().map(|string| Note::from(*string).to_string()).collect::<Vec<_>>();
+        if let Some(highest) = labels.last() {
+            if labels[..labels.len() - 1].contains(highest) {
+                let highest = highest.to_lowercase();
+                *labels.last_mut().unwrap() = highest;
+            }
+        }
+        let width = labels.iter().map(|label| label.chars().count()).max().unwrap_or(0);
+
+        let mut lines = labels
+            .iter()
+            .map(|label| format!("{}{}|", label, " ".repeat(width - label.chars().count())))
+            .collect::<Vec<_>>();
+        for column in &self.columns {
+            match column {
+                TabColumn::Notes(notes) => {
+                    let frets = (0..strings.len())
+                        .map(|string| {
+                            let note = notes.iter().find(|(p, _)| p.string() == string);
+                            note.map(|(position, _)| position.fret().to_string())
+                        })
+                        .collect::<Vec<_>>();
+                    let width = frets.iter().flatten().map(|fret| fret.len()).max().unwrap_or(1);
+                    for (line, fret) in lines.iter_mut().zip(frets) {
+                        let fret = fret.unwrap_or_default();
+                        line.push_str(&format!("{}{}", fret, "-".repeat(width - fret.len())));
+                    }
+                }
+                TabColumn::Space => lines.iter_mut().for_each(|line| line.push('-')),
+                TabColumn::Bar => lines.iter_mut().for_each(|line| line.push('|')),
+            }
+        }
+        if self.columns.last() != Some(&TabColumn::Bar) {
+            lines.iter_mut().for_each(|line| line.push('|'));
+        }
+        lines.reverse();
+        write!(f, "{}", lines.join("\n"))
+    }
+}
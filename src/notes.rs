@@ -9,6 +9,7 @@ pub use acc::*;
 pub use name::*;
 
 pub mod acc;
+mod diatonic;
 pub mod name;
 mod tests;
 mod utils;
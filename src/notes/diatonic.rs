@@ -0,0 +1,202 @@
+use super::*;
+use crate::{
+    error::ScaleError,
+    keys::Key,
+    scales::{ChromaticPolicy, Scale},
+};
+
+/// The notes of a scale in ascending order, with the number of semitones
+/// from the first note to each of them.
+struct Degrees {
+    notes: Vec<Note>,
+    offsets: Vec<i32>,
+    period: i32,
+}
+
+impl Degrees {
+    fn new(notes: Vec<Note>) -> Self {
+        let mut offsets = vec![0];
+        for pair in notes.windows(2) {
+            offsets.push(offsets.last().unwrap() + pair[0].semitones_to(&pair[1]));
+        }
+        let period = offsets.last().unwrap() + notes.last().unwrap().semitones_to(&notes[0]);
+        Degrees { notes, offsets, period: if period == 0 { 12 } else { period } }
+    }
+
+    fn from_scale(scale: &Scale, root: Note) -> Self {
+        Degrees::new(scale.to_notes(root))
+    }
+
+    fn from_key(key: &Key) -> Self {
+        Degrees::new(key.pitches())
+    }
+
+    /// Returns the number of semitones from the first note to the given degree,
+    /// which may lie outside the first octave.
+    fn offset(&self, degree: i32) -> i32 {
+        let len = self.notes.len() as i32;
+        degree.div_euclid(len) * self.period + self.offsets[degree.rem_euclid(len) as usize]
+    }
+
+    fn note(&self, degree: i32) -> Note {
+        self.notes[degree.rem_euclid(self.notes.len() as i32) as usize]
+    }
+
+    /// Returns the degree the note belongs to, and the number of semitones it lies
+    /// above that degree, according to the policy.
+    fn locate(&self, note: Note, policy: ChromaticPolicy) -> Result<(i32, i32)> {
+        // The number of semitones from a scale note to the given note, between -6 and 5.
+        let distance = |from: &Note| (from.semitones_to(&note) + 6).rem_euclid(12) - 6;
+
+        if let Some(degree) = self.notes.iter().position(|n| *n == note) {
+            return Ok((degree as i32, 0));
+        }
+        if let Some(degree) = self.notes.iter().position(|n| distance(n) == 0) {
+            return Ok((degree as i32, 0));
+        }
+
+        let nearest = |above: bool| {
+            let semitones = |n: &Note| match above {
+                true => note.semitones_to(n),
+                false => n.semitones_to(&note),
+            };
+            let degree = (0..self.notes.len()).min_by_key(|i| semitones(&self.notes[*i])).unwrap();
+            (degree as i32, distance(&self.notes[degree]))
+        };
+
+        match policy {
+            ChromaticPolicy::Reject => nope!(ScaleError::NoteNotInScale(note)),
+            ChromaticPolicy::KeepAlteration => {
+                match self.notes.iter().position(|n| n.name() == note.name()) {
+                    Some(degree) => Ok((degree as i32, distance(&self.notes[degree]))),
+                    None => Ok(nearest(false)),
+                }
+            }
+            ChromaticPolicy::SnapDown => Ok(nearest(false)),
+            ChromaticPolicy::SnapUp => Ok(nearest(true)),
+        }
+    }
+
+    fn move_note(&self, note: Note, steps: i32, policy: ChromaticPolicy) -> Result<Note> {
+        let (degree, alteration) = self.locate(note, policy)?;
+        let target = self.note(degree + steps);
+        match policy {
+            ChromaticPolicy::KeepAlteration => Ok(altered(target, alteration)),
+            _ => Ok(target),
+        }
+    }
+
+    fn move_pitch(
+        &self,
+        pitch: PitchedNote,
+        steps: i32,
+        policy: ChromaticPolicy,
+    ) -> Result<PitchedNote> {
+        let (degree, alteration) = self.locate(pitch.note, policy)?;
+        let target = self.note(degree + steps);
+        let midi = pitch.to_midi_number() as i32 - alteration - self.offset(degree)
+            + self.offset(degree + steps);
+        let octave = (midi - target.to_chromatic_scale_degree()).div_euclid(12) - 1;
+        match policy {
+            ChromaticPolicy::KeepAlteration => {
+                altered(target, alteration).with_octave(octave as i8)
+            }
+            _ => target.with_octave(octave as i8),
+        }
+    }
+}
+
+/// Raises or lowers the note by the given number of semitones, keeping its name.
+fn altered(note: Note, semitones: i32) -> Note {
+    Note::new(note.name, note.accidental + semitones)
+}
+
+impl Note {
+    /// Returns this note moved by the given number of degrees of a scale built on the
+    /// given root. Notes outside the scale are handled according to the policy.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::{notes::*, scales::*};
+    ///
+    /// let (scale, root) = (Scale::harmonic_minor(), note!("E").unwrap());
+    /// let policy = ChromaticPolicy::Reject;
+    /// assert_eq!(note!("B").unwrap().moved_in_scale(2, &scale, root, policy).unwrap(), note!("D#").unwrap());
+    /// assert_eq!(note!("E").unwrap().moved_in_scale(-1, &scale, root, policy).unwrap(), note!("D#").unwrap());
+    /// assert_eq!(note!("Cb").unwrap().moved_in_scale(1, &scale, root, policy).unwrap(), note!("C").unwrap());
+    /// ```
+    pub fn moved_in_scale(
+        &self,
+        steps: i32,
+        scale: &Scale,
+        root: Note,
+        policy: ChromaticPolicy,
+    ) -> Result<Self> {
+        Degrees::from_scale(scale, root).move_note(*self, steps, policy)
+    }
+
+    /// Returns this note moved by the given number of degrees of the key, which moves the
+    /// note name by that many steps and takes its accidental from the key signature.
+    /// Notes outside the key are handled according to the policy.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::{notes::*, keys::*, scales::*};
+    ///
+    /// let key = key!("Bb Eb").unwrap();
+    /// let policy = ChromaticPolicy::KeepAlteration;
+    /// assert_eq!(note!("C").unwrap().moved_in_key(2, &key, policy).unwrap(), note!("Eb").unwrap());
+    /// assert_eq!(note!("F#").unwrap().moved_in_key(-1, &key, policy).unwrap(), note!("E").unwrap());
+    /// ```
+    pub fn moved_in_key(&self, steps: i32, key: &Key, policy: ChromaticPolicy) -> Result<Self> {
+        Degrees::from_key(key).move_note(*self, steps, policy)
+    }
+}
+
+impl PitchedNote {
+    /// Returns this note moved by the given number of degrees of a scale built on the
+    /// given root, in the octave it falls in. Notes outside the scale are handled
+    /// according to the policy.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::{notes::*, scales::*};
+    ///
+    /// let (scale, root) = (Scale::major(), note!("Eb").unwrap());
+    /// let policy = ChromaticPolicy::Reject;
+    /// let g4 = pnote!("G4").unwrap();
+    /// assert_eq!(g4.moved_in_scale(2, &scale, root, policy).unwrap(), pnote!("Bb4").unwrap());
+    /// assert_eq!(g4.moved_in_scale(4, &scale, root, policy).unwrap(), pnote!("D5").unwrap());
+    /// assert_eq!(g4.moved_in_scale(-3, &scale, root, policy).unwrap(), pnote!("D4").unwrap());
+    /// assert_eq!(g4.moved_in_scale(7, &scale, root, policy).unwrap(), pnote!("G5").unwrap());
+    /// ```
+    pub fn moved_in_scale(
+        &self,
+        steps: i32,
+        scale: &Scale,
+        root: Note,
+        policy: ChromaticPolicy,
+    ) -> Result<Self> {
+        Degrees::from_scale(scale, root).move_pitch(*self, steps, policy)
+    }
+
+    /// Returns this note moved by the given number of degrees of the key, in the octave
+    /// it falls in. Notes outside the key are handled according to the policy.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::{notes::*, keys::*, scales::*};
+    ///
+    /// let key = key!("F# C#").unwrap();
+    /// let policy = ChromaticPolicy::KeepAlteration;
+    /// let b3 = pnote!("B3").unwrap();
+    /// assert_eq!(b3.moved_in_key(1, &key, policy).unwrap(), pnote!("C#4").unwrap());
+    /// assert_eq!(b3.moved_in_key(-2, &key, policy).unwrap(), pnote!("G3").unwrap());
+    ///
+    /// let a_sharp = pnote!("A#3").unwrap();
+    /// assert_eq!(a_sharp.moved_in_key(3, &key, policy).unwrap(), pnote!("D#4").unwrap());
+    /// ```
+    pub fn moved_in_key(&self, steps: i32, key: &Key, policy: ChromaticPolicy) -> Result<Self> {
+        Degrees::from_key(key).move_pitch(*self, steps, policy)
+    }
+}
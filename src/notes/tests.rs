@@ -49,6 +49,61 @@ mod tests {
         assert_eq!(note.unwrap(), pnote!("Bb3").unwrap());
     }
 
+    #[test]
+    fn test_transpose_traits() {
+        use crate::{TransposeDown, TransposeUp};
+
+        let note = note!("D").unwrap();
+        assert_eq!(note.transposed_up(inv!("m3").unwrap()), note!("F").unwrap());
+        assert_eq!(note.transposed_down(inv!("M2").unwrap()), note!("C").unwrap());
+
+        let note = pnote!("B3").unwrap();
+        assert_eq!(note.transposed_up(inv!("m2").unwrap()).unwrap(), pnote!("C4").unwrap());
+        assert_eq!(note.transposed_down(inv!("P8").unwrap()).unwrap(), pnote!("B2").unwrap());
+    }
+
+    #[test]
+    fn test_moved_in_scale() {
+        use crate::scales::*;
+
+        // A rising sequence in Ab major stays spelled with flats.
+        let (scale, root) = (Scale::major(), note!("Ab").unwrap());
+        let policy = ChromaticPolicy::Reject;
+        let notes = ["Ab3", "Bb3", "C4", "Db4", "Eb4", "F4", "G4", "Ab4"];
+        let start = pnote!("Ab3").unwrap();
+        for (steps, expected) in notes.iter().enumerate() {
+            let moved = start.moved_in_scale(steps as i32, &scale, root, policy).unwrap();
+            assert_eq!(moved, expected.parse::<PitchedNote>().unwrap());
+        }
+
+        // Chromatic notes in a pentatonic scale, which has no degree named F.
+        let (scale, root) = (Scale::major_pentatonic(), note!("C").unwrap());
+        let f4 = pnote!("F4").unwrap();
+        let moved = |policy| f4.moved_in_scale(1, &scale, root, policy);
+        assert!(moved(ChromaticPolicy::Reject).is_err());
+        assert_eq!(moved(ChromaticPolicy::KeepAlteration).unwrap(), pnote!("G#4").unwrap());
+        assert_eq!(moved(ChromaticPolicy::SnapDown).unwrap(), pnote!("G4").unwrap());
+        assert_eq!(moved(ChromaticPolicy::SnapUp).unwrap(), pnote!("A4").unwrap());
+
+        let moved = pnote!("B3").unwrap().moved_in_scale(-5, &scale, root, ChromaticPolicy::SnapUp);
+        assert_eq!(moved.unwrap(), pnote!("C3").unwrap());
+    }
+
+    #[test]
+    fn test_moved_in_key() {
+        use crate::{keys::*, scales::*};
+
+        let key = key!("Bb Eb Ab Db").unwrap();
+        let policy = ChromaticPolicy::KeepAlteration;
+        let moved = pnote!("C5").unwrap().moved_in_key(-2, &key, policy).unwrap();
+        assert_eq!(moved, pnote!("Ab4").unwrap());
+        let moved = pnote!("E4").unwrap().moved_in_key(1, &key, policy).unwrap();
+        assert_eq!(moved, pnote!("F#4").unwrap());
+        let moved = pnote!("E4").unwrap().moved_in_key(1, &key, ChromaticPolicy::SnapDown);
+        assert_eq!(moved.unwrap(), pnote!("F4").unwrap());
+        assert!(pnote!("E4").unwrap().moved_in_key(1, &key, ChromaticPolicy::Reject).is_err());
+    }
+
     #[test]
     fn test_note_from_str() {
         let c = "C".parse::<Note>().unwrap();
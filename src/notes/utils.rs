@@ -1,7 +1,7 @@
 use crate::Interval;
 
 use super::*;
-use crate::{err, error::*};
+use crate::{err, error::*, TransposeDown, TransposeUp};
 use lazy_static::lazy_static;
 use regex::Regex;
 use std::{
@@ -16,6 +16,13 @@ impl From<PitchedNote> for u8 {
     }
 }
 
+impl From<PitchedNote> for Note {
+    /// Convert a pitched note to a note, dropping the octave.
+    fn from(pnote: PitchedNote) -> Self {
+        pnote.note
+    }
+}
+
 lazy_static! {
     static ref NOTE_RE: Regex = Regex::new("^([A-Ga-g])([#x𝄪b♯♯♭♭♮]*)$").unwrap();
 }
@@ -120,6 +127,34 @@ impl SubAssign<Interval> for PitchedNote {
     }
 }
 
+impl TransposeUp for Note {
+    type Output = Note;
+    fn transposed_up(&self, interval: Interval) -> Self::Output {
+        *self + interval
+    }
+}
+
+impl TransposeDown for Note {
+    type Output = Note;
+    fn transposed_down(&self, interval: Interval) -> Self::Output {
+        *self - interval
+    }
+}
+
+impl TransposeUp for PitchedNote {
+    type Output = Result<PitchedNote>;
+    fn transposed_up(&self, interval: Interval) -> Self::Output {
+        *self + interval
+    }
+}
+
+impl TransposeDown for PitchedNote {
+    type Output = Result<PitchedNote>;
+    fn transposed_down(&self, interval: Interval) -> Self::Output {
+        *self - interval
+    }
+}
+
 impl FromStr for Note {
     type Err = ResonataError;
     fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
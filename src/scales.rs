@@ -1,8 +1,17 @@
-use crate::{error::ResonataError, intervals::Interval, notes::*};
+use crate::{error::ResonataError, intervals::Interval, nope, notes::*};
 
 pub use crate::scale;
-pub use types::{HarmonicMinorMode, MajorMode, MelodicMinorMode, ScaleEnumType, ScaleType};
+pub use degrees::{DegreeName, ScaleDegree, Solfege};
+pub use matching::ScaleMatch;
+pub(crate) use sets::{pitch_class, pitch_class_set, rotate_set};
+pub use types::{
+    HarmonicMajorMode, HarmonicMinorMode, MajorMode, MelodicMinorMode, ScaleEnumType, ScaleType,
+};
 
+pub mod degrees;
+mod matching;
+mod sets;
+mod tests;
 pub mod types;
 mod utils;
 
@@ -59,6 +68,38 @@ pub struct Scale {
     intervals: Vec<Interval>,
 }
 
+/// How to treat a note that is not in the scale when moving notes by scale degrees.
+///
+/// ### Examples
+/// ```
+/// use resonata::{notes::*, scales::*};
+///
+/// let (scale, root) = (Scale::major(), note!("C").unwrap());
+/// let f_sharp = note!("F#").unwrap();
+///
+/// assert!(f_sharp.moved_in_scale(1, &scale, root, ChromaticPolicy::Reject).is_err());
+/// let moved = f_sharp.moved_in_scale(1, &scale, root, ChromaticPolicy::KeepAlteration);
+/// assert_eq!(moved.unwrap(), note!("G#").unwrap());
+/// let moved = f_sharp.moved_in_scale(1, &scale, root, ChromaticPolicy::SnapDown);
+/// assert_eq!(moved.unwrap(), note!("G").unwrap());
+/// let moved = f_sharp.moved_in_scale(1, &scale, root, ChromaticPolicy::SnapUp);
+/// assert_eq!(moved.unwrap(), note!("A").unwrap());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ChromaticPolicy {
+    /// Return an error for notes outside the scale.
+    Reject,
+    /// Treat the note as an altered scale degree, and carry the alteration to the
+    /// degree it moves to. The degree is the one with the same note name, or the
+    /// nearest one below when no degree shares its name.
+    #[default]
+    KeepAlteration,
+    /// Move the note down to the nearest scale note before moving it.
+    SnapDown,
+    /// Move the note up to the nearest scale note before moving it.
+    SnapUp,
+}
+
 /// A macro to create a scale from a root note and a scale type.
 #[macro_export]
 macro_rules! scale {
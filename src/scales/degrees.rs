@@ -0,0 +1,314 @@
+use super::*;
+use crate::error::ScaleError;
+use std::{
+    fmt::{self, Debug, Display, Formatter},
+    str::FromStr,
+};
+
+/// A degree of a scale, counting from 1 for the root, with an optional chromatic
+/// alteration relative to the scale.
+///
+/// ### Examples
+/// ```
+/// use resonata::{notes::*, scales::*};
+///
+/// let degree = "b3".parse::<ScaleDegree>().unwrap();
+/// assert_eq!(degree.number(), 3);
+/// assert_eq!(degree.accidental(), Accidental::Flat(1));
+/// assert_eq!(degree.to_string(), "♭3");
+///
+/// assert_eq!(ScaleDegree::new(4, Accidental::Sharp(1)).unwrap(), "♯4".parse().unwrap());
+/// assert!(ScaleDegree::new(0, Accidental::Natural).is_err());
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct ScaleDegree {
+    number: u8,
+    accidental: Accidental,
+}
+
+/// The traditional names of the degrees of a seven note scale.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DegreeName {
+    Tonic,
+    Supertonic,
+    Mediant,
+    Subdominant,
+    Dominant,
+    Submediant,
+    /// The seventh degree a semitone below the tonic.
+    LeadingTone,
+    /// The seventh degree a whole tone below the tonic.
+    Subtonic,
+}
+
+/// A solfège syllable, including the chromatic syllables for raised and lowered degrees.
+///
+/// Syllables are relative to the major scale: in movable do, do is the tonic of the key,
+/// so the lowered third of a minor key is me; in fixed do, do is always C.
+///
+/// ### Examples
+/// ```
+/// use resonata::{notes::*, scales::*};
+///
+/// let tonic = note!("D").unwrap();
+/// assert_eq!(Solfege::movable(note!("F#").unwrap(), tonic), Some(Solfege::Mi));
+/// assert_eq!(Solfege::movable(note!("F").unwrap(), tonic), Some(Solfege::Me));
+/// assert_eq!(Solfege::movable(note!("G#").unwrap(), tonic), Some(Solfege::Fi));
+/// assert_eq!(Solfege::movable(note!("Ab").unwrap(), tonic), Some(Solfege::Se));
+///
+/// assert_eq!(Solfege::fixed(note!("Bb").unwrap()), Some(Solfege::Te));
+/// assert_eq!(Solfege::fixed(note!("Bb").unwrap()).unwrap().to_string(), "te");
+/// assert_eq!(Solfege::fixed(note!("Bbb").unwrap()), None);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Solfege {
+    Do,
+    Di,
+    Ra,
+    Re,
+    Ri,
+    Me,
+    Mi,
+    Fa,
+    Fi,
+    Se,
+    Sol,
+    Si,
+    Le,
+    La,
+    Li,
+    Te,
+    Ti,
+}
+
+/// The syllables of each degree of the major scale, lowered, natural and raised.
+const SYLLABLES: [[Option<Solfege>; 3]; 7] = {
+    use Solfege::*;
+    [
+        [None, Some(Do), Some(Di)],
+        [Some(Ra), Some(Re), Some(Ri)],
+        [Some(Me), Some(Mi), None],
+        [None, Some(Fa), Some(Fi)],
+        [Some(Se), Some(Sol), Some(Si)],
+        [Some(Le), Some(La), Some(Li)],
+        [Some(Te), Some(Ti), None],
+    ]
+};
+
+impl ScaleDegree {
+    /// Creates a scale degree from its number, counting from 1 for the root,
+    /// and its alteration.
+    pub fn new(number: u8, accidental: Accidental) -> Result<Self> {
+        if number == 0 {
+            nope!(ScaleError::InvalidScaleDegree(number));
+        }
+        let accidental = match accidental {
+            Accidental::Sharp(0) | Accidental::Flat(0) => Accidental::Natural,
+            accidental => accidental,
+        };
+        Ok(ScaleDegree { number, accidental })
+    }
+
+    /// Returns the number of the degree, counting from 1 for the root.
+    pub fn number(&self) -> u8 {
+        self.number
+    }
+
+    /// Returns the alteration of the degree relative to the scale.
+    pub fn accidental(&self) -> Accidental {
+        self.accidental
+    }
+
+    /// Returns true if the degree is not altered.
+    pub fn is_diatonic(&self) -> bool {
+        self.accidental == Accidental::Natural
+    }
+}
+
+impl Solfege {
+    /// Returns the movable do syllable of a note in the key of the given tonic,
+    /// or `None` if the note is more than a semitone away from the major scale degree
+    /// with its name, or has no syllable, such as a raised third.
+    pub fn movable(note: Note, tonic: Note) -> Option<Self> {
+        let degree = (note.name() as i32 - tonic.name() as i32).rem_euclid(7) as usize;
+        let major = Scale::major().to_notes(tonic)[degree];
+        let alteration = (major.semitones_to(&note) + 6).rem_euclid(12) - 6;
+        match alteration {
+            -1..=1 => SYLLABLES[degree][(alteration + 1) as usize],
+            _ => None,
+        }
+    }
+
+    /// Returns the fixed do syllable of a note, where do is always C.
+    pub fn fixed(note: Note) -> Option<Self> {
+        Solfege::movable(note, Note::from_note_name(NoteName::C))
+    }
+
+    /// Returns the scale degree of the syllable relative to the major scale.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::{notes::*, scales::*};
+    ///
+    /// assert_eq!(Solfege::Le.to_degree().to_string(), "♭6");
+    /// assert_eq!(Solfege::Sol.to_degree().to_string(), "5");
+    /// ```
+    pub fn to_degree(&self) -> ScaleDegree {
+        for (number, syllables) in SYLLABLES.iter().enumerate() {
+            if let Some(index) = syllables.iter().position(|s| *s == Some(*self)) {
+                let accidental = Accidental::from_semitones(index as i32 - 1);
+                return ScaleDegree::new(number as u8 + 1, accidental).unwrap();
+            }
+        }
+        unreachable!()
+    }
+}
+
+impl Scale {
+    /// Returns the degree of the scale built on the given root that the note falls on.
+    ///
+    /// Notes in the scale, or enharmonically equivalent to a note in the scale, are
+    /// unaltered degrees. Other notes are alterations of the degree with the same note
+    /// name, if there is one, and otherwise `None` is returned.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::{notes::*, scales::*};
+    ///
+    /// let (scale, root) = (Scale::minor(), note!("A").unwrap());
+    /// assert_eq!(scale.degree_of(note!("C").unwrap(), root).unwrap().to_string(), "3");
+    /// assert_eq!(scale.degree_of(note!("G#").unwrap(), root).unwrap().to_string(), "♯7");
+    /// assert_eq!(scale.degree_of(note!("Bb").unwrap(), root).unwrap().to_string(), "♭2");
+    ///
+    /// let (scale, root) = (Scale::major_pentatonic(), note!("C").unwrap());
+    /// assert_eq!(scale.degree_of(note!("A").unwrap(), root).unwrap().to_string(), "5");
+    /// assert_eq!(scale.degree_of(note!("F").unwrap(), root), None);
+    /// ```
+    pub fn degree_of(&self, note: Note, root: Note) -> Option<ScaleDegree> {
+        let notes = self.to_notes(root);
+        let position = notes
+            .iter()
+            .position(|n| *n == note)
+            .or_else(|| notes.iter().position(|n| n.semitones_between(&note) == 0));
+        if let Some(position) = position {
+            return ScaleDegree::new(position as u8 + 1, Accidental::Natural).ok();
+        }
+
+        let position = notes.iter().position(|n| n.name() == note.name())?;
+        let alteration = (notes[position].semitones_to(&note) + 6).rem_euclid(12) - 6;
+        ScaleDegree::new(position as u8 + 1, Accidental::from_semitones(alteration)).ok()
+    }
+
+    /// Returns the note at the given degree of the scale built on the given root.
+    /// Degrees beyond the length of the scale wrap around.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::{notes::*, scales::*};
+    ///
+    /// let (scale, root) = (Scale::major(), note!("Eb").unwrap());
+    /// assert_eq!(scale.note_at("3".parse().unwrap(), root), note!("G").unwrap());
+    /// assert_eq!(scale.note_at("b3".parse().unwrap(), root), note!("Gb").unwrap());
+    /// assert_eq!(scale.note_at("#4".parse().unwrap(), root), note!("A").unwrap());
+    /// assert_eq!(scale.note_at("9".parse().unwrap(), root), note!("F").unwrap());
+    /// ```
+    pub fn note_at(&self, degree: ScaleDegree, root: Note) -> Note {
+        let notes = self.to_notes(root);
+        let note = notes[(degree.number as usize - 1) % notes.len()];
+        Note::new(note.name(), note.accidental() + degree.accidental.to_semitones())
+    }
+
+    /// Returns the traditional name of the given degree of a seven note scale, or `None`
+    /// for other scales and for altered degrees. The seventh degree is named by its
+    /// distance from the tonic, so a raised seventh in minor is the leading tone.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::{notes::*, scales::*};
+    ///
+    /// let minor = Scale::minor();
+    /// assert_eq!(minor.degree_name("5".parse().unwrap()), Some(DegreeName::Dominant));
+    /// assert_eq!(minor.degree_name("7".parse().unwrap()), Some(DegreeName::Subtonic));
+    /// assert_eq!(minor.degree_name("#7".parse().unwrap()), Some(DegreeName::LeadingTone));
+    /// assert_eq!(minor.degree_name("#4".parse().unwrap()), None);
+    /// assert_eq!(Scale::major().degree_name("7".parse().unwrap()), Some(DegreeName::LeadingTone));
+    /// ```
+    pub fn degree_name(&self, degree: ScaleDegree) -> Option<DegreeName> {
+        if self.intervals.len() != 7 || degree.number > 7 {
+            return None;
+        }
+        if degree.number == 7 {
+            let semitones = self.interval(5).to_semitones() + degree.accidental.to_semitones();
+            return match semitones {
+                11 => Some(DegreeName::LeadingTone),
+                10 => Some(DegreeName::Subtonic),
+                _ => None,
+            };
+        }
+        if !degree.is_diatonic() {
+            return None;
+        }
+        match degree.number {
+            1 => Some(DegreeName::Tonic),
+            2 => Some(DegreeName::Supertonic),
+            3 => Some(DegreeName::Mediant),
+            4 => Some(DegreeName::Subdominant),
+            5 => Some(DegreeName::Dominant),
+            _ => Some(DegreeName::Submediant),
+        }
+    }
+}
+
+impl FromStr for ScaleDegree {
+    type Err = ResonataError;
+
+    /// Parses a scale degree such as "3", "b3", "♭3" or "#4".
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let s = s.trim();
+        let split = s.find(|c: char| c.is_ascii_digit()).unwrap_or(s.len());
+        let (accidental, number) = s.split_at(split);
+        let accidental = match accidental {
+            "" => Accidental::Natural,
+            accidental => accidental.parse::<Accidental>()?,
+        };
+        let number = number
+            .parse::<u8>()
+            .map_err(|_| ScaleError::InvalidScaleDegreeFormat(s.to_string()))?;
+        ScaleDegree::new(number, accidental)
+    }
+}
+
+impl Display for ScaleDegree {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.accidental, self.number)
+    }
+}
+
+impl Debug for ScaleDegree {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+impl Display for DegreeName {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            DegreeName::Tonic => "tonic",
+            DegreeName::Supertonic => "supertonic",
+            DegreeName::Mediant => "mediant",
+            DegreeName::Subdominant => "subdominant",
+            DegreeName::Dominant => "dominant",
+            DegreeName::Submediant => "submediant",
+            DegreeName::LeadingTone => "leading tone",
+            DegreeName::Subtonic => "subtonic",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl Display for Solfege {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let syllable = format!("{:?}", self).to_lowercase();
+        write!(f, "{}", syllable)
+    }
+}
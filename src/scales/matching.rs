@@ -0,0 +1,262 @@
+use super::*;
+use crate::scales::types::utils::ALL_SCALES;
+
+/// A scale on a particular root that contains a given set of notes, as found by
+/// [Scale::containing].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScaleMatch {
+    root: Note,
+    scale_type: ScaleEnumType,
+    scale: Scale,
+    fit: f64,
+    extra_notes: Vec<Note>,
+}
+
+impl ScaleMatch {
+    /// Returns the root of the matching scale.
+    pub fn root(&self) -> Note {
+        self.root
+    }
+
+    /// Returns the name of the matching scale.
+    pub fn scale_type(&self) -> ScaleEnumType {
+        self.scale_type
+    }
+
+    /// Returns the matching scale.
+    pub fn scale(&self) -> &Scale {
+        &self.scale
+    }
+
+    /// Returns the notes of the matching scale, spelled from its root.
+    pub fn notes(&self) -> Vec<Note> {
+        self.scale.to_notes(self.root)
+    }
+
+    /// Returns the proportion of the scale's pitch classes that were given,
+    /// from 0 to 1, where 1 means the notes fill the scale exactly.
+    pub fn fit(&self) -> f64 {
+        self.fit
+    }
+
+    /// Returns the notes of the scale that were not given.
+    pub fn extra_notes(&self) -> &Vec<Note> {
+        &self.extra_notes
+    }
+}
+
+impl Scale {
+    /// Returns every scale in the catalog, on every root, that contains all of the
+    /// given notes, ranked by fit. Scales with fewer notes besides the given ones come
+    /// first. Ties go to scales that spell more of the notes as given, then to scales
+    /// rooted on the first note, then to scales whose root occurs most often.
+    ///
+    /// Each root is spelled so that the scale spells as many of the given notes as
+    /// possible, and otherwise with as few accidentals as possible.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::{notes::*, scales::*};
+    ///
+    /// let notes = ["C", "D", "E", "F", "G", "A"].map(|note| note.parse::<Note>().unwrap());
+    /// let matches = Scale::containing(&notes);
+    /// assert_eq!(matches[0].root(), note!("C").unwrap());
+    /// assert_eq!(matches[0].scale_type(), ScaleType::Major.into());
+    /// assert_eq!(matches[0].extra_notes(), &vec![note!("B").unwrap()]);
+    /// assert_eq!(matches[1].scale_type(), MajorMode::Mixolydian.into());
+    ///
+    /// let f_major = matches.iter().find(|m| m.root() == note!("F").unwrap()).unwrap();
+    /// assert_eq!(f_major.scale_type(), ScaleType::Major.into());
+    /// assert_eq!(f_major.extra_notes(), &vec![note!("Bb").unwrap()]);
+    /// assert!(matches.iter().all(|m| m.fit() <= 6.0 / 7.0));
+    ///
+    /// // Roots are spelled to match the notes given.
+    /// let notes = ["Db", "F", "Ab"].map(|note| note.parse::<Note>().unwrap());
+    /// let matches = Scale::containing(&notes);
+    /// assert_eq!(matches[0].root(), note!("Db").unwrap());
+    /// assert_eq!(matches[0].scale_type(), ScaleType::MajorPentatonic.into());
+    /// ```
+    pub fn containing(notes: &[Note]) -> Vec<ScaleMatch> {
+        let set = pitch_class_set(notes);
+        if set == 0 {
+            return Vec::new();
+        }
+
+        let mut matches = Vec::new();
+        let mut seen = Vec::new();
+        let catalog = ALL_SCALES.iter().flat_map(|scales| scales.iter());
+        for (index, (_, scale)) in catalog.enumerate() {
+            let scale = scale();
+            let number = scale.number();
+            if seen.contains(&number) {
+                continue;
+            }
+            seen.push(number);
+            let scale_type = scale.get_known_scale_type().unwrap();
+
+            for semitones in 0..12 {
+                let scale_set = rotate_set(number, semitones);
+                if set & !scale_set != 0 {
+                    continue;
+                }
+                let (root, spelled) = spell_root(&scale, semitones, notes);
+                let extra_notes = scale
+                    .to_notes(root)
+                    .into_iter()
+                    .filter(|note| set & 1 << pitch_class(note) == 0)
+                    .collect::<Vec<_>>();
+                let fit = set.count_ones() as f64 / scale_set.count_ones() as f64;
+                let first = pitch_class(&notes[0]) == pitch_class(&root);
+                let occurrences =
+                    notes.iter().filter(|note| pitch_class(note) == pitch_class(&root));
+                let rank = (extra_notes.len(), -spelled, !first, -(occurrences.count() as i32));
+                matches.push((
+                    rank,
+                    index,
+                    ScaleMatch { root, scale_type, scale: scale.clone(), fit, extra_notes },
+                ));
+            }
+        }
+
+        matches.sort_by_key(|(rank, index, m)| (*rank, *index, m.root.to_chromatic_scale_degree()));
+        matches.into_iter().map(|(_, _, m)| m).collect()
+    }
+
+    /// Returns the known scale types and modes that contain the scale, on the same root,
+    /// with the number of notes they add, fewest first. Unlike
+    /// [Scale::get_parent_scale_type], this finds scales that contain this one without
+    /// matching it exactly.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::{notes::*, scales::*};
+    ///
+    /// let scale = scale!("C D E G A C").unwrap();
+    /// let parents = scale.get_parent_scale_types();
+    /// assert_eq!(parents[0], (ScaleType::MajorPentatonic.into(), 0));
+    /// assert!(parents.contains(&(ScaleType::Major.into(), 2)));
+    /// assert!(parents.contains(&(MajorMode::Mixolydian.into(), 2)));
+    /// ```
+    pub fn get_parent_scale_types(&self) -> Vec<(ScaleEnumType, usize)> {
+        let number = self.number();
+        let mut parents = Vec::new();
+        for scales in ALL_SCALES {
+            for (name, scale) in *scales {
+                let scale = scale();
+                if number & !scale.number() == 0 && scale.get_known_scale_type() == Some(*name) {
+                    parents.push((*name, scale.cardinality() - self.cardinality()));
+                }
+            }
+        }
+        parents.sort_by_key(|(_, extra)| *extra);
+        parents
+    }
+
+    /// Returns the number of pitch classes that are in only one of the two scales,
+    /// built on the given roots.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::{notes::*, scales::*};
+    ///
+    /// let (c, g) = (note!("C").unwrap(), note!("G").unwrap());
+    /// assert_eq!(Scale::major().hamming_distance(c, &Scale::major(), g), 2);
+    /// assert_eq!(Scale::major().hamming_distance(c, &Scale::minor(), c), 6);
+    /// assert_eq!(Scale::major().hamming_distance(c, &Scale::major_pentatonic(), c), 2);
+    /// ```
+    pub fn hamming_distance(&self, root: Note, other: &Scale, other_root: Note) -> u32 {
+        (self.pitch_class_set(root) ^ other.pitch_class_set(other_root)).count_ones()
+    }
+
+    /// Returns the number of pitch classes the two scales, built on the given roots,
+    /// have in common.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::{notes::*, scales::*};
+    ///
+    /// let (c, g) = (note!("C").unwrap(), note!("G").unwrap());
+    /// assert_eq!(Scale::major().common_tones(c, &Scale::major(), g), 6);
+    /// assert_eq!(Scale::whole_tone().common_tones(c, &Scale::whole_tone(), g), 0);
+    /// ```
+    pub fn common_tones(&self, root: Note, other: &Scale, other_root: Note) -> u32 {
+        (self.pitch_class_set(root) & other.pitch_class_set(other_root)).count_ones()
+    }
+
+    /// Returns the smallest total number of semitones the notes of one scale must move
+    /// to reach the notes of the other, built on the given roots, with each note moving
+    /// to a different note. Returns None if the scales have different numbers of notes.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::{notes::*, scales::*};
+    ///
+    /// let (c, g, f_sharp) = (note!("C").unwrap(), note!("G").unwrap(), note!("F#").unwrap());
+    /// let major = Scale::major();
+    /// assert_eq!(major.voice_leading_distance(c, &major, g), Some(1));
+    /// assert_eq!(major.voice_leading_distance(c, &major, f_sharp), Some(6));
+    /// assert_eq!(major.voice_leading_distance(c, &Scale::melodic_minor(), c), Some(1));
+    /// assert_eq!(major.voice_leading_distance(c, &Scale::whole_tone(), c), None);
+    /// ```
+    pub fn voice_leading_distance(
+        &self,
+        root: Note,
+        other: &Scale,
+        other_root: Note,
+    ) -> Option<u32> {
+        let from = set_to_pitch_classes(self.pitch_class_set(root));
+        let to = set_to_pitch_classes(other.pitch_class_set(other_root));
+        if from.len() != to.len() {
+            return None;
+        }
+
+        let n = to.len();
+        (0..n)
+            .flat_map(|k| [-12, 0, 12].map(move |offset| (k, offset)))
+            .map(|(k, offset)| {
+                (0..n)
+                    .map(|i| {
+                        let target = to[(i + k) % n] + 12 * ((i + k) / n) as i32 + offset;
+                        (from[i] - target).unsigned_abs()
+                    })
+                    .sum()
+            })
+            .min()
+    }
+
+    /// Returns the set of pitch classes of the scale on the given root, with bit n set
+    /// for the pitch class n semitones above C.
+    fn pitch_class_set(&self, root: Note) -> u16 {
+        rotate_set(self.number(), pitch_class(&root) as u32)
+    }
+}
+
+fn set_to_pitch_classes(set: u16) -> Vec<i32> {
+    (0..12).filter(|pitch_class| set & (1 << pitch_class) != 0).collect()
+}
+
+/// Chooses a spelling for a root with the given pitch class, preferring the one that
+/// spells the most of the given notes when used to spell the scale, then the one with
+/// the fewest accidentals. Returns the root and the number of notes it spells.
+fn spell_root(scale: &Scale, pitch_class: u32, notes: &[Note]) -> (Note, i32) {
+    let names =
+        [NoteName::C, NoteName::D, NoteName::E, NoteName::F, NoteName::G, NoteName::A, NoteName::B];
+    names
+        .into_iter()
+        .filter_map(|name| {
+            let semitones = (pitch_class as i32 - name.to_chromatic_scale_degree() as i32 + 6)
+                .rem_euclid(12)
+                - 6;
+            (semitones.abs() <= 1).then(|| Note::new(name, Accidental::from_semitones(semitones)))
+        })
+        .map(|root| {
+            let scale_notes = scale.to_notes(root);
+            let spelled = notes.iter().filter(|note| scale_notes.contains(note)).count() as i32;
+            let accidentals: i32 =
+                scale_notes.iter().map(|note| note.accidental().to_semitones().abs()).sum();
+            (root, spelled, accidentals)
+        })
+        .min_by_key(|(_, spelled, accidentals)| (-spelled, *accidentals))
+        .map(|(root, spelled, _)| (root, spelled))
+        .unwrap()
+}
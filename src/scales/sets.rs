@@ -0,0 +1,210 @@
+use super::*;
+use crate::error::ScaleError;
+
+impl Scale {
+    /// Creates a scale from its scale number, as used in Ian Ring's catalog of scales.
+    /// Bit n of the number is set when the scale contains the note n semitones above
+    /// the root, so bit 0 must always be set, and there are 2048 scales in all.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::scales::*;
+    ///
+    /// assert_eq!(Scale::from_number(2741).unwrap(), Scale::major());
+    /// assert_eq!(Scale::from_number(661).unwrap(), Scale::major_pentatonic());
+    /// assert!(Scale::from_number(2740).is_err());
+    /// assert!(Scale::from_number(4097).is_err());
+    /// ```
+    pub fn from_number(number: u16) -> Result<Self> {
+        if number & 1 == 0 || number > 0xfff {
+            nope!(ScaleError::InvalidScaleNumber(number));
+        }
+        let mut intervals = (1..12)
+            .filter(|pitch_class| number & (1 << pitch_class) != 0)
+            .map(Interval::from_semitones)
+            .collect::<Result<Vec<_>>>()?;
+        intervals.push(Interval::from_semitones(12)?);
+        Ok(Scale { intervals })
+    }
+
+    /// Returns the scale number of the scale, as used in Ian Ring's catalog of scales.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::scales::*;
+    ///
+    /// assert_eq!(Scale::major().number(), 2741);
+    /// assert_eq!(Scale::minor().number(), 1453);
+    /// assert_eq!(Scale::chromatic().number(), 4095);
+    /// ```
+    pub fn number(&self) -> u16 {
+        self.pitch_classes().iter().fold(0, |number, pitch_class| number | 1 << pitch_class)
+    }
+
+    /// Returns the distinct pitch classes of the scale as semitones above the root,
+    /// in ascending order, starting with 0 for the root.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::scales::*;
+    ///
+    /// assert_eq!(Scale::minor_pentatonic().pitch_classes(), vec![0, 3, 5, 7, 10]);
+    /// ```
+    pub fn pitch_classes(&self) -> Vec<u8> {
+        let mut pitch_classes = vec![0];
+        pitch_classes.extend(
+            self.intervals.iter().map(|interval| interval.to_semitones().rem_euclid(12) as u8),
+        );
+        pitch_classes.sort();
+        pitch_classes.dedup();
+        pitch_classes
+    }
+
+    /// Returns the number of distinct pitch classes in the scale.
+    pub fn cardinality(&self) -> usize {
+        self.pitch_classes().len()
+    }
+
+    /// Returns the steps between the pitch classes of the scale, including the step
+    /// from the highest back up to the root.
+    fn cyclic_steps(&self) -> Vec<u8> {
+        let pitch_classes = self.pitch_classes();
+        let mut steps = pitch_classes.windows(2).map(|pair| pair[1] - pair[0]).collect::<Vec<_>>();
+        steps.push(12 - pitch_classes.last().unwrap());
+        steps
+    }
+
+    /// Returns the largest step between adjacent notes of the scale, in semitones.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::scales::*;
+    ///
+    /// assert_eq!(Scale::harmonic_minor().max_step(), 3);
+    /// assert_eq!(Scale::from_name("Hirajoshi").unwrap().max_step(), 4);
+    /// ```
+    pub fn max_step(&self) -> u8 {
+        self.cyclic_steps().into_iter().max().unwrap()
+    }
+
+    /// Returns true if the scale has two semitone steps in a row, that is three
+    /// chromatically adjacent notes, counting around the octave.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::scales::*;
+    ///
+    /// assert!(!Scale::melodic_minor().has_consecutive_semitones());
+    /// assert!(Scale::from_name("Major Bebop").unwrap().has_consecutive_semitones());
+    /// // B, C and Db in the Neapolitan minor scale on C.
+    /// assert!(Scale::from_name("Neapolitan Minor").unwrap().has_consecutive_semitones());
+    /// ```
+    pub fn has_consecutive_semitones(&self) -> bool {
+        let steps = self.cyclic_steps();
+        (0..steps.len()).any(|i| steps[i] == 1 && steps[(i + 1) % steps.len()] == 1)
+    }
+
+    /// Returns all 2048 scales built from the chromatic scale that contain the root,
+    /// in order of scale number.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::scales::*;
+    ///
+    /// let heptatonic = Scale::all()
+    ///     .into_iter()
+    ///     .filter(|scale| scale.cardinality() == 7)
+    ///     .filter(|scale| scale.max_step() <= 2)
+    ///     .filter(|scale| !scale.has_consecutive_semitones())
+    ///     .collect::<Vec<_>>();
+    ///
+    /// // The modes of the major and melodic minor scales
+    /// assert_eq!(heptatonic.len(), 14);
+    /// assert!(heptatonic.iter().all(|scale| scale.get_known_scale_type().is_some()));
+    /// ```
+    pub fn all() -> Vec<Scale> {
+        (1..0x1000).step_by(2).map(|number| Scale::from_number(number).unwrap()).collect()
+    }
+
+    /// Returns the distinct modes of the scale, starting with the scale itself.
+    /// Scales that are symmetrical about some division of the octave have fewer modes
+    /// than notes.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::scales::*;
+    ///
+    /// assert_eq!(Scale::major().modes().len(), 7);
+    /// assert_eq!(Scale::major().modes()[1], Scale::major().rotated(1));
+    /// assert_eq!(Scale::diminished().modes().len(), 2);
+    /// assert_eq!(Scale::whole_tone().modes().len(), 1);
+    /// ```
+    pub fn modes(&self) -> Vec<Scale> {
+        let start = Scale::from_number(self.number()).unwrap();
+        let mut modes: Vec<Scale> = Vec::new();
+        for i in 0..start.intervals.len() {
+            let mode = start.rotated(i as i8);
+            if !modes.contains(&mode) {
+                modes.push(mode);
+            }
+        }
+        modes
+    }
+
+    /// Returns the prime mode of the scale's modal family, which is the mode with
+    /// the lowest scale number.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::scales::*;
+    ///
+    /// assert_eq!(Scale::major().prime_mode().number(), 1387);
+    /// assert_eq!(Scale::minor().prime_mode(), Scale::major().prime_mode());
+    /// ```
+    pub fn prime_mode(&self) -> Scale {
+        self.modes().into_iter().min_by_key(|mode| mode.number()).unwrap()
+    }
+
+    /// Returns all scales grouped into modal families, each listed from its prime mode,
+    /// in order of the scale number of the prime mode.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::scales::*;
+    ///
+    /// let families = Scale::modal_families();
+    /// assert_eq!(families.len(), 351);
+    /// assert_eq!(families.iter().map(|family| family.len()).sum::<usize>(), 2048);
+    /// ```
+    pub fn modal_families() -> Vec<Vec<Scale>> {
+        let mut families = Vec::new();
+        let mut seen = vec![false; 0x1000];
+        for scale in Scale::all() {
+            if seen[scale.number() as usize] {
+                continue;
+            }
+            let modes = scale.modes();
+            for mode in &modes {
+                seen[mode.number() as usize] = true;
+            }
+            families.push(modes);
+        }
+        families
+    }
+}
+
+/// Returns the pitch class of a note, from 0 for C to 11 for B.
+pub(crate) fn pitch_class(note: &Note) -> i32 {
+    note.to_chromatic_scale_degree().rem_euclid(12)
+}
+
+/// Returns the set of pitch classes of the notes, with bit n set for pitch class n.
+pub(crate) fn pitch_class_set(notes: &[Note]) -> u16 {
+    notes.iter().fold(0, |set, note| set | 1 << pitch_class(note))
+}
+
+/// Returns a set of pitch classes transposed up by the given number of semitones,
+/// from 0 to 11.
+pub(crate) fn rotate_set(set: u16, semitones: u32) -> u16 {
+    ((set << semitones) | (set >> (12 - semitones))) & 0xfff
+}
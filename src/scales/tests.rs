@@ -0,0 +1,219 @@
+#[cfg(test)]
+mod tests {
+    use super::super::{types::utils::ALL_SCALES, *};
+    use crate::{error::ScaleError, melodies::Melody};
+
+    #[test]
+    fn test_catalog_spans_an_octave() {
+        for (name, scale) in ALL_SCALES.iter().flat_map(|scales| scales.iter()) {
+            assert_eq!(scale().to_steps().iter().sum::<i32>(), 12, "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_names_round_trip() {
+        for (name, _) in ALL_SCALES.iter().flat_map(|scales| scales.iter()) {
+            let parsed = name.to_string().parse::<ScaleEnumType>().unwrap();
+            // Mode roots share their names with the scale types they come from.
+            assert_eq!(parsed.to_scale(), name.to_scale(), "{}", name);
+            for alias in name.aliases() {
+                assert_eq!(alias.parse::<ScaleEnumType>().unwrap(), *name, "{}", alias);
+            }
+        }
+    }
+
+    #[test]
+    fn test_lookup() {
+        let altered = ScaleEnumType::from(MelodicMinorMode::Altered);
+        for name in ["Altered", "SUPER LOCRIAN", "super-locrian", "Diminished Whole Tone"] {
+            assert_eq!(name.parse::<ScaleEnumType>().unwrap(), altered);
+        }
+        assert_eq!(
+            "locrian natural 6".parse::<ScaleEnumType>().unwrap(),
+            HarmonicMinorMode::LocrianNat6.into()
+        );
+        assert_eq!(
+            "Locrian natural 2".parse::<ScaleEnumType>(),
+            "locrian ♮2".parse::<ScaleEnumType>()
+        );
+        assert_eq!(
+            "Phrygian ♭4".parse::<ScaleEnumType>().unwrap(),
+            HarmonicMajorMode::PhrygianFlat4.into()
+        );
+        assert_eq!("hexatonic".parse::<Scale>().unwrap().to_steps(), vec![3, 1, 3, 1, 3, 1]);
+        assert!("Locrian #9".parse::<ScaleEnumType>().is_err());
+    }
+
+    #[test]
+    fn test_identification() {
+        let scale = scale!("E F G# A B C D# E").unwrap();
+        assert_eq!(scale.get_known_scale_type(), Some(ScaleType::DoubleHarmonic.into()));
+        let scale = scale!("C D E F G Ab B C").unwrap();
+        assert_eq!(scale.get_known_scale_type(), Some(ScaleType::HarmonicMajor.into()));
+        let scale = scale!("C D# E F# G# A B C").unwrap();
+        assert_eq!(
+            scale.get_known_scale_type(),
+            Some(HarmonicMajorMode::LydianAugmentedSharp2.into())
+        );
+        let scale = Scale::from_name("Hirajoshi").unwrap();
+        assert_eq!(scale.rotated(1).get_known_scale_type(), Some(ScaleType::Iwato.into()));
+    }
+
+    #[test]
+    fn test_scale_numbers() {
+        for (name, scale) in ALL_SCALES.iter().flat_map(|scales| scales.iter()) {
+            let scale = scale();
+            assert_eq!(Scale::from_number(scale.number()).unwrap(), scale, "{}", name);
+        }
+        let numbers = Scale::all().iter().map(|scale| scale.number()).collect::<Vec<_>>();
+        assert_eq!(numbers, (1..4096).step_by(2).collect::<Vec<u16>>());
+        assert_eq!(Scale::from_number(1).unwrap().to_steps(), vec![12]);
+        assert_eq!(Scale::whole_tone().number(), 1365);
+        assert_eq!(Scale::harmonic_minor().number(), 2477);
+    }
+
+    #[test]
+    fn test_modal_families() {
+        for family in Scale::modal_families() {
+            let prime = family[0].number();
+            for mode in &family {
+                assert_eq!(mode.prime_mode().number(), prime);
+                assert_eq!(mode.cardinality(), family[0].cardinality());
+            }
+            assert_eq!(family[0].cardinality() % family.len(), 0);
+        }
+        let heptatonic = Scale::modal_families()
+            .into_iter()
+            .filter(|family| family[0].cardinality() == 7)
+            .filter(|family| family[0].max_step() <= 3)
+            .filter(|family| !family[0].has_consecutive_semitones())
+            .collect::<Vec<_>>();
+        assert_eq!(heptatonic.len(), 6);
+        // Major, melodic minor, harmonic minor and harmonic major, but not the two
+        // families built from the diminished scale with one note left out
+        let known = heptatonic
+            .iter()
+            .filter(|family| family.iter().all(|mode| mode.get_known_scale_type().is_some()))
+            .count();
+        assert_eq!(known, 4);
+    }
+
+    #[test]
+    fn test_containing() {
+        let melody = "A4:1/4 B4:1/4 C5:1/4 D5:1/4 E5:1/4 G#4:1/2".parse::<Melody>().unwrap();
+        let notes = melody.notes().into_iter().map(Note::from).collect::<Vec<_>>();
+        let matches = Scale::containing(&notes);
+        assert_eq!(matches[0].root(), note!("A").unwrap());
+        assert_eq!(matches[0].scale_type(), ScaleType::HarmonicMinor.into());
+        assert!(notes.iter().all(|note| matches[0].notes().contains(note)));
+        assert!(matches.iter().all(|m| m.extra_notes().len() + 6 == m.scale().cardinality()));
+        assert!(Scale::containing(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_distances() {
+        let (c, e_flat) = (note!("C").unwrap(), note!("Eb").unwrap());
+        let (major, minor) = (Scale::major(), Scale::minor());
+        assert_eq!(major.hamming_distance(e_flat, &minor, c), 0);
+        assert_eq!(major.common_tones(e_flat, &minor, c), 7);
+        assert_eq!(major.voice_leading_distance(e_flat, &minor, c), Some(0));
+        for root in ["D", "E", "F#", "Bb"].map(|note| note.parse::<Note>().unwrap()) {
+            assert_eq!(
+                major.voice_leading_distance(c, &minor, root),
+                minor.voice_leading_distance(root, &major, c)
+            );
+            assert_eq!(
+                major.hamming_distance(c, &minor, root) + 2 * major.common_tones(c, &minor, root),
+                14
+            );
+        }
+    }
+
+    #[test]
+    fn test_degrees() {
+        for text in ["1", "♭3", "♯4", "♭♭7", "𝄪5", "♭9", "♯11", "13"] {
+            let degree = text.parse::<ScaleDegree>().unwrap();
+            assert_eq!(degree.to_string(), text);
+            assert_eq!(degree.to_string().parse::<ScaleDegree>().unwrap(), degree);
+        }
+        let flat_three = ScaleDegree::new(3, Accidental::Flat(1)).unwrap();
+        assert_eq!("b3".parse::<ScaleDegree>().unwrap(), flat_three);
+        assert_eq!(" b3 ".parse::<ScaleDegree>().unwrap(), flat_three);
+        assert_eq!(ScaleDegree::new(5, Accidental::Sharp(0)).unwrap().to_string(), "5");
+        assert!(!flat_three.is_diatonic());
+
+        assert_eq!("0".parse::<ScaleDegree>(), Err(ScaleError::InvalidScaleDegree(0).into()));
+        for text in ["", "b", "3b", "256"] {
+            assert_eq!(
+                text.parse::<ScaleDegree>(),
+                Err(ScaleError::InvalidScaleDegreeFormat(text.to_string()).into()),
+                "{}",
+                text
+            );
+        }
+        for text in ["?3", "three", "b-3"] {
+            assert!(text.parse::<ScaleDegree>().is_err(), "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_degree_names() {
+        let degree = |s: &str| s.parse::<ScaleDegree>().unwrap();
+        let names = ["1", "2", "3", "4", "5", "6", "7"]
+            .map(|d| Scale::major().degree_name(degree(d)).unwrap().to_string());
+        assert_eq!(
+            names,
+            [
+                "tonic",
+                "supertonic",
+                "mediant",
+                "subdominant",
+                "dominant",
+                "submediant",
+                "leading tone"
+            ]
+        );
+
+        let (minor, harmonic) = (Scale::minor(), Scale::harmonic_minor());
+        assert_eq!(minor.degree_name(degree("7")), Some(DegreeName::Subtonic));
+        assert_eq!(harmonic.degree_name(degree("7")), Some(DegreeName::LeadingTone));
+        assert_eq!(harmonic.degree_name(degree("b7")), Some(DegreeName::Subtonic));
+        assert_eq!(Scale::major().degree_name(degree("bb7")), None);
+        assert_eq!(Scale::major().degree_name(degree("b3")), None);
+        assert_eq!(Scale::major().degree_name(degree("9")), None);
+        assert_eq!(Scale::major_pentatonic().degree_name(degree("1")), None);
+    }
+
+    #[test]
+    fn test_solfege() {
+        use Solfege::*;
+        let syllables = [Do, Di, Ra, Re, Ri, Me, Mi, Fa, Fi, Se, Sol, Si, Le, La, Li, Te, Ti];
+        let names = syllables.map(|syllable| syllable.to_string());
+        assert_eq!(names[..6], ["do", "di", "ra", "re", "ri", "me"]);
+
+        // Every syllable, altered or not, is found again on its degree in any key.
+        for tonic in ["C", "Eb", "F#", "Bb"].map(|note| note.parse::<Note>().unwrap()) {
+            for syllable in syllables {
+                let note = Scale::major().note_at(syllable.to_degree(), tonic);
+                assert_eq!(Solfege::movable(note, tonic), Some(syllable), "{} in {}", note, tonic);
+            }
+        }
+        assert_eq!(Di.to_degree().to_string(), "♯1");
+        assert_eq!(Ra.to_degree().to_string(), "♭2");
+        assert_eq!(Se.to_degree().to_string(), "♭5");
+        assert_eq!(Te.to_degree().to_string(), "♭7");
+
+        // Enharmonic spellings take the syllable of their name.
+        let c = note!("C").unwrap();
+        assert_eq!(Solfege::movable(note!("C#").unwrap(), c), Some(Di));
+        assert_eq!(Solfege::movable(note!("Db").unwrap(), c), Some(Ra));
+        assert_eq!(Solfege::fixed(note!("G#").unwrap()), Some(Si));
+        assert_eq!(Solfege::fixed(note!("Ab").unwrap()), Some(Le));
+
+        // Raised thirds and sevenths, lowered tonics and fourths, and double
+        // alterations have no syllable.
+        for note in ["E#", "B#", "Cb", "Fb", "D##", "Ebb"] {
+            assert_eq!(Solfege::movable(note.parse().unwrap(), c), None, "{}", note);
+        }
+    }
+}
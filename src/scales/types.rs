@@ -1,3 +1,4 @@
+pub use HarmonicMajorMode::*;
 pub use HarmonicMinorMode::*;
 pub use MajorMode::*;
 pub use MelodicMinorMode::*;
@@ -6,6 +7,9 @@ pub use ScaleType::*;
 pub mod utils;
 
 use super::Scale;
+use crate::error::ResonataError;
+
+type Result<T> = std::result::Result<T, ResonataError>;
 
 impl Scale {
     /// Creates a major scale
@@ -28,6 +32,11 @@ impl Scale {
         Self::from_steps(MelodicMinor.as_steps()).unwrap()
     }
 
+    /// Creates a harmonic major scale
+    pub fn harmonic_major() -> Self {
+        Self::from_steps(HarmonicMajor.as_steps()).unwrap()
+    }
+
     /// Creates a major pentatonic scale
     pub fn major_pentatonic() -> Self {
         Self::from_steps(MajorPentatonic.as_steps()).unwrap()
@@ -63,6 +72,22 @@ impl Scale {
         Self::from_steps(Chromatic.as_steps()).unwrap()
     }
 
+    /// Creates a scale from its name or one of its aliases, ignoring case, spaces
+    /// and hyphens.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::{notes::*, scales::*};
+    ///
+    /// assert_eq!(Scale::from_name("dorian").unwrap(), Scale::major().rotated(1));
+    /// assert_eq!(Scale::from_name("Super Locrian").unwrap(), Scale::from_name("altered").unwrap());
+    /// assert_eq!(Scale::from_name("Byzantine").unwrap(), scale!(ScaleType::DoubleHarmonic));
+    /// assert!(Scale::from_name("Resonata").is_err());
+    /// ```
+    pub fn from_name(name: &str) -> Result<Self> {
+        Ok(name.parse::<ScaleEnumType>()?.to_scale())
+    }
+
     /// Returns the parent scale of the scale if it matches a known scale type or mode.
     ///
     /// ### Examples
@@ -127,12 +152,30 @@ impl Scale {
     }
 }
 
+/// A scale type or mode from the catalog of known scales.
+///
+/// Each scale has a name, given by its `Display` implementation, and may have aliases.
+/// Parsing looks up names and aliases, ignoring case, spaces and hyphens, and accepts
+/// ASCII accidentals in place of ♯, ♭ and ♮.
+///
+/// ### Examples
+/// ```
+/// use resonata::scales::*;
+///
+/// let altered: ScaleEnumType = MelodicMinorMode::Altered.into();
+/// assert_eq!("super locrian".parse::<ScaleEnumType>().unwrap(), altered);
+/// assert_eq!("Diminished Whole-Tone".parse::<ScaleEnumType>().unwrap(), altered);
+/// assert_eq!("Lydian b7".parse::<ScaleEnumType>().unwrap(), MelodicMinorMode::LydianDominant.into());
+/// assert_eq!(altered.to_string(), "Altered");
+/// assert!(altered.aliases().contains(&"Super Locrian"));
+/// ```
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum ScaleEnumType {
     ScaleType(ScaleType),
     MajorMode(MajorMode),
     HarmonicMinorMode(HarmonicMinorMode),
     MelodicMinorMode(MelodicMinorMode),
+    HarmonicMajorMode(HarmonicMajorMode),
 }
 
 /// A non-exhaustive list of musical scales
@@ -148,7 +191,32 @@ pub enum ScaleType {
     MajorBlues,
     WholeTone,
     Diminished,
+    DominantDiminished,
     Chromatic,
+    MajorBebop,
+    DominantBebop,
+    DorianBebop,
+    MelodicMinorBebop,
+    HarmonicMajor,
+    DoubleHarmonic,
+    HungarianMinor,
+    NeapolitanMajor,
+    NeapolitanMinor,
+    Enigmatic,
+    Persian,
+    Hirajoshi,
+    In,
+    Yo,
+    Iwato,
+    Kumoi,
+    Augmented,
+    Prometheus,
+    Tritone,
+    MessiaenMode3,
+    MessiaenMode4,
+    MessiaenMode5,
+    MessiaenMode6,
+    MessiaenMode7,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
@@ -173,6 +241,17 @@ pub enum HarmonicMinorMode {
     SuperLocrian,
 }
 
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum HarmonicMajorMode {
+    HarmonicMajorRoot = 0,
+    DorianFlat5,
+    PhrygianFlat4,
+    LydianFlat3,
+    MixolydianFlat2,
+    LydianAugmentedSharp2,
+    LocrianDoubleFlat7,
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum MelodicMinorMode {
     MelodicMinorRoot = 0,
@@ -1,5 +1,9 @@
 use super::*;
-use std::fmt::{self, Display, Formatter};
+use crate::{error::ScaleError, scale};
+use std::{
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
 
 type NamedScale = (ScaleEnumType, fn() -> Scale);
 
@@ -20,7 +24,32 @@ pub static KNOWN_SCALES: &[NamedScale] = &[
     (as_enum!(ScaleType, MajorBlues), Scale::major_blues),
     (as_enum!(ScaleType, WholeTone), Scale::whole_tone),
     (as_enum!(ScaleType, Diminished), Scale::diminished),
+    (as_enum!(ScaleType, DominantDiminished), || Scale::diminished().rotated(1)),
     (as_enum!(ScaleType, Chromatic), Scale::chromatic),
+    (as_enum!(ScaleType, MajorBebop), || scale!(MajorBebop)),
+    (as_enum!(ScaleType, DominantBebop), || scale!(DominantBebop)),
+    (as_enum!(ScaleType, DorianBebop), || scale!(DorianBebop)),
+    (as_enum!(ScaleType, MelodicMinorBebop), || scale!(MelodicMinorBebop)),
+    (as_enum!(ScaleType, HarmonicMajor), Scale::harmonic_major),
+    (as_enum!(ScaleType, DoubleHarmonic), || scale!(DoubleHarmonic)),
+    (as_enum!(ScaleType, HungarianMinor), || scale!(HungarianMinor)),
+    (as_enum!(ScaleType, NeapolitanMajor), || scale!(NeapolitanMajor)),
+    (as_enum!(ScaleType, NeapolitanMinor), || scale!(NeapolitanMinor)),
+    (as_enum!(ScaleType, Enigmatic), || scale!(Enigmatic)),
+    (as_enum!(ScaleType, Persian), || scale!(Persian)),
+    (as_enum!(ScaleType, Hirajoshi), || scale!(Hirajoshi)),
+    (as_enum!(ScaleType, In), || scale!(In)),
+    (as_enum!(ScaleType, Yo), || scale!(Yo)),
+    (as_enum!(ScaleType, Iwato), || scale!(Iwato)),
+    (as_enum!(ScaleType, Kumoi), || scale!(Kumoi)),
+    (as_enum!(ScaleType, Augmented), || scale!(Augmented)),
+    (as_enum!(ScaleType, Prometheus), || scale!(Prometheus)),
+    (as_enum!(ScaleType, Tritone), || scale!(Tritone)),
+    (as_enum!(ScaleType, MessiaenMode3), || scale!(MessiaenMode3)),
+    (as_enum!(ScaleType, MessiaenMode4), || scale!(MessiaenMode4)),
+    (as_enum!(ScaleType, MessiaenMode5), || scale!(MessiaenMode5)),
+    (as_enum!(ScaleType, MessiaenMode6), || scale!(MessiaenMode6)),
+    (as_enum!(ScaleType, MessiaenMode7), || scale!(MessiaenMode7)),
 ];
 
 pub static MAJOR_MODES: &[NamedScale] = &[
@@ -53,9 +82,138 @@ pub static MELODIC_MINOR_MODES: &[NamedScale] = &[
     (as_enum!(MelodicMinorMode, Altered), || Scale::melodic_minor().rotated(6)),
 ];
 
+pub static HARMONIC_MAJOR_MODES: &[NamedScale] = &[
+    (as_enum!(HarmonicMajorMode, HarmonicMajorRoot), Scale::harmonic_major),
+    (as_enum!(HarmonicMajorMode, DorianFlat5), || Scale::harmonic_major().rotated(1)),
+    (as_enum!(HarmonicMajorMode, PhrygianFlat4), || Scale::harmonic_major().rotated(2)),
+    (as_enum!(HarmonicMajorMode, LydianFlat3), || Scale::harmonic_major().rotated(3)),
+    (as_enum!(HarmonicMajorMode, MixolydianFlat2), || Scale::harmonic_major().rotated(4)),
+    (as_enum!(HarmonicMajorMode, LydianAugmentedSharp2), || Scale::harmonic_major().rotated(5)),
+    (as_enum!(HarmonicMajorMode, LocrianDoubleFlat7), || Scale::harmonic_major().rotated(6)),
+];
+
 // The list of all things to check
 pub static ALL_SCALES: &[&[NamedScale]] =
-    &[KNOWN_SCALES, MAJOR_MODES, HARMONIC_MINOR_MODES, MELODIC_MINOR_MODES];
+    &[KNOWN_SCALES, MAJOR_MODES, HARMONIC_MINOR_MODES, MELODIC_MINOR_MODES, HARMONIC_MAJOR_MODES];
+
+/// Alternative names for scales, in addition to the names given by `Display`.
+static ALIASES: &[(ScaleEnumType, &[&str])] = &[
+    (as_enum!(ScaleType, Minor), &["Natural Minor"]),
+    (as_enum!(ScaleType, MelodicMinor), &["Jazz Minor", "Ascending Melodic Minor"]),
+    (as_enum!(ScaleType, MinorBlues), &["Blues"]),
+    (as_enum!(ScaleType, WholeTone), &["Messiaen Mode 1"]),
+    (as_enum!(ScaleType, Diminished), &["Whole-Half Diminished", "Octatonic", "Messiaen Mode 2"]),
+    (as_enum!(ScaleType, DominantDiminished), &["Half-Whole Diminished"]),
+    (as_enum!(ScaleType, MajorBebop), &["Bebop Major"]),
+    (as_enum!(ScaleType, DominantBebop), &["Bebop Dominant", "Bebop", "Mixolydian Bebop"]),
+    (as_enum!(ScaleType, DorianBebop), &["Bebop Dorian", "Minor Bebop"]),
+    (as_enum!(ScaleType, MelodicMinorBebop), &["Bebop Melodic Minor"]),
+    (
+        as_enum!(ScaleType, DoubleHarmonic),
+        &["Double Harmonic Major", "Byzantine", "Arabic", "Gypsy Major"],
+    ),
+    (as_enum!(ScaleType, HungarianMinor), &["Gypsy Minor", "Double Harmonic Minor"]),
+    (as_enum!(ScaleType, In), &["Miyako-bushi"]),
+    (as_enum!(ScaleType, Kumoi), &["Kumoijoshi"]),
+    (as_enum!(ScaleType, Augmented), &["Hexatonic", "Augmented Hexatonic"]),
+    (as_enum!(ScaleType, Prometheus), &["Mystic"]),
+    (as_enum!(ScaleType, Tritone), &["Petrushka"]),
+    (as_enum!(HarmonicMinorMode, IonianAugmented), &["Ionian ♯5"]),
+    (
+        as_enum!(HarmonicMinorMode, DorianSharp4),
+        &["Ukrainian Dorian", "Romanian Minor", "Altered Dorian"],
+    ),
+    (
+        as_enum!(HarmonicMinorMode, PhrygianDominant),
+        &["Spanish Phrygian", "Freygish", "Phrygian Major"],
+    ),
+    (as_enum!(HarmonicMinorMode, SuperLocrian), &["Super Locrian 𝄫7", "Altered 𝄫7"]),
+    (as_enum!(MelodicMinorMode, DorianFlat2), &["Phrygian ♮6", "Phrygidorian"]),
+    (as_enum!(MelodicMinorMode, LydianAugmented), &["Lydian ♯5"]),
+    (
+        as_enum!(MelodicMinorMode, LydianDominant),
+        &["Overtone", "Acoustic", "Lydian ♭7", "Mixolydian ♯4"],
+    ),
+    (as_enum!(MelodicMinorMode, AeolianDominant), &["Mixolydian ♭6", "Hindu"]),
+    (as_enum!(MelodicMinorMode, HalfDiminished), &["Locrian ♮2", "Aeolian ♭5"]),
+    (
+        as_enum!(MelodicMinorMode, Altered),
+        &["Super Locrian", "Diminished Whole Tone", "Altered Dominant"],
+    ),
+    (as_enum!(HarmonicMajorMode, DorianFlat5), &["Locrian ♮2 ♮6"]),
+    (as_enum!(HarmonicMajorMode, PhrygianFlat4), &["Altered Dominant ♮5"]),
+    (as_enum!(HarmonicMajorMode, LydianFlat3), &["Melodic Minor ♯4", "Lydian Diminished"]),
+];
+
+/// Reduces a scale name to a form for comparison, ignoring case, spacing and
+/// the spelling of accidentals.
+fn normalize_name(name: &str) -> String {
+    name.to_lowercase()
+        .replace('𝄫', "bb")
+        .replace('♭', "b")
+        .replace('♯', "#")
+        .replace('♮', "nat")
+        .replace("natural", "nat")
+        .replace("sharp", "#")
+        .replace("flat", "b")
+        .chars()
+        .filter(|c| !matches!(c, ' ' | '-' | '_' | '(' | ')' | '.'))
+        .collect()
+}
+
+impl ScaleEnumType {
+    /// Returns the scale of this type.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::scales::*;
+    ///
+    /// let dorian: ScaleEnumType = MajorMode::Dorian.into();
+    /// assert_eq!(dorian.to_scale(), Scale::major().rotated(1));
+    /// ```
+    pub fn to_scale(&self) -> Scale {
+        let (_, scale) = ALL_SCALES
+            .iter()
+            .flat_map(|scales| scales.iter())
+            .find(|(name, _)| name == self)
+            .unwrap();
+        scale()
+    }
+
+    /// Returns the alternative names of the scale, not including its name.
+    pub fn aliases(&self) -> &'static [&'static str] {
+        ALIASES.iter().find(|(name, _)| name == self).map_or(&[], |(_, aliases)| aliases)
+    }
+}
+
+impl FromStr for ScaleEnumType {
+    type Err = ResonataError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let name = normalize_name(s);
+        ALL_SCALES
+            .iter()
+            .flat_map(|scales| scales.iter())
+            .map(|(scale_type, _)| *scale_type)
+            .find(|scale_type| {
+                normalize_name(&scale_type.to_string()) == name
+                    || scale_type.aliases().iter().any(|alias| normalize_name(alias) == name)
+            })
+            .ok_or_else(|| ScaleError::UnknownScaleName(s.to_string()).into())
+    }
+}
+
+impl Display for ScaleEnumType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ScaleEnumType::ScaleType(scale_type) => write!(f, "{}", scale_type),
+            ScaleEnumType::MajorMode(mode) => write!(f, "{}", mode),
+            ScaleEnumType::HarmonicMinorMode(mode) => write!(f, "{}", mode),
+            ScaleEnumType::MelodicMinorMode(mode) => write!(f, "{}", mode),
+            ScaleEnumType::HarmonicMajorMode(mode) => write!(f, "{}", mode),
+        }
+    }
+}
 
 impl Display for ScaleType {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
@@ -70,7 +228,86 @@ impl Display for ScaleType {
             MajorBlues => "Major Blues",
             WholeTone => "Whole Tone",
             Diminished => "Diminished",
+            DominantDiminished => "Dominant Diminished",
             Chromatic => "Chromatic",
+            MajorBebop => "Major Bebop",
+            DominantBebop => "Dominant Bebop",
+            DorianBebop => "Dorian Bebop",
+            MelodicMinorBebop => "Melodic Minor Bebop",
+            HarmonicMajor => "Harmonic Major",
+            DoubleHarmonic => "Double Harmonic",
+            HungarianMinor => "Hungarian Minor",
+            NeapolitanMajor => "Neapolitan Major",
+            NeapolitanMinor => "Neapolitan Minor",
+            Enigmatic => "Enigmatic",
+            Persian => "Persian",
+            Hirajoshi => "Hirajoshi",
+            In => "In",
+            Yo => "Yo",
+            Iwato => "Iwato",
+            Kumoi => "Kumoi",
+            Augmented => "Augmented",
+            Prometheus => "Prometheus",
+            Tritone => "Tritone",
+            MessiaenMode3 => "Messiaen Mode 3",
+            MessiaenMode4 => "Messiaen Mode 4",
+            MessiaenMode5 => "Messiaen Mode 5",
+            MessiaenMode6 => "Messiaen Mode 6",
+            MessiaenMode7 => "Messiaen Mode 7",
+        };
+
+        write!(f, "{}", token)
+    }
+}
+
+impl Display for MajorMode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl Display for HarmonicMinorMode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let token = match self {
+            HarmonicMinorRoot => "Harmonic Minor",
+            LocrianNat6 => "Locrian ♮6",
+            IonianAugmented => "Ionian Augmented",
+            DorianSharp4 => "Dorian ♯4",
+            PhrygianDominant => "Phrygian Dominant",
+            LydianSharp2 => "Lydian ♯2",
+            SuperLocrian => "Ultralocrian",
+        };
+
+        write!(f, "{}", token)
+    }
+}
+
+impl Display for MelodicMinorMode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let token = match self {
+            MelodicMinorRoot => "Melodic Minor",
+            DorianFlat2 => "Dorian ♭2",
+            LydianAugmented => "Lydian Augmented",
+            LydianDominant => "Lydian Dominant",
+            AeolianDominant => "Aeolian Dominant",
+            HalfDiminished => "Half Diminished",
+            Altered => "Altered",
+        };
+
+        write!(f, "{}", token)
+    }
+}
+
+impl Display for HarmonicMajorMode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let token = match self {
+            HarmonicMajorRoot => "Harmonic Major",
+            DorianFlat5 => "Dorian ♭5",
+            PhrygianFlat4 => "Phrygian ♭4",
+            LydianFlat3 => "Lydian ♭3",
+            MixolydianFlat2 => "Mixolydian ♭2",
+            LydianAugmentedSharp2 => "Lydian Augmented ♯2",
+            LocrianDoubleFlat7 => "Locrian 𝄫7",
         };
 
         write!(f, "{}", token)
@@ -90,7 +327,32 @@ impl ScaleType {
             MajorBlues => vec![2, 1, 1, 3, 2, 3],
             WholeTone => vec![2; 6],
             Diminished => vec![2, 1, 2, 1, 2, 1, 2, 1],
+            DominantDiminished => vec![1, 2, 1, 2, 1, 2, 1, 2],
             Chromatic => vec![1; 12],
+            MajorBebop => vec![2, 2, 1, 2, 1, 1, 2, 1],
+            DominantBebop => vec![2, 2, 1, 2, 2, 1, 1, 1],
+            DorianBebop => vec![2, 1, 1, 1, 2, 2, 1, 2],
+            MelodicMinorBebop => vec![2, 1, 2, 2, 1, 1, 2, 1],
+            HarmonicMajor => vec![2, 2, 1, 2, 1, 3, 1],
+            DoubleHarmonic => vec![1, 3, 1, 2, 1, 3, 1],
+            HungarianMinor => vec![2, 1, 3, 1, 1, 3, 1],
+            NeapolitanMajor => vec![1, 2, 2, 2, 2, 2, 1],
+            NeapolitanMinor => vec![1, 2, 2, 2, 1, 3, 1],
+            Enigmatic => vec![1, 3, 2, 2, 2, 1, 1],
+            Persian => vec![1, 3, 1, 1, 2, 3, 1],
+            Hirajoshi => vec![2, 1, 4, 1, 4],
+            In => vec![1, 4, 2, 1, 4],
+            Yo => vec![2, 3, 2, 2, 3],
+            Iwato => vec![1, 4, 1, 4, 2],
+            Kumoi => vec![2, 1, 4, 2, 3],
+            Augmented => vec![3, 1, 3, 1, 3, 1],
+            Prometheus => vec![2, 2, 2, 3, 1, 2],
+            Tritone => vec![1, 3, 2, 1, 3, 2],
+            MessiaenMode3 => vec![2, 1, 1, 2, 1, 1, 2, 1, 1],
+            MessiaenMode4 => vec![1, 1, 3, 1, 1, 1, 3, 1],
+            MessiaenMode5 => vec![1, 4, 1, 1, 4, 1],
+            MessiaenMode6 => vec![2, 2, 1, 1, 2, 2, 1, 1],
+            MessiaenMode7 => vec![1, 1, 1, 2, 1, 1, 1, 1, 2, 1],
         }
     }
 }
@@ -118,3 +380,11 @@ impl MelodicMinorMode {
         mode
     }
 }
+
+impl HarmonicMajorMode {
+    pub fn to_steps(&self) -> Vec<i32> {
+        let mut mode = ScaleType::HarmonicMajor.as_steps();
+        mode.rotate_left(*self as usize);
+        mode
+    }
+}
@@ -30,7 +30,11 @@ impl FromStr for Scale {
             Ok(notes) if notes.len() >= 2 => {
                 Ok(Scale::from_notes(notes))
             }
-            _ => nope!(InvalidScale),
+            // Finally, try looking the scale up by name
+            _ => match s.parse::<ScaleEnumType>() {
+                Ok(scale_type) => Ok(scale_type.to_scale()),
+                Err(_) => nope!(InvalidScale),
+            },
         }
     }
 }
@@ -94,3 +98,9 @@ impl From<MelodicMinorMode> for ScaleEnumType {
         ScaleEnumType::MelodicMinorMode(melodic_minor_mode)
     }
 }
+
+impl From<HarmonicMajorMode> for ScaleEnumType {
+    fn from(harmonic_major_mode: HarmonicMajorMode) -> Self {
+        ScaleEnumType::HarmonicMajorMode(harmonic_major_mode)
+    }
+}
@@ -0,0 +1,254 @@
+//! Serde support for the core types, enabled by the `serde` feature.
+//!
+//! By default each type is written as its canonical string form, using ASCII
+//! accidentals so that the output is easy to type and to read back:
+//!
+//! | Type            | Example                 |
+//! |-----------------|-------------------------|
+//! | `NoteName`      | `"C"`                   |
+//! | `Accidental`    | `"#"`, `"bb"`, `""`     |
+//! | `Note`          | `"C#"`                  |
+//! | `PitchedNote`   | `"C#4"`                 |
+//! | `Quality`       | `"M"`, `"dd"`           |
+//! | `Size`          | `"3"`, `"U"`            |
+//! | `Interval`      | `"M3"`, `"P8"`          |
+//! | `Scale`         | `"2 2 1 2 2 2 1"`       |
+//! | `Key`           | `"C D E F# G A B"`      |
+//! | `Duration`      | `"3/8"`                 |
+//! | `Tempo`         | `"1/4=120"`             |
+//! | `Event`         | `"C#4:1/8"`, `"r:1/4"`  |
+//! | `TimeSignature` | `"6/8"`, `"2+2+3/8"`    |
+//! | `Melody`        | `"C4:1/4 r:1/8 D4:1/8"` |
+//! | `ScaleDegree`   | `"b3"`, `"#4"`, `"5"`   |
+//! | `Chord`         | `"Bbmaj7#11/D"`         |
+//! | `ChordFunction` | `"V/ii"`, `"bVII"`      |
+//! | `Tuning`        | `"D2 A2 D3 G3 B3 E4"`   |
+//!
+//! A structured form, with one field per component, is available through the
+//! [structured] module for use with `#[serde(with = "...")]`.
+//!
+//! A trained [MarkovChain] is written as its order, openings and transitions, with its
+//! states in their own forms.
+use crate::{
+    chords::{Chord, ChordFunction},
+    durations::{Duration, Event, Tempo},
+    fretboards::Tuning,
+    generators::{MarkovChain, Transitions},
+    intervals::*,
+    keys::Key,
+    melodies::Melody,
+    notes::*,
+    scales::{Scale, ScaleDegree},
+    time_signatures::TimeSignature,
+};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+pub mod structured;
+mod tests;
+
+/// Returns the canonical string form of a value, which its `FromStr` implementation reads back.
+trait Canonical {
+    fn canonical(&self) -> String;
+}
+
+impl Canonical for NoteName {
+    fn canonical(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl Canonical for Accidental {
+    fn canonical(&self) -> String {
+        match self {
+            Accidental::Flat(n) => "b".repeat(*n as usize),
+            Accidental::Natural => String::new(),
+            Accidental::Sharp(n) => "#".repeat(*n as usize),
+        }
+    }
+}
+
+impl Canonical for Note {
+    fn canonical(&self) -> String {
+        format!("{}{}", self.name().canonical(), self.accidental().canonical())
+    }
+}
+
+impl Canonical for PitchedNote {
+    fn canonical(&self) -> String {
+        format!("{}{}{}", self.name().canonical(), self.accidental().canonical(), self.octave())
+    }
+}
+
+impl Canonical for Quality {
+    fn canonical(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl Canonical for Size {
+    fn canonical(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl Canonical for Interval {
+    fn canonical(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl Canonical for Scale {
+    fn canonical(&self) -> String {
+        self.to_steps().iter().map(|step| step.to_string()).collect::<Vec<_>>().join(" ")
+    }
+}
+
+impl Canonical for Key {
+    fn canonical(&self) -> String {
+        self.pitches().iter().map(|note| note.canonical()).collect::<Vec<_>>().join(" ")
+    }
+}
+
+impl Canonical for Duration {
+    fn canonical(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl Canonical for Tempo {
+    fn canonical(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl Canonical for ScaleDegree {
+    fn canonical(&self) -> String {
+        format!("{}{}", self.accidental().canonical(), self.number())
+    }
+}
+
+impl Canonical for Melody {
+    fn canonical(&self) -> String {
+        self.events().iter().map(|event| event.canonical()).collect::<Vec<_>>().join(" ")
+    }
+}
+
+impl Canonical for TimeSignature {
+    fn canonical(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl Canonical for Chord {
+    fn canonical(&self) -> String {
+        ascii(&self.to_string())
+    }
+}
+
+impl Canonical for ChordFunction {
+    fn canonical(&self) -> String {
+        ascii(&self.to_string())
+    }
+}
+
+impl Canonical for Tuning {
+    fn canonical(&self) -> String {
+        self.strings().iter().map(|pitch| pitch.canonical()).collect::<Vec<_>>().join(" ")
+    }
+}
+
+/// Replaces the accidentals of a display string with ASCII ones.
+fn ascii(s: &str) -> String {
+    s.replace('♭', "b").replace('♯', "#").replace('𝄪', "x")
+}
+
+impl Canonical for Event {
+    fn canonical(&self) -> String {
+        match self {
+            Event::Note(note, duration) => format!("{}:{}", note.canonical(), duration),
+            Event::Rest(duration) => format!("r:{}", duration),
+        }
+    }
+}
+
+/// Implements `Serialize` and `Deserialize` using the canonical string form of a type.
+macro_rules! serde_as_string {
+    ($($type:ty),*) => {
+        $(
+            impl Serialize for $type {
+                fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    serializer.serialize_str(&self.canonical())
+                }
+            }
+
+            impl<'de> Deserialize<'de> for $type {
+                fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                    let s = String::deserialize(deserializer)?;
+                    s.parse::<$type>().map_err(de::Error::custom)
+                }
+            }
+        )*
+    };
+}
+
+serde_as_string!(
+    NoteName,
+    Note,
+    PitchedNote,
+    Quality,
+    Size,
+    Interval,
+    Scale,
+    Key,
+    Duration,
+    Tempo,
+    Event,
+    TimeSignature,
+    Melody,
+    ScaleDegree,
+    Chord,
+    ChordFunction,
+    Tuning
+);
+
+impl Serialize for Accidental {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.canonical())
+    }
+}
+
+impl<'de> Deserialize<'de> for Accidental {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        let accidental = s.parse::<Accidental>().map_err(de::Error::custom)?;
+        // An empty string is read as zero sharps, which is stored as a natural.
+        Ok(Accidental::from_semitones(accidental.to_semitones()))
+    }
+}
+
+/// The serde form of a Markov chain.
+#[derive(Serialize, Deserialize)]
+struct MarkovChainForm<T> {
+    order: usize,
+    openings: Vec<(Vec<T>, u32)>,
+    transitions: Transitions<T>,
+}
+
+impl<T: Serialize + Clone + PartialEq> Serialize for MarkovChain<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let form = MarkovChainForm {
+            order: self.order(),
+            openings: self.openings().clone(),
+            transitions: self.transitions().clone(),
+        };
+        form.serialize(serializer)
+    }
+}
+
+impl<'de, T: Deserialize<'de> + Clone + PartialEq> Deserialize<'de> for MarkovChain<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let form = MarkovChainForm::<T>::deserialize(deserializer)?;
+        MarkovChain::from_counts(form.order, form.openings, form.transitions)
+            .map_err(de::Error::custom)
+    }
+}
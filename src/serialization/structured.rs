@@ -0,0 +1,246 @@
+//! The structured serde form of the core types, for use with `#[serde(with = "...")]`.
+//!
+//! ### Examples
+//! ```
+//! use resonata::{notes::*, serialization::structured};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize, PartialEq, Debug)]
+//! struct Exercise {
+//!     #[serde(with = "structured")]
+//!     answer: PitchedNote,
+//! }
+//!
+//! let exercise = Exercise { answer: pnote!("Eb4").unwrap() };
+//! let json = serde_json::to_string(&exercise).unwrap();
+//! assert_eq!(json, r#"{"answer":{"name":"E","accidental":-1,"octave":4}}"#);
+//! assert_eq!(serde_json::from_str::<Exercise>(&json).unwrap(), exercise);
+//! ```
+use crate::{
+    durations::Duration,
+    error::{IntervalError, ResonataError},
+    intervals::*,
+    keys::Key,
+    notes::*,
+    scales::Scale,
+};
+use serde::{de, de::DeserializeOwned, Deserialize, Deserializer, Serialize, Serializer};
+
+type Result<T> = std::result::Result<T, ResonataError>;
+
+/// A type with a structured serde form.
+pub trait Structured: Sized {
+    type Form: Serialize + DeserializeOwned;
+
+    fn to_structured(&self) -> Self::Form;
+
+    fn from_structured(form: Self::Form) -> Result<Self>;
+}
+
+/// Serializes a value in its structured form.
+pub fn serialize<T: Structured, S: Serializer>(
+    value: &T,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    value.to_structured().serialize(serializer)
+}
+
+/// Deserializes a value from its structured form.
+pub fn deserialize<'de, T: Structured, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<T, D::Error> {
+    T::from_structured(T::Form::deserialize(deserializer)?).map_err(de::Error::custom)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NoteForm {
+    pub name: NoteName,
+    pub accidental: i32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PitchedNoteForm {
+    pub name: NoteName,
+    pub accidental: i32,
+    pub octave: i8,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QualityForm {
+    Diminished(u8),
+    Augmented(u8),
+    Minor,
+    Major,
+    Perfect,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IntervalForm {
+    pub quality: QualityForm,
+    pub size: u8,
+    pub octaves: u8,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScaleForm {
+    pub steps: Vec<i32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyForm {
+    pub pitches: Vec<NoteForm>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DurationForm {
+    pub numerator: u32,
+    pub denominator: u32,
+}
+
+impl Structured for NoteName {
+    type Form = NoteName;
+
+    fn to_structured(&self) -> Self::Form {
+        *self
+    }
+
+    fn from_structured(form: Self::Form) -> Result<Self> {
+        Ok(form)
+    }
+}
+
+impl Structured for Accidental {
+    type Form = i32;
+
+    fn to_structured(&self) -> Self::Form {
+        self.to_semitones()
+    }
+
+    fn from_structured(form: Self::Form) -> Result<Self> {
+        Ok(Accidental::from_semitones(form))
+    }
+}
+
+impl Structured for Note {
+    type Form = NoteForm;
+
+    fn to_structured(&self) -> Self::Form {
+        NoteForm { name: self.name(), accidental: self.accidental().to_semitones() }
+    }
+
+    fn from_structured(form: Self::Form) -> Result<Self> {
+        Ok(Note::new(form.name, Accidental::from_semitones(form.accidental)))
+    }
+}
+
+impl Structured for PitchedNote {
+    type Form = PitchedNoteForm;
+
+    fn to_structured(&self) -> Self::Form {
+        PitchedNoteForm {
+            name: self.name(),
+            accidental: self.accidental().to_semitones(),
+            octave: self.octave(),
+        }
+    }
+
+    fn from_structured(form: Self::Form) -> Result<Self> {
+        PitchedNote::new(form.name, Accidental::from_semitones(form.accidental), form.octave)
+    }
+}
+
+impl Structured for Quality {
+    type Form = QualityForm;
+
+    fn to_structured(&self) -> Self::Form {
+        match self {
+            Quality::Diminished(n) => QualityForm::Diminished(*n),
+            Quality::Augmented(n) => QualityForm::Augmented(*n),
+            Quality::Minor => QualityForm::Minor,
+            Quality::Major => QualityForm::Major,
+            Quality::Perfect => QualityForm::Perfect,
+        }
+    }
+
+    fn from_structured(form: Self::Form) -> Result<Self> {
+        Ok(match form {
+            QualityForm::Diminished(n) => Quality::Diminished(n),
+            QualityForm::Augmented(n) => Quality::Augmented(n),
+            QualityForm::Minor => Quality::Minor,
+            QualityForm::Major => Quality::Major,
+            QualityForm::Perfect => Quality::Perfect,
+        })
+    }
+}
+
+impl Structured for Size {
+    type Form = u8;
+
+    /// Sizes are written as their interval number, from 1 for a unison to 7 for a seventh.
+    fn to_structured(&self) -> Self::Form {
+        *self as u8 + 1
+    }
+
+    fn from_structured(form: Self::Form) -> Result<Self> {
+        match form {
+            1..=7 => Ok(Size::from(form - 1)),
+            _ => Err(IntervalError::InvalidIntervalSize(form as i16).into()),
+        }
+    }
+}
+
+impl Structured for Interval {
+    type Form = IntervalForm;
+
+    fn to_structured(&self) -> Self::Form {
+        IntervalForm {
+            quality: self.quality().to_structured(),
+            size: self.size().to_structured(),
+            octaves: self.octaves(),
+        }
+    }
+
+    fn from_structured(form: Self::Form) -> Result<Self> {
+        let quality = Quality::from_structured(form.quality)?;
+        let size = Size::from_structured(form.size)?;
+        Interval::build(quality, size, form.octaves)
+    }
+}
+
+impl Structured for Scale {
+    type Form = ScaleForm;
+
+    fn to_structured(&self) -> Self::Form {
+        ScaleForm { steps: self.to_steps() }
+    }
+
+    fn from_structured(form: Self::Form) -> Result<Self> {
+        Scale::from_steps(form.steps)
+    }
+}
+
+impl Structured for Key {
+    type Form = KeyForm;
+
+    fn to_structured(&self) -> Self::Form {
+        KeyForm { pitches: self.pitches().iter().map(|note| note.to_structured()).collect() }
+    }
+
+    fn from_structured(form: Self::Form) -> Result<Self> {
+        let notes = form.pitches.into_iter().map(Note::from_structured).collect::<Result<_>>()?;
+        Ok(Key::new(notes))
+    }
+}
+
+impl Structured for Duration {
+    type Form = DurationForm;
+
+    fn to_structured(&self) -> Self::Form {
+        DurationForm { numerator: self.numerator(), denominator: self.denominator() }
+    }
+
+    fn from_structured(form: Self::Form) -> Result<Self> {
+        Duration::new(form.numerator, form.denominator)
+    }
+}
@@ -0,0 +1,122 @@
+#[cfg(test)]
+mod tests {
+    use super::super::structured::{self, Structured};
+    use crate::{
+        chords::*, durations::*, fretboards::*, generators::*, intervals::*, keys::*, melodies::*,
+        notes::*, scales::*, time_signatures::*,
+    };
+    use serde::{Deserialize, Serialize};
+
+    fn round_trip<T>(value: T, json: &str)
+    where
+        T: Serialize + for<'de> Deserialize<'de> + PartialEq + std::fmt::Debug,
+    {
+        assert_eq!(serde_json::to_string(&value).unwrap(), json);
+        assert_eq!(serde_json::from_str::<T>(json).unwrap(), value);
+    }
+
+    fn structured_round_trip<T>(value: T, json: &str)
+    where
+        T: Structured + PartialEq + std::fmt::Debug,
+    {
+        let form = value.to_structured();
+        assert_eq!(serde_json::to_string(&form).unwrap(), json);
+        let form = serde_json::from_str::<T::Form>(json).unwrap();
+        assert_eq!(T::from_structured(form).unwrap(), value);
+    }
+
+    #[test]
+    fn test_canonical_strings() {
+        round_trip(NoteName::F, r#""F""#);
+        round_trip(Accidental::Sharp(2), r###""##""###);
+        round_trip(Accidental::Natural, r#""""#);
+        round_trip(note!("Bb").unwrap(), r#""Bb""#);
+        round_trip(pnote!("C#4").unwrap(), r##""C#4""##);
+        round_trip(pnote!("Gbb-1").unwrap(), r#""Gbb-1""#);
+        round_trip(Quality::Diminished(2), r#""dd""#);
+        round_trip(Size::Third, r#""3""#);
+        round_trip(inv!("M3").unwrap(), r#""M3""#);
+        round_trip(inv!("P8").unwrap(), r#""P8""#);
+        round_trip(inv!("A11").unwrap(), r#""A11""#);
+        round_trip(Scale::major(), r#""2 2 1 2 2 2 1""#);
+        round_trip(key!("F#").unwrap(), r##""C D E F# G A B""##);
+        round_trip(dur!(3, 8).unwrap(), r#""3/8""#);
+        round_trip(Tempo::quarter(120.0).unwrap(), r#""1/4=120""#);
+        round_trip(Event::Note(pnote!("F#3").unwrap(), Duration::eighth()), r##""F#3:1/8""##);
+        round_trip(Event::Rest(Duration::half()), r#""r:1/2""#);
+        round_trip(time!(6, 8).unwrap(), r#""6/8""#);
+        round_trip(time!("3+3+2/8").unwrap(), r#""3+3+2/8""#);
+        round_trip("♭3".parse::<ScaleDegree>().unwrap(), r#""b3""#);
+        round_trip(melody!("F#4:1/4 r:1/8 Bb3:1/8").unwrap(), r##""F#4:1/4 r:1/8 Bb3:1/8""##);
+        round_trip(chord!("B♭maj7♯11/D").unwrap(), r##""Bbmaj7#11/D""##);
+        round_trip(chord!("Cø7").unwrap(), r#""Cm7b5""#);
+        round_trip("♭VII".parse::<ChordFunction>().unwrap(), r#""bVII""#);
+        round_trip(tuning!("Eb2 Ab2 Db3 Gb3 Bb3 Eb4").unwrap(), r#""Eb2 Ab2 Db3 Gb3 Bb3 Eb4""#);
+    }
+
+    #[test]
+    fn test_unicode_input() {
+        let note: PitchedNote = serde_json::from_str(r#""C♯4""#).unwrap();
+        assert_eq!(note, pnote!("C#4").unwrap());
+        assert!(serde_json::from_str::<PitchedNote>(r#""H4""#).is_err());
+        assert!(serde_json::from_str::<Interval>(r#""P3""#).is_err());
+    }
+
+    #[test]
+    fn test_structured_forms() {
+        structured_round_trip(Accidental::Flat(2), "-2");
+        structured_round_trip(note!("F#").unwrap(), r#"{"name":"F","accidental":1}"#);
+        structured_round_trip(inv!("m10").unwrap(), r#"{"quality":"minor","size":3,"octaves":1}"#);
+        structured_round_trip(
+            inv!("AA4").unwrap(),
+            r#"{"quality":{"augmented":2},"size":4,"octaves":0}"#,
+        );
+        structured_round_trip(Scale::minor_pentatonic(), r#"{"steps":[3,2,2,3,2]}"#);
+        structured_round_trip(dur!(1, 12).unwrap(), r#"{"numerator":1,"denominator":12}"#);
+
+        let key = key!("Bb").unwrap();
+        let json = serde_json::to_string(&key.to_structured()).unwrap();
+        assert!(json.starts_with(r#"{"pitches":[{"name":"C","accidental":0}"#));
+        assert!(json.contains(r#"{"name":"B","accidental":-1}"#));
+    }
+
+    #[test]
+    fn test_markov_chain() {
+        let mut chain = MarkovChain::new(1).unwrap();
+        chain.train(&["I", "V", "I"].map(|s| s.parse::<ChordFunction>().unwrap()));
+        round_trip(
+            chain,
+            r#"{"order":1,"openings":[[["I"],1]],"transitions":[[["I"],[["V",1]]],[["V"],[["I",1]]]]}"#,
+        );
+        let json = r#"{"order":1,"openings":[[["I","V"],1]],"transitions":[]}"#;
+        assert!(serde_json::from_str::<MarkovChain<ChordFunction>>(json).is_err());
+        let json = r#"{"order":1,"openings":[[["I"],1]],"transitions":[[["I"],[]]]}"#;
+        assert!(serde_json::from_str::<MarkovChain<ChordFunction>>(json).is_err());
+    }
+
+    #[test]
+    fn test_with_attribute() {
+        #[derive(Serialize, Deserialize, PartialEq, Debug)]
+        struct Progress {
+            key: Key,
+            #[serde(with = "structured")]
+            target: Interval,
+            attempts: Vec<PitchedNote>,
+        }
+
+        let progress = Progress {
+            key: key!("Bb Eb").unwrap(),
+            target: inv!("P5").unwrap(),
+            attempts: vec![pnote!("F4").unwrap(), pnote!("Bb4").unwrap()],
+        };
+        let json = serde_json::to_string(&progress).unwrap();
+        assert_eq!(
+            json,
+            r#"{"key":"C D Eb F G A Bb","target":{"quality":"perfect","size":5,"octaves":0},"attempts":["F4","Bb4"]}"#
+        );
+        assert_eq!(serde_json::from_str::<Progress>(&json).unwrap(), progress);
+        assert!(
+            serde_json::from_str::<Progress>(&json.replace("\"size\":5", "\"size\":9")).is_err()
+        );
+    }
+}
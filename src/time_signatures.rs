@@ -0,0 +1,401 @@
+use crate::{
+    durations::{Duration, Event},
+    error::{ResonataError, TimeSignatureError},
+    nope,
+};
+
+pub use crate::time;
+
+mod tests;
+mod utils;
+
+type Result<T> = std::result::Result<T, ResonataError>;
+
+/// The metric weight of a position in a measure, from weakest to strongest.
+///
+/// The downbeat is the first beat of the measure. Strong beats are secondary accents,
+/// such as the third beat of 4/4, and weak beats are the remaining beats. A division
+/// is the start of a subdivision of a beat, such as the second eighth of a quarter note
+/// beat or any eighth of a dotted quarter beat, and anything else is an offbeat.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum BeatStrength {
+    Offbeat,
+    Division,
+    Weak,
+    Strong,
+    Downbeat,
+}
+
+/// A time signature
+///
+/// A time signature is represented by its denominator and a grouping of its numerator
+/// into beats. Simple meters such as 3/4 have one denominator unit per beat, compound
+/// meters such as 6/8 have beats of three units, and additive meters such as 2+2+3/8
+/// have beats of uneven lengths. Irrational meters, whose denominator is not a power of
+/// two, such as 4/3 or 3/10, are also supported.
+///
+/// A macro is provided to make creating time signatures easier:
+/// time!(string)
+/// time!(numerator, denominator)
+///
+/// ### Examples
+/// ```
+/// use resonata::{durations::*, time_signatures::*};
+///
+/// let time = time!(6, 8).unwrap();
+/// assert!(time.is_compound());
+/// assert_eq!(time.beats(), vec![dur!(3, 8).unwrap(), dur!(3, 8).unwrap()]);
+///
+/// let time = time!("2+2+3/8").unwrap();
+/// assert!(time.is_additive());
+/// assert_eq!(time.numerator(), 7);
+/// assert_eq!(time.measure_duration(), dur!(7, 8).unwrap());
+///
+/// let time = time!("C").unwrap();
+/// assert_eq!(time, time!(4, 4).unwrap());
+/// assert_eq!(time.strength_at(Duration::half()), BeatStrength::Strong);
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct TimeSignature {
+    groups: Vec<u32>,
+    denominator: u32,
+}
+
+/// A macro to create a time signature.
+#[macro_export]
+macro_rules! time {
+    ($str:literal) => {
+        $str.parse::<TimeSignature>()
+    };
+    ($numerator:expr, $denominator:expr) => {
+        TimeSignature::new($numerator, $denominator)
+    };
+}
+
+/// An event placed in a measure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MeasureEvent {
+    event: Event,
+    offset: Duration,
+    tied: bool,
+    beam: Option<usize>,
+}
+
+impl MeasureEvent {
+    /// Returns the event, whose duration is the part of it that falls in this measure.
+    pub fn event(&self) -> Event {
+        self.event
+    }
+
+    /// Returns the position of the event from the start of the measure.
+    pub fn offset(&self) -> Duration {
+        self.offset
+    }
+
+    /// Returns true if the note is tied to the next event, because it continues
+    /// over the barline.
+    pub fn is_tied(&self) -> bool {
+        self.tied
+    }
+
+    /// Returns the beam group of the event, counting from zero in each measure,
+    /// or `None` if the event is not beamed.
+    pub fn beam_group(&self) -> Option<usize> {
+        self.beam
+    }
+}
+
+/// A measure of events, as produced by `TimeSignature::measures`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Measure {
+    number: usize,
+    events: Vec<MeasureEvent>,
+}
+
+impl Measure {
+    /// Returns the number of the measure, counting from one.
+    pub fn number(&self) -> usize {
+        self.number
+    }
+
+    /// Returns the events in the measure.
+    pub fn events(&self) -> &Vec<MeasureEvent> {
+        &self.events
+    }
+
+    /// Returns the total duration of the events in the measure.
+    pub fn duration(&self) -> Duration {
+        self.events.iter().map(|event| event.event.duration()).sum()
+    }
+}
+
+impl TimeSignature {
+    /// Creates a time signature, grouping the numerator into beats in the usual way:
+    /// numerators divisible by three above three are compound, odd numerators over
+    /// eighths or shorter are grouped into twos and a final three, and everything else
+    /// has one beat per denominator unit.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::time_signatures::*;
+    ///
+    /// assert_eq!(TimeSignature::new(4, 4).unwrap().groups(), &vec![1, 1, 1, 1]);
+    /// assert_eq!(TimeSignature::new(12, 8).unwrap().groups(), &vec![3, 3, 3, 3]);
+    /// assert_eq!(TimeSignature::new(5, 8).unwrap().groups(), &vec![2, 3]);
+    /// assert!(TimeSignature::new(0, 4).is_err());
+    /// ```
+    pub fn new(numerator: u32, denominator: u32) -> Result<Self> {
+        if numerator == 0 || denominator == 0 {
+            nope!(TimeSignatureError::InvalidTimeSignature(format!(
+                "{}/{}",
+                numerator, denominator
+            )));
+        }
+
+        let groups = if numerator.is_multiple_of(3) && numerator > 3 {
+            vec![3; numerator as usize / 3]
+        } else if numerator % 2 == 1 && numerator > 3 && denominator >= 8 {
+            let mut groups = vec![2; (numerator as usize - 3) / 2];
+            groups.push(3);
+            groups
+        } else {
+            vec![1; numerator as usize]
+        };
+
+        Ok(TimeSignature { groups, denominator })
+    }
+
+    /// Creates a time signature with the given grouping of denominator units into beats.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::{durations::*, time_signatures::*};
+    ///
+    /// let time = TimeSignature::additive(vec![3, 3, 2], 8).unwrap();
+    /// assert_eq!(time.to_string(), "3+3+2/8");
+    /// assert_eq!(time.beats()[2], Duration::quarter());
+    ///
+    /// assert!(TimeSignature::additive(vec![], 8).is_err());
+    /// assert!(TimeSignature::additive(vec![2, 0], 8).is_err());
+    /// ```
+    pub fn additive(groups: Vec<u32>, denominator: u32) -> Result<Self> {
+        if groups.is_empty() || groups.contains(&0) || denominator == 0 {
+            nope!(TimeSignatureError::InvalidBeatGrouping(groups, denominator));
+        }
+        Ok(TimeSignature { groups, denominator })
+    }
+
+    /// Returns the numerator of the time signature.
+    pub fn numerator(&self) -> u32 {
+        self.groups.iter().sum()
+    }
+
+    /// Returns the denominator of the time signature.
+    pub fn denominator(&self) -> u32 {
+        self.denominator
+    }
+
+    /// Returns the grouping of denominator units into beats.
+    pub fn groups(&self) -> &Vec<u32> {
+        &self.groups
+    }
+
+    /// Returns the number of beats in a measure.
+    pub fn beat_count(&self) -> usize {
+        self.groups.len()
+    }
+
+    /// Returns the length of each beat in a measure.
+    pub fn beats(&self) -> Vec<Duration> {
+        self.groups.iter().map(|group| Duration::new(*group, self.denominator).unwrap()).collect()
+    }
+
+    /// Returns the length of a full measure.
+    pub fn measure_duration(&self) -> Duration {
+        Duration::new(self.numerator(), self.denominator).unwrap()
+    }
+
+    /// Returns true if every beat is a single denominator unit, as in 3/4.
+    pub fn is_simple(&self) -> bool {
+        self.groups.iter().all(|group| *group == 1)
+    }
+
+    /// Returns true if every beat is three denominator units, as in 6/8.
+    pub fn is_compound(&self) -> bool {
+        self.groups.len() > 1 && self.groups.iter().all(|group| *group == 3)
+    }
+
+    /// Returns true if the beats are of uneven lengths, as in 2+2+3/8.
+    pub fn is_additive(&self) -> bool {
+        self.groups.iter().any(|group| *group != self.groups[0])
+    }
+
+    /// Returns true if the denominator is not a power of two, as in 4/3.
+    pub fn is_irrational(&self) -> bool {
+        !self.denominator.is_power_of_two()
+    }
+
+    /// Returns the index of the beat at the given position in the measure, and the
+    /// offset of the position from the start of that beat. Positions past the end of
+    /// the measure wrap around into the next measure.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::{durations::*, time_signatures::*};
+    ///
+    /// let time = time!("2+3/8").unwrap();
+    /// assert_eq!(time.beat_at(dur!(3, 8).unwrap()), (1, Duration::eighth()));
+    /// assert_eq!(time.beat_at(dur!(5, 8).unwrap()), (0, Duration::zero()));
+    /// ```
+    pub fn beat_at(&self, position: Duration) -> (usize, Duration) {
+        let measure = self.measure_duration();
+        let (n, d) = position.ratio_to(&measure).unwrap();
+        let mut offset = position - measure.scaled(n / d, 1).unwrap();
+        for (index, beat) in self.beats().into_iter().enumerate() {
+            if offset < beat {
+                return (index, offset);
+            }
+            offset = offset - beat;
+        }
+        (0, offset)
+    }
+
+    /// Returns the metric weight of the given position in the measure.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::{durations::*, time_signatures::*};
+    ///
+    /// let time = time!(4, 4).unwrap();
+    /// assert_eq!(time.strength_at(Duration::zero()), BeatStrength::Downbeat);
+    /// assert_eq!(time.strength_at(Duration::quarter()), BeatStrength::Weak);
+    /// assert_eq!(time.strength_at(Duration::half()), BeatStrength::Strong);
+    /// assert_eq!(time.strength_at(Duration::eighth()), BeatStrength::Division);
+    /// assert_eq!(time.strength_at(Duration::sixteenth()), BeatStrength::Offbeat);
+    ///
+    /// let time = time!(6, 8).unwrap();
+    /// assert_eq!(time.strength_at(dur!(3, 8).unwrap()), BeatStrength::Weak);
+    /// assert_eq!(time.strength_at(Duration::quarter()), BeatStrength::Division);
+    /// ```
+    pub fn strength_at(&self, position: Duration) -> BeatStrength {
+        let (beat, offset) = self.beat_at(position);
+        if offset.is_zero() {
+            // Meters of four or more beats, in an even number, have a secondary
+            // accent halfway through the measure.
+            let count = self.beat_count();
+            return match beat {
+                0 => BeatStrength::Downbeat,
+                n if count > 2 && count.is_multiple_of(2) && n * 2 == count => BeatStrength::Strong,
+                _ => BeatStrength::Weak,
+            };
+        }
+
+        let unit = Duration::new(1, self.denominator).unwrap();
+        let division = match self.groups[beat] {
+            1 => unit.scaled(1, 2).unwrap(),
+            _ => unit,
+        };
+        match offset.ratio_to(&division).unwrap() {
+            (_, 1) => BeatStrength::Division,
+            _ => BeatStrength::Offbeat,
+        }
+    }
+
+    /// Splits a stream of events into measures. Notes that cross a barline are split
+    /// and tied into the next measure, and runs of notes shorter than a quarter note
+    /// within the same beat are beamed together. The last measure may be incomplete.
+    ///
+    /// ### Examples
+    /// ```
+    /// use resonata::{notes::*, durations::*, time_signatures::*};
+    ///
+    /// let c4 = pnote!("C4").unwrap();
+    /// let events = vec![
+    ///     Event::Note(c4, Duration::quarter()),
+    ///     Event::Note(c4, Duration::eighth()),
+    ///     Event::Note(c4, Duration::eighth()),
+    ///     Event::Note(c4, Duration::half()),
+    ///     Event::Rest(Duration::quarter()),
+    /// ];
+    ///
+    /// let measures = time!(3, 4).unwrap().measures(&events);
+    /// assert_eq!(measures.len(), 2);
+    ///
+    /// let first = measures[0].events();
+    /// assert_eq!(first.len(), 4);
+    /// assert_eq!(first[1].beam_group(), Some(0));
+    /// assert_eq!(first[2].beam_group(), Some(0));
+    /// assert_eq!(first[3].event(), Event::Note(c4, Duration::quarter()));
+    /// assert!(first[3].is_tied());
+    ///
+    /// let second = measures[1].events();
+    /// assert_eq!(second[0].event(), Event::Note(c4, Duration::quarter()));
+    /// assert!(!second[0].is_tied());
+    /// assert_eq!(measures[1].duration(), Duration::half());
+    /// ```
+    pub fn measures(&self, events: &[Event]) -> Vec<Measure> {
+        let measure_duration = self.measure_duration();
+        let mut measures = vec![Measure { number: 1, events: Vec::new() }];
+        let mut position = Duration::zero();
+
+        for event in events {
+            let mut remaining = event.duration();
+            loop {
+                if position == measure_duration {
+                    measures.push(Measure { number: measures.len() + 1, events: Vec::new() });
+                    position = Duration::zero();
+                }
+                let space = measure_duration - position;
+                let length = remaining.min(space);
+                remaining = remaining - length;
+                let tied = !remaining.is_zero() && !event.is_rest();
+                measures.last_mut().unwrap().events.push(MeasureEvent {
+                    event: event.with_duration(length),
+                    offset: position,
+                    tied,
+                    beam: None,
+                });
+                position += length;
+                if remaining.is_zero() {
+                    break;
+                }
+            }
+        }
+
+        for measure in &mut measures {
+            self.beam(measure);
+        }
+        measures
+    }
+
+    /// Assigns beam groups to runs of two or more notes shorter than a quarter note
+    /// that start in the same beat.
+    fn beam(&self, measure: &mut Measure) {
+        let mut group = 0;
+        let mut run: Vec<usize> = Vec::new();
+        let mut run_beat = None;
+
+        for index in 0..=measure.events.len() {
+            let beamable = measure.events.get(index).and_then(|event| {
+                let short = event.event.duration() < Duration::quarter();
+                let beat = self.beat_at(event.offset).0;
+                (short && !event.event.is_rest() && !event.event.duration().is_zero())
+                    .then_some(beat)
+            });
+
+            if beamable.is_none() || beamable != run_beat {
+                if run.len() > 1 {
+                    for i in &run {
+                        measure.events[*i].beam = Some(group);
+                    }
+                    group += 1;
+                }
+                run.clear();
+            }
+            run_beat = beamable;
+            if beamable.is_some() {
+                run.push(index);
+            }
+        }
+    }
+}
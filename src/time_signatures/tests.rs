@@ -0,0 +1,97 @@
+#[cfg(test)]
+mod tests {
+    use super::super::*;
+    use crate::{dur, notes::*, pnote};
+
+    #[test]
+    fn test_parse_and_display() {
+        let cases = [
+            ("4/4", "4/4", vec![1, 1, 1, 1]),
+            ("C", "4/4", vec![1, 1, 1, 1]),
+            ("C|", "2/2", vec![1, 1]),
+            ("3/8", "3/8", vec![1, 1, 1]),
+            ("9/8", "9/8", vec![3, 3, 3]),
+            ("7/8", "7/8", vec![2, 2, 3]),
+            ("3+2+2/8", "3+2+2/8", vec![3, 2, 2]),
+            ("3 + 3 + 2 / 8", "3+3+2/8", vec![3, 3, 2]),
+            ("4/3", "4/3", vec![1, 1, 1, 1]),
+        ];
+        for (input, output, groups) in cases {
+            let time = input.parse::<TimeSignature>().unwrap();
+            assert_eq!(time.to_string(), output);
+            assert_eq!(time.groups(), &groups);
+        }
+
+        for invalid in ["", "4", "4/", "/4", "0/4", "4/0", "2+/8", "2+0/8", "x/4"] {
+            assert!(invalid.parse::<TimeSignature>().is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_classification() {
+        let time = time!(3, 4).unwrap();
+        assert!(time.is_simple() && !time.is_compound() && !time.is_additive());
+        let time = time!(12, 8).unwrap();
+        assert!(time.is_compound() && !time.is_simple() && !time.is_additive());
+        let time = time!("2+2+3/8").unwrap();
+        assert!(time.is_additive() && !time.is_compound());
+        assert!(time!(3, 10).unwrap().is_irrational());
+        assert!(!time!(3, 16).unwrap().is_irrational());
+        assert_eq!(time!(4, 3).unwrap().measure_duration(), dur!(4, 3).unwrap());
+    }
+
+    #[test]
+    fn test_beat_hierarchy() {
+        let time = time!(12, 8).unwrap();
+        assert_eq!(time.strength_at(Duration::zero()), BeatStrength::Downbeat);
+        assert_eq!(time.strength_at(dur!(3, 8).unwrap()), BeatStrength::Weak);
+        assert_eq!(time.strength_at(dur!(6, 8).unwrap()), BeatStrength::Strong);
+        assert_eq!(time.strength_at(dur!(7, 8).unwrap()), BeatStrength::Division);
+        assert_eq!(time.strength_at(dur!(13, 16).unwrap()), BeatStrength::Offbeat);
+        assert_eq!(time.strength_at(dur!(12, 8).unwrap()), BeatStrength::Downbeat);
+
+        let time = time!("3+2+2/8").unwrap();
+        assert_eq!(time.beat_at(dur!(5, 8).unwrap()), (2, Duration::zero()));
+        assert_eq!(time.strength_at(dur!(3, 8).unwrap()), BeatStrength::Weak);
+        assert_eq!(time.strength_at(dur!(1, 8).unwrap()), BeatStrength::Division);
+
+        let time = time!(3, 4).unwrap();
+        assert_eq!(time.strength_at(Duration::half()), BeatStrength::Weak);
+        assert!(BeatStrength::Downbeat > BeatStrength::Strong);
+        assert!(BeatStrength::Division > BeatStrength::Offbeat);
+    }
+
+    #[test]
+    fn test_measures() {
+        let g4 = pnote!("G4").unwrap();
+        let eighth = Duration::eighth();
+        let events = vec![
+            Event::Note(g4, Duration::whole().dotted(1).unwrap()),
+            Event::Note(g4, eighth),
+            Event::Note(g4, eighth),
+            Event::Rest(eighth),
+            Event::Note(g4, eighth),
+            Event::Note(g4, eighth),
+            Event::Note(g4, eighth),
+            Event::Rest(Duration::whole()),
+        ];
+
+        let measures = time!(6, 8).unwrap().measures(&events);
+        assert_eq!(measures.len(), 5);
+        assert_eq!(measures.iter().map(|m| m.number()).collect::<Vec<_>>(), vec![1, 2, 3, 4, 5]);
+
+        // The dotted whole note fills two measures of 6/8 exactly
+        assert!(measures[0].events()[0].is_tied());
+        assert!(!measures[1].events()[0].is_tied());
+
+        let third = measures[2].events();
+        let beams = third.iter().map(|e| e.beam_group()).collect::<Vec<_>>();
+        assert_eq!(beams, vec![Some(0), Some(0), None, Some(1), Some(1), Some(1)]);
+        assert_eq!(third[5].offset(), dur!(5, 8).unwrap());
+
+        // Rests split across barlines are never tied
+        assert_eq!(measures[3].events()[0].event(), Event::Rest(dur!(6, 8).unwrap()));
+        assert!(!measures[3].events()[0].is_tied());
+        assert_eq!(measures[4].duration(), Duration::quarter());
+    }
+}
@@ -0,0 +1,51 @@
+use super::*;
+use std::{
+    fmt::{self, Debug, Display, Formatter},
+    str::FromStr,
+};
+
+impl FromStr for TimeSignature {
+    type Err = ResonataError;
+
+    /// Parses a time signature such as "3/4", "6/8", "2+2+3/8", "4/3",
+    /// or the symbols "C" for common time and "C|" for cut time.
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let s = s.trim();
+        match s {
+            "C" => return TimeSignature::new(4, 4),
+            "C|" => return TimeSignature::new(2, 2),
+            _ => {}
+        }
+
+        let invalid = || TimeSignatureError::InvalidTimeSignature(s.to_string());
+        let (numerator, denominator) = s.split_once('/').ok_or_else(invalid)?;
+        let denominator = denominator.trim().parse::<u32>().map_err(|_| invalid())?;
+        let groups = numerator
+            .split('+')
+            .map(|group| group.trim().parse::<u32>().map_err(|_| invalid()))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        match groups.len() {
+            1 => TimeSignature::new(groups[0], denominator),
+            _ => TimeSignature::additive(groups, denominator),
+        }
+    }
+}
+
+impl Display for TimeSignature {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let default = TimeSignature::new(self.numerator(), self.denominator);
+        if default.is_ok_and(|default| default.groups == self.groups) {
+            return write!(f, "{}/{}", self.numerator(), self.denominator);
+        }
+
+        let groups = self.groups.iter().map(|group| group.to_string()).collect::<Vec<_>>();
+        write!(f, "{}/{}", groups.join("+"), self.denominator)
+    }
+}
+
+impl Debug for TimeSignature {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}